@@ -1 +1,2 @@
-pub(crate) mod config;
\ No newline at end of file
+pub(crate) mod config;
+pub(crate) mod route;
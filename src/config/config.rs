@@ -1,12 +1,167 @@
 use serde::Deserialize;
+use log::error;
 use iso8601::DateTime;
 use crate::serde_integration::deserializers::*;
+use crate::sink::matrix::MatrixSink;
+use crate::sink::telegram::TelegramSink;
+use crate::sink::line::LineSink;
+use crate::sink::misskey::MisskeySink;
+use crate::sink::mastodon::MastodonSink;
+use crate::sink::mattermost::MattermostSink;
+use crate::sink::rocketchat::RocketChatSink;
+use crate::sink::email::EmailSink;
+use crate::sink::ntfy::NtfySink;
+use crate::sink::gotify::GotifySink;
+use crate::sink::pushover::PushoverSink;
+use crate::sink::http::HttpSink;
+use crate::sink::mqtt::MqttSink;
+use crate::sink::amqp::AmqpSink;
+use crate::sink::redis_stream::RedisStreamSink;
+use crate::sink::jsonl::JsonLinesSink;
+use crate::sink::exec::ExecSink;
+use crate::config::route::RouteConfig;
+use crate::sink::any::AnySink;
+use crate::source::Source;
+use crate::source::github::GitHubSource;
+use crate::source::gitlab::GitLabSource;
+use crate::source::gitea::GiteaSource;
+use crate::source::alertmanager::AlertmanagerSource;
+use crate::source::uptime_kuma::UptimeKumaSource;
+use crate::source::sentry::SentrySource;
+use crate::source::backlog::BacklogSource;
+use crate::source::jira::JiraSource;
+use crate::source::linear::LinearSource;
+use crate::source::trello::TrelloSource;
 
 #[derive(Deserialize)]
 pub(crate) struct Config {
     #[serde(deserialize_with = "deserialize_iso8601")]
     created_at: DateTime,
     pub(crate) discord_webhook: Option<String>,
+    pub(crate) matrix: Option<MatrixSink>,
+    pub(crate) telegram: Option<TelegramSink>,
+    pub(crate) line: Option<LineSink>,
+    pub(crate) misskey: Option<MisskeySink>,
+    pub(crate) mastodon: Option<MastodonSink>,
+    pub(crate) mattermost: Option<MattermostSink>,
+    pub(crate) rocketchat: Option<RocketChatSink>,
+    pub(crate) email: Option<EmailSink>,
+    pub(crate) ntfy: Option<NtfySink>,
+    pub(crate) gotify: Option<GotifySink>,
+    pub(crate) pushover: Option<PushoverSink>,
+    pub(crate) http: Option<HttpSink>,
+    pub(crate) mqtt: Option<MqttSink>,
+    pub(crate) amqp: Option<AmqpSink>,
+    pub(crate) redis_stream: Option<RedisStreamSink>,
+    pub(crate) jsonl: Option<JsonLinesSink>,
+    pub(crate) exec: Option<ExecSink>,
+    /// enables `/api/from/github/to/discord`, which delivers to `discord_webhook`
+    pub(crate) github: Option<GitHubSource>,
+    /// enables `/api/from/gitlab/to/discord`, which delivers to `discord_webhook`
+    pub(crate) gitlab: Option<GitLabSource>,
+    /// enables `/api/from/gitea/to/discord` for Gitea and Forgejo, which delivers to `discord_webhook`
+    pub(crate) gitea: Option<GiteaSource>,
+    /// enables `/api/from/alertmanager/to/discord`, which delivers to `discord_webhook`
+    pub(crate) alertmanager: Option<AlertmanagerSource>,
+    /// enables `/api/from/grafana/to/discord`, which delivers to `discord_webhook`
+    pub(crate) grafana: Option<AlertmanagerSource>,
+    /// enables `/api/from/uptime-kuma/to/discord`, which delivers to `discord_webhook`
+    pub(crate) uptime_kuma: Option<UptimeKumaSource>,
+    /// enables `/api/from/sentry/to/discord`, which delivers to `discord_webhook`
+    pub(crate) sentry: Option<SentrySource>,
+    /// enables `/api/from/backlog/to/discord`, which delivers to `discord_webhook`
+    pub(crate) backlog: Option<BacklogSource>,
+    /// enables `/api/from/jira/to/discord`, which delivers to `discord_webhook`
+    pub(crate) jira: Option<JiraSource>,
+    /// enables `/api/from/linear/to/discord`, which delivers to `discord_webhook`
+    pub(crate) linear: Option<LinearSource>,
+    /// enables `/api/from/trello/to/discord`, which delivers to `discord_webhook`
+    pub(crate) trello: Option<TrelloSource>,
+    /// fan-out routes, each of which delivers to multiple sinks
+    #[serde(default)]
+    pub(crate) routes: Vec<RouteConfig>,
     pub http_port: u16,
     pub https_port: u16,
 }
+
+impl Config {
+    /// whether any JSON Lines sink writes to stdout, so that logs must go elsewhere
+    pub(crate) fn writes_jsonl_to_stdout(&self) -> bool {
+        self.jsonl.as_ref().is_some_and(JsonLinesSink::writes_to_stdout)
+            || self.routes.iter()
+                .flat_map(|route| &route.sinks)
+                .any(|entry| matches!(&entry.sink, AnySink::Jsonl(sink) if sink.writes_to_stdout()))
+    }
+
+    /// adds the routes made from the per-source entries to `routes`
+    pub(crate) fn add_fixed_routes(&mut self) {
+        let sources = [
+            ("/api/from/github/to/discord", self.github.clone().map(Source::GitHub)),
+            ("/api/from/gitlab/to/discord", self.gitlab.clone().map(Source::GitLab)),
+            ("/api/from/gitea/to/discord", self.gitea.clone().map(Source::Gitea)),
+            ("/api/from/alertmanager/to/discord", self.alertmanager.clone().map(Source::Alertmanager)),
+            ("/api/from/grafana/to/discord", self.grafana.clone().map(Source::Grafana)),
+            ("/api/from/uptime-kuma/to/discord", self.uptime_kuma.clone().map(Source::UptimeKuma)),
+            ("/api/from/sentry/to/discord", self.sentry.clone().map(Source::Sentry)),
+            ("/api/from/backlog/to/discord", self.backlog.clone().map(Source::Backlog)),
+            ("/api/from/jira/to/discord", self.jira.clone().map(Source::Jira)),
+            ("/api/from/linear/to/discord", self.linear.clone().map(Source::Linear)),
+            ("/api/from/trello/to/discord", self.trello.clone().map(Source::Trello)),
+        ];
+        let configured = sources.into_iter().filter_map(|(path, source)| Some((path, source?)));
+        let Some(url) = &self.discord_webhook else {
+            for (path, _) in configured {
+                error!("{path} is not served, as it delivers to `discord_webhook` which is not set");
+            }
+            return
+        };
+        for (path, source) in configured {
+            self.routes.push(RouteConfig::fixed(path, source, AnySink::Discord { url: url.clone() }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(config: serde_json::Value) -> Config {
+        let mut config = config;
+        config["created_at"] = "2026-10-19T00:00:00Z".into();
+        config["http_port"] = 8080.into();
+        config["https_port"] = 8443.into();
+        serde_json::from_value(config).unwrap()
+    }
+
+    #[test]
+    fn jsonl_to_stdout_is_detected() {
+        assert!(!config(serde_json::json!({})).writes_jsonl_to_stdout());
+        assert!(config(serde_json::json!({ "jsonl": {} })).writes_jsonl_to_stdout());
+        assert!(!config(serde_json::json!({ "jsonl": { "path": "events.jsonl" } })).writes_jsonl_to_stdout());
+        let routes = config(serde_json::json!({
+            "routes": [{ "path": "/api/from/todoist/to/everywhere", "sinks": [{ "kind": "jsonl" }] }],
+        }));
+        assert!(routes.writes_jsonl_to_stdout());
+    }
+
+    #[test]
+    fn fixed_routes_deliver_to_discord_webhook() {
+        let mut config = config(serde_json::json!({
+            "discord_webhook": "https://discord.com/api/webhooks/1/token",
+            "github": {},
+            "alertmanager": {},
+        }));
+        config.add_fixed_routes();
+        let paths = config.routes.iter().map(|route| route.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, ["/api/from/github/to/discord", "/api/from/alertmanager/to/discord"]);
+        assert!(config.routes.iter().all(|route| matches!(&route.sinks[..], [entry] if entry.sink.kind() == "discord")));
+    }
+
+    #[test]
+    fn fixed_routes_need_discord_webhook() {
+        let mut config = config(serde_json::json!({ "github": {}, "alertmanager": {} }));
+        // reported as an error instead
+        config.add_fixed_routes();
+        assert!(config.routes.is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+use serde::Deserialize;
+use crate::filter::Filter;
+use crate::render::message::MessageTemplate;
+use crate::script::Script;
+use crate::plugin::Plugin;
+use crate::source::Source;
+use crate::sink::any::AnySink;
+use crate::sink::retry::RetryPolicy;
+
+/// a route fanning out from one receiving endpoint to several sinks
+#[derive(Deserialize)]
+pub(crate) struct RouteConfig {
+    /// e.g. `/api/from/todoist/to/everywhere`
+    pub(crate) path: String,
+    /// format of the incoming data, defaults to Todoist
+    #[serde(default)]
+    pub(crate) source: Source,
+    #[serde(default)]
+    pub(crate) policy: DeliveryPolicy,
+    /// events which do not match are acknowledged but not delivered to any sink
+    #[serde(default)]
+    pub(crate) filter: Option<Filter>,
+    /// overrides the built-in message template for every sink on this route
+    #[serde(default)]
+    pub(crate) template: Option<MessageTemplate>,
+    /// runs after `filter`, and takes precedence over templates
+    #[serde(default)]
+    pub(crate) script: Option<Script>,
+    /// runs after `script`, and its result replaces that of `script`
+    #[serde(default)]
+    pub(crate) plugin: Option<Plugin>,
+    pub(crate) sinks: Vec<SinkEntry>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SinkEntry {
+    #[serde(flatten)]
+    pub(crate) sink: AnySink,
+    #[serde(default)]
+    pub(crate) retry: RetryPolicy,
+    /// events which do not match are not delivered to this sink, and it is not counted as failure
+    #[serde(default)]
+    pub(crate) filter: Option<Filter>,
+    /// overrides the template of the route
+    #[serde(default)]
+    pub(crate) template: Option<MessageTemplate>,
+}
+
+impl RouteConfig {
+    /// a route with a single sink, made from a per-source entry of the config file
+    pub(crate) fn fixed(path: &str, source: Source, sink: AnySink) -> Self {
+        Self {
+            path: path.to_string(),
+            source,
+            policy: DeliveryPolicy::default(),
+            filter: None,
+            template: None,
+            script: None,
+            plugin: None,
+            sinks: vec![SinkEntry { sink, retry: RetryPolicy::default(), filter: None, template: None }],
+        }
+    }
+}
+
+/// which delivery result decides the response to the source
+#[derive(Deserialize, Copy, Clone, Eq, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum DeliveryPolicy {
+    /// succeeds only if every sink succeeded
+    #[default]
+    All,
+    /// succeeds as soon as one of sinks succeeded
+    Any,
+    /// responds immediately without waiting for any sink
+    FireAndForget,
+}
@@ -0,0 +1,80 @@
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use minijinja::{Environment, Expression};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
+use crate::payload::event::NormalizedEvent;
+
+/// environment the expressions are compiled in. The compiled expressions keep referring to it, so like the config it lives as long as the process.
+static ENVIRONMENT: Lazy<Environment<'static>> = Lazy::new(Environment::new);
+
+/// a condition evaluated against the normalized event. The syntax is the same as Jinja2 expressions.
+///
+/// e.g. `event == "item:completed" and project == "2203306141" and priority == "P1" and "private" not in labels`
+///
+/// see [`NormalizedEvent::template_context`] for the available variables.
+#[derive(Clone)]
+pub(crate) struct Filter {
+    source: String,
+    expression: Arc<Expression<'static, 'static>>,
+}
+
+impl Filter {
+    pub(crate) fn matches(&self, event: &NormalizedEvent) -> anyhow::Result<bool> {
+        let result = self.expression.eval(event.template_context())?;
+        Ok(result.is_true())
+    }
+}
+
+impl<'de> Deserialize<'de> for Filter {
+    /// compiled once when the config is loaded, so syntax errors are detected then
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        let expression = ENVIRONMENT
+            .compile_expression_owned(source.clone())
+            .map_err(|e| D::Error::custom(format!("invalid filter `{source}`: {e}")))?;
+        Ok(Self { source, expression: Arc::new(expression) })
+    }
+}
+
+impl Display for Filter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(expression: &str) -> Filter {
+        serde_json::from_value(serde_json::Value::String(expression.to_string())).unwrap()
+    }
+
+    #[test]
+    fn expression_is_evaluated_against_event() {
+        let event = NormalizedEvent::example();
+        assert!(filter(r#"event == "item:completed" and priority == "P1""#).matches(&event).unwrap());
+        assert!(filter(r#""errand" in labels and body is none"#).matches(&event).unwrap());
+        assert!(!filter(r#""private" in labels"#).matches(&event).unwrap());
+    }
+
+    #[test]
+    fn compiled_filter_is_reused() {
+        let filter = filter("project == 'Inbox'");
+        let cloned = filter.clone();
+        let event = NormalizedEvent::example();
+        for _ in 0..3 {
+            assert!(filter.matches(&event).unwrap());
+            assert!(cloned.matches(&event).unwrap());
+        }
+        assert_eq!(cloned.to_string(), "project == 'Inbox'");
+    }
+
+    #[test]
+    fn syntax_error_is_rejected_on_load() {
+        let error = serde_json::from_value::<Filter>(serde_json::Value::String("event ==".to_string())).err().unwrap();
+        assert!(error.to_string().starts_with("invalid filter `event ==`"));
+    }
+}
@@ -1,3 +1,4 @@
 pub(crate) mod incoming;
 pub(crate) mod outgoing;
 pub(crate) mod handler;
+pub(crate) mod decode;
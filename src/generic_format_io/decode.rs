@@ -0,0 +1,279 @@
+use std::fmt::{Display, Formatter};
+use actix_web::{HttpRequest, HttpResponse};
+use actix_web::error::PayloadError;
+use actix_web::web::Bytes;
+use actix_multipart::Multipart;
+use futures_util::{stream, StreamExt};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde_json::{Map, Value};
+
+/// the received data turned into a common intermediate representation (a JSON value) according to its Content-Type.
+/// later conversions work on this value regardless of the original format.
+///
+/// * `application/json`, `*/*+json`: as is
+/// * `application/x-www-form-urlencoded`: an object of names and values. A name given more than once becomes an array.
+///   If the only name is `payload` and its value is JSON (e.g. GitHub), that value is used.
+/// * `multipart/form-data`: same as the form. Parts of `application/json` are read as JSON.
+/// * `application/xml`, `text/xml`, `*/*+xml`: an object keyed by the element names. Attributes become `@name`, and text next to child elements becomes `#text`.
+/// * `text/plain`: a string
+pub(crate) async fn decode(request: &HttpRequest, body: Bytes) -> Result<Value, DecodeError> {
+    let Some(content_type) = request.headers().get("content-type") else {
+        return Err(DecodeError::Missing);
+    };
+    let mime: mime::Mime = content_type.to_str().ok()
+        .and_then(|content_type| content_type.parse().ok())
+        .ok_or_else(|| DecodeError::Unsupported(format!("{content_type:?}")))?;
+
+    match (mime.type_(), mime.subtype(), mime.suffix()) {
+        (mime::APPLICATION, mime::JSON, _) | (_, _, Some(mime::JSON)) => {
+            serde_json::from_slice(&body).map_err(|e| DecodeError::Invalid(e.to_string()))
+        }
+        (mime::APPLICATION, mime::WWW_FORM_URLENCODED, _) => Ok(unwrap_payload(form(utf8(&body)?))),
+        (mime::MULTIPART, mime::FORM_DATA, _) => multipart(request, body).await.map(unwrap_payload),
+        (mime::APPLICATION | mime::TEXT, mime::XML, _) | (_, _, Some(mime::XML)) => xml(utf8(&body)?),
+        (mime::TEXT, mime::PLAIN, _) => Ok(Value::String(utf8(&body)?.to_string())),
+        _ => Err(DecodeError::Unsupported(mime.essence_str().to_string())),
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum DecodeError {
+    Missing,
+    Unsupported(String),
+    Invalid(String),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing => f.write_str("Content-Type header must be included"),
+            Self::Unsupported(content_type) => write!(f, "unsupported Content-Type: {content_type}"),
+            Self::Invalid(detail) => f.write_str(detail),
+        }
+    }
+}
+
+impl From<DecodeError> for HttpResponse {
+    fn from(error: DecodeError) -> Self {
+        let detail = error.to_string();
+        match error {
+            DecodeError::Missing | DecodeError::Invalid(_) => Self::BadRequest().body(detail),
+            DecodeError::Unsupported(_) => Self::UnsupportedMediaType().body(detail),
+        }
+    }
+}
+
+fn utf8(body: &[u8]) -> Result<&str, DecodeError> {
+    std::str::from_utf8(body).map_err(|e| DecodeError::Invalid(e.to_string()))
+}
+
+/// turns the value into an array when the same name appears a second time
+fn insert(object: &mut Map<String, Value>, key: String, value: Value) {
+    match object.get_mut(&key) {
+        None => {
+            object.insert(key, value);
+        }
+        Some(Value::Array(values)) => values.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+    }
+}
+
+fn form(body: &str) -> Value {
+    // qstring does not treat `+` as a space, while HTML forms do
+    let mut object = Map::new();
+    for (key, value) in qstring::QString::from(body.replace('+', "%20").as_str()).into_pairs() {
+        insert(&mut object, key, Value::String(value));
+    }
+    Value::Object(object)
+}
+
+fn unwrap_payload(value: Value) -> Value {
+    let Value::Object(object) = &value else {
+        return value;
+    };
+    match object.get("payload") {
+        Some(Value::String(payload)) if object.len() == 1 => serde_json::from_str(payload).unwrap_or(value),
+        _ => value,
+    }
+}
+
+async fn multipart(request: &HttpRequest, body: Bytes) -> Result<Value, DecodeError> {
+    let mut parts = Multipart::new(request.headers(), stream::once(async { Ok::<_, PayloadError>(body) }));
+    let mut object = Map::new();
+    while let Some(part) = parts.next().await {
+        let mut part = part.map_err(|e| DecodeError::Invalid(e.to_string()))?;
+        let name = part.name().unwrap_or_default().to_string();
+        let is_json = part.content_type().is_some_and(|mime| mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON));
+        let mut content = Vec::new();
+        while let Some(chunk) = part.next().await {
+            content.extend_from_slice(&chunk.map_err(|e| DecodeError::Invalid(e.to_string()))?);
+        }
+        let value = if is_json {
+            serde_json::from_slice(&content).map_err(|e| DecodeError::Invalid(format!("{name}: {e}")))?
+        } else {
+            Value::String(utf8(&content)?.to_string())
+        };
+        insert(&mut object, name, value);
+    }
+    Ok(Value::Object(object))
+}
+
+fn xml(body: &str) -> Result<Value, DecodeError> {
+    /// elements being read
+    struct Element {
+        name: String,
+        children: Map<String, Value>,
+        text: String,
+    }
+
+    impl Element {
+        fn into_value(self) -> Value {
+            let text = self.text.trim();
+            match (self.children.is_empty(), text.is_empty()) {
+                (true, true) => Value::Null,
+                (true, false) => Value::String(text.to_string()),
+                (false, _) => {
+                    let mut children = self.children;
+                    if !text.is_empty() {
+                        children.insert("#text".to_string(), Value::String(text.to_string()));
+                    }
+                    Value::Object(children)
+                }
+            }
+        }
+    }
+
+    let invalid = |e: &dyn std::error::Error| DecodeError::Invalid(format!("invalid XML: {e}"));
+    let open = |start: &quick_xml::events::BytesStart| -> Result<Element, DecodeError> {
+        let mut children = Map::new();
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(|e| invalid(&e))?;
+            let key = format!("@{}", String::from_utf8_lossy(attribute.key.as_ref()));
+            let value = attribute.unescape_value().map_err(|e| invalid(&e))?;
+            children.insert(key, Value::String(value.into_owned()));
+        }
+        Ok(Element { name: String::from_utf8_lossy(start.name().as_ref()).into_owned(), children, text: String::new() })
+    };
+
+    let mut reader = Reader::from_str(body);
+    let mut root = Map::new();
+    let mut stack: Vec<Element> = Vec::new();
+    loop {
+        let closed = match reader.read_event().map_err(|e| invalid(&e))? {
+            Event::Start(start) => {
+                stack.push(open(&start)?);
+                None
+            }
+            Event::Empty(start) => Some(open(&start)?),
+            Event::End(_) => stack.pop(),
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text.unescape().map_err(|e| invalid(&e))?);
+                }
+                None
+            }
+            Event::CData(data) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&String::from_utf8_lossy(&data));
+                }
+                None
+            }
+            Event::Eof => match stack.last() {
+                None => break,
+                Some(element) => return Err(DecodeError::Invalid(format!("invalid XML: <{}> is not closed", element.name))),
+            },
+            _ => None,
+        };
+        if let Some(element) = closed {
+            let parent = stack.last_mut().map_or(&mut root, |parent| &mut parent.children);
+            let name = element.name.clone();
+            insert(parent, name, element.into_value());
+        }
+    }
+    Ok(Value::Object(root))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use serde_json::json;
+    use super::*;
+
+    async fn decode_as(content_type: &str, body: &'static str) -> Result<Value, DecodeError> {
+        let request = TestRequest::default().insert_header(("content-type", content_type)).to_http_request();
+        decode(&request, Bytes::from_static(body.as_bytes())).await
+    }
+
+    #[actix_web::test]
+    async fn json_is_kept() {
+        let value = decode_as("application/vnd.api+json", r#"{"title":"Buy milk"}"#).await.unwrap();
+        assert_eq!(value, json!({ "title": "Buy milk" }));
+    }
+
+    #[actix_web::test]
+    async fn form_collects_repeated_keys() {
+        let value = decode_as("application/x-www-form-urlencoded", "title=Buy+milk%2Bbread&label=errand&label=home&label=shop").await.unwrap();
+        assert_eq!(value, json!({ "title": "Buy milk+bread", "label": ["errand", "home", "shop"] }));
+    }
+
+    #[actix_web::test]
+    async fn form_payload_is_unwrapped() {
+        let value = decode_as("application/x-www-form-urlencoded", "payload=%7B%22action%22%3A%22opened%22%7D").await.unwrap();
+        assert_eq!(value, json!({ "action": "opened" }));
+    }
+
+    #[actix_web::test]
+    async fn multipart_reads_json_parts() {
+        let body = "--b\r\n\
+            content-disposition: form-data; name=\"title\"\r\n\r\n\
+            Buy milk\r\n\
+            --b\r\n\
+            content-disposition: form-data; name=\"meta\"\r\n\
+            content-type: application/json\r\n\r\n\
+            {\"priority\":1}\r\n\
+            --b\r\n\
+            content-disposition: form-data; name=\"title\"\r\n\r\n\
+            Buy bread\r\n\
+            --b--\r\n";
+        let value = decode_as("multipart/form-data; boundary=b", body).await.unwrap();
+        assert_eq!(value, json!({ "title": ["Buy milk", "Buy bread"], "meta": { "priority": 1 } }));
+    }
+
+    #[actix_web::test]
+    async fn xml_keeps_attributes_and_text() {
+        let body = r#"<task id="1"><title>Buy &amp; milk</title><label>errand</label><label>home</label><note lang="en">two bottles</note><done/></task>"#;
+        let value = decode_as("application/xml", body).await.unwrap();
+        assert_eq!(value, json!({
+            "task": {
+                "@id": "1",
+                "title": "Buy & milk",
+                "label": ["errand", "home"],
+                "note": { "@lang": "en", "#text": "two bottles" },
+                "done": null,
+            },
+        }));
+    }
+
+    #[actix_web::test]
+    async fn unclosed_xml_is_an_error() {
+        let error = decode_as("text/xml", "<task><title>Buy milk</title>").await.unwrap_err();
+        assert!(matches!(&error, DecodeError::Invalid(detail) if detail.contains("<task>")), "{error}");
+    }
+
+    #[actix_web::test]
+    async fn plain_text_is_a_string() {
+        let value = decode_as("text/plain; charset=utf-8", "Buy milk\n").await.unwrap();
+        assert_eq!(value, json!("Buy milk\n"));
+    }
+
+    #[actix_web::test]
+    async fn content_type_is_required() {
+        let request = TestRequest::default().to_http_request();
+        assert!(matches!(decode(&request, Bytes::new()).await, Err(DecodeError::Missing)));
+        assert!(matches!(decode_as("image/png", "").await, Err(DecodeError::Unsupported(_))));
+    }
+}
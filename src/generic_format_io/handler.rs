@@ -1,12 +1,20 @@
 use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use std::rc::Rc;
 use std::sync::Arc;
 use std::marker::PhantomData;
-use actix_web::{error, HttpRequest, HttpResponse, Responder};
-use actix_web::web::{Json, Query};
-use anyhow::anyhow;
+use actix_web::{HttpRequest, HttpResponse};
+use actix_web::web::{Bytes, Query};
 use log::{error, trace};
 use crate::{ApiKey, GenericOutgoingSerializer, PhantomLifetime};
 use crate::generic_format_io::incoming::GenericIncomingDeserializer;
+use crate::generic_format_io::decode::decode;
+use crate::sink::Sink;
+use crate::config::route::{DeliveryPolicy, RouteConfig};
+use crate::filter::Filter;
+use crate::sink::any::MessageSource;
+use crate::sink::matrix::MatrixSink;
+use tokio::task::JoinSet;
 
 pub struct GenericHandler<'de, D: Deserialize<'de>, S: Serialize, F: 'static + FnOnce(D) -> S, TD: FnOnce(&'static str) -> D, TS: FnOnce(S) -> &'static str> {
     incoming_deserializer: GenericIncomingDeserializer<'de, D, TD>,
@@ -28,18 +36,18 @@ impl <'de, D: Deserialize<'de>, S: Serialize, F: 'static + FnOnce(D) -> S, TD: F
     }
 }
 
-pub struct JsonHandler<'de, D: Deserialize<'de>, S: Serialize, F: 'static + FnOnce(D) -> S + ?Sized> {
-    to: String,
+pub struct JsonHandler<'de, D: Deserialize<'de>, S: Serialize, F: 'static + FnOnce(D) -> S + ?Sized, K: Sink<S>> {
+    sink: K,
     f: Arc<F>,
     __phantom_de: PhantomLifetime<'de>,
     __phantom_s: PhantomData<S>,
     __phantom_d: PhantomData<D>
 }
 
-impl <'de, D: Deserialize<'de>, S: Serialize, F: 'static + FnOnce(D) -> S> JsonHandler<'de, D, S, F> {
-    pub(crate) fn new(to: String, f: F) -> Self {
-        JsonHandler::<'de, D, S, F> {
-            to, f: Arc::new(f),
+impl <'de, D: Deserialize<'de>, S: Serialize, F: 'static + FnOnce(D) -> S, K: Sink<S>> JsonHandler<'de, D, S, F, K> {
+    pub(crate) fn new(sink: K, f: F) -> Self {
+        JsonHandler::<'de, D, S, F, K> {
+            sink, f: Arc::new(f),
             __phantom_d: PhantomData,
             __phantom_s: PhantomData,
             __phantom_de: PhantomData
@@ -47,47 +55,207 @@ impl <'de, D: Deserialize<'de>, S: Serialize, F: 'static + FnOnce(D) -> S> JsonH
     }
 }
 
-// TODO: input type can be inferred by Content-Type
-pub async fn handle<'de, D: Deserialize<'de>, S: Serialize, F: 'static + Copy + FnOnce(D) -> S>(
-    handler: Arc<JsonHandler<'de, D, S, F>>,
-    Json(incoming_data): actix_web::web::Json<D>,
+pub async fn handle<D: DeserializeOwned, S: Serialize, F: 'static + Copy + FnOnce(D) -> S, K: Sink<S>>(
+    handler: Arc<JsonHandler<'_, D, S, F, K>>,
+    request: HttpRequest,
+    body: Bytes,
     Query(api_key): actix_web::web::Query<ApiKey>,
-) -> impl Responder {
+) -> HttpResponse {
     // TODO: api_key=something in query string
     trace!("enter");
+    let incoming_data = match decode(&request, body).await.map(D::deserialize) {
+        Ok(Ok(incoming_data)) => incoming_data,
+        Ok(Err(e)) => return HttpResponse::UnprocessableEntity().body(e.to_string()),
+        Err(e) => return e.into(),
+    };
     let client = reqwest::Client::new();
     let outgoing_data: &S = &(handler.f)(incoming_data);
-    let result = client
-        .post(&handler.to)
-        .json(outgoing_data)
-        .send()
-        .await
-        .map(|_| ())
-        .map_err(|x| anyhow!(x));
+    let result = handler.sink.deliver(&client, outgoing_data).await;
     match result {
         Ok(_) => {
-            HttpResponse::NoContent()
+            HttpResponse::NoContent().finish()
         }
         Err(e) => {
             error!("ERROR!!!: {:?}", e);
-            HttpResponse::NotModified()
+            HttpResponse::NotModified().finish()
         }
     }
 }
 
-pub fn json_error_handler(err: error::JsonPayloadError, _req: &HttpRequest) -> error::Error {
-    use actix_web::error::JsonPayloadError;
-    use actix_web::HttpResponse;
+/// delivers a single piece of received data to all sinks of the route concurrently.
+/// each sink is retried on its own, so a failing sink never holds back the others.
+pub async fn handle_fan_out(
+    route: &'static RouteConfig,
+    request: HttpRequest,
+    body: Bytes,
+) -> HttpResponse {
+    trace!("enter");
+    let raw = match decode(&request, body.clone()).await {
+        Ok(raw) => raw,
+        Err(e) => return e.into(),
+    };
+    // the script needs the incoming data as is
+    let incoming = match route.source.parse(&request, &body, raw.clone()) {
+        Ok(incoming) => Rc::new(incoming),
+        Err(e) => {
+            error!("{}: {e}", route.path);
+            return e.into();
+        }
+    };
+    // built once, so that every sink sees the same event including `received_at`
+    let event = Rc::new(incoming.event());
+    let evaluate = |filter: &Option<Filter>, target: &str| filter.as_ref().map_or(Ok(true), |filter| {
+        let matched = filter.matches(&event);
+        trace!("{}: filter `{filter}` for {target} evaluated to {matched:?}", route.path);
+        matched
+    });
+    match evaluate(&route.filter, "route") {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NoContent().finish(),
+        Err(e) => {
+            error!("{}: failed to evaluate filter: {e:?}", route.path);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    // evaluated lazily, so that the plugin does not run for events dropped by the script
+    let transforms = route.script.iter().map(|script| ("script", script.run(&raw, &event)))
+        .chain(route.plugin.iter().map(|plugin| ("plugin", plugin.run(&raw, &event))));
+    let mut transformed = None;
+    for (kind, result) in transforms {
+        match result {
+            Ok(Some(message)) => transformed = Some(message),
+            Ok(None) => {
+                trace!("{}: {kind} dropped the event", route.path);
+                return HttpResponse::NoContent().finish();
+            }
+            Err(e) => {
+                error!("{}: {kind}: {e:?}", route.path);
+                return HttpResponse::InternalServerError().finish();
+            }
+        }
+    }
+    // shared between deliveries on the same thread
+    let transformed = Rc::new(transformed);
 
-    let detail = err.to_string();
-    let resp = match &err {
-        JsonPayloadError::ContentType => {
-            HttpResponse::UnsupportedMediaType().body(detail)
+    let client = reqwest::Client::new();
+    let mut deliveries = JoinSet::new();
+    for (index, entry) in route.sinks.iter().enumerate() {
+        match evaluate(&entry.filter, &format!("sink #{index}")) {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                error!("{}: failed to evaluate filter of sink #{index}: {e:?}", route.path);
+                deliveries.spawn_local(async { false });
+                continue;
+            }
         }
-        JsonPayloadError::Deserialize(json_err) if json_err.is_data() => {
-            HttpResponse::UnprocessableEntity().body(detail)
+        let client = client.clone();
+        let incoming = Rc::clone(&incoming);
+        let event = Rc::clone(&event);
+        let transformed = Rc::clone(&transformed);
+        deliveries.spawn_local(async move {
+            let message = match (transformed.as_ref(), entry.template.as_ref().or(route.template.as_ref())) {
+                (Some(transformed), _) => MessageSource::Transformed(transformed),
+                (None, Some(template)) => MessageSource::Template(template),
+                (None, None) => MessageSource::BuiltIn,
+            };
+            // kept on retries, so that the homeserver can drop a duplicate
+            let transaction_id = MatrixSink::next_transaction_id();
+            let result = entry.retry.run(|| entry.sink.deliver(&client, &incoming, &event, message, &transaction_id)).await;
+            if let Err(e) = &result {
+                error!("{}: sink #{index} ({}) failed: {e:?}", route.path, entry.sink.kind());
+            }
+            result.is_ok()
+        });
+    }
+
+    respond(route.policy, deliveries).await
+}
+
+/// Decides the response to the sender from the results of the deliveries, by `policy`.
+async fn respond(policy: DeliveryPolicy, mut deliveries: JoinSet<bool>) -> HttpResponse {
+    match policy {
+        DeliveryPolicy::FireAndForget => {
+            deliveries.detach_all();
+            HttpResponse::Accepted().finish()
         }
-        _ => HttpResponse::BadRequest().body(detail),
-    };
-    error::InternalError::from_response(err, resp).into()
+        // every sink is filtered out, so nothing is expected to succeed
+        DeliveryPolicy::Any if deliveries.is_empty() => HttpResponse::NoContent().finish(),
+        DeliveryPolicy::Any => {
+            while let Some(delivered) = deliveries.join_next().await {
+                if delivered.unwrap_or(false) {
+                    // the rest keep running in background
+                    deliveries.detach_all();
+                    return HttpResponse::NoContent().finish();
+                }
+            }
+            HttpResponse::BadGateway().finish()
+        }
+        DeliveryPolicy::All => {
+            let mut all_delivered = true;
+            while let Some(delivered) = deliveries.join_next().await {
+                all_delivered &= delivered.unwrap_or(false);
+            }
+            if all_delivered {
+                HttpResponse::NoContent().finish()
+            } else {
+                HttpResponse::BadGateway().finish()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::pending;
+    use std::time::Duration;
+    use actix_web::http::StatusCode;
+    use super::*;
+
+    /// stands in for the deliveries of sinks, each finishing after the given milliseconds; `None` never finishes
+    fn deliveries(results: &[(u64, Option<bool>)]) -> JoinSet<bool> {
+        let mut deliveries = JoinSet::new();
+        for &(millis, result) in results {
+            deliveries.spawn_local(async move {
+                tokio::time::sleep(Duration::from_millis(millis)).await;
+                match result {
+                    Some(delivered) => delivered,
+                    None => pending().await,
+                }
+            });
+        }
+        deliveries
+    }
+
+    async fn status(policy: DeliveryPolicy, results: &[(u64, Option<bool>)]) -> StatusCode {
+        let response = tokio::time::timeout(Duration::from_secs(5), respond(policy, deliveries(results))).await;
+        response.expect("the response does not wait for unfinished sinks").status()
+    }
+
+    #[actix_web::test]
+    async fn all_needs_every_sink() {
+        assert_eq!(status(DeliveryPolicy::All, &[(0, Some(true)), (10, Some(true))]).await, StatusCode::NO_CONTENT);
+        assert_eq!(status(DeliveryPolicy::All, &[(0, Some(true)), (10, Some(false))]).await, StatusCode::BAD_GATEWAY);
+        assert_eq!(status(DeliveryPolicy::All, &[]).await, StatusCode::NO_CONTENT);
+    }
+
+    #[actix_web::test]
+    async fn any_returns_on_the_first_success() {
+        assert_eq!(status(DeliveryPolicy::Any, &[(0, None), (0, Some(false)), (10, Some(true))]).await, StatusCode::NO_CONTENT);
+        assert_eq!(status(DeliveryPolicy::Any, &[(0, Some(false)), (10, Some(false))]).await, StatusCode::BAD_GATEWAY);
+        assert_eq!(status(DeliveryPolicy::Any, &[]).await, StatusCode::NO_CONTENT);
+    }
+
+    #[actix_web::test]
+    async fn fire_and_forget_does_not_wait() {
+        assert_eq!(status(DeliveryPolicy::FireAndForget, &[(0, None), (0, Some(false))]).await, StatusCode::ACCEPTED);
+    }
+
+    #[actix_web::test]
+    async fn panicked_sink_is_a_failure() {
+        let mut deliveries = JoinSet::new();
+        deliveries.spawn_local(async { panic!("sink panicked") });
+        assert_eq!(respond(DeliveryPolicy::All, deliveries).await.status(), StatusCode::BAD_GATEWAY);
+    }
 }
@@ -7,16 +7,22 @@ mod call;
 mod config;
 mod serde_integration;
 mod generic_format_io;
+mod sink;
+mod render;
+mod filter;
+mod script;
+mod plugin;
+mod source;
 
 use std::any::Any;
 use std::borrow::Borrow;
+use std::borrow::Cow;
 use std::fs::File;
 use std::io::BufReader;
 use std::marker::PhantomData;
 use once_cell::sync::OnceCell;
 use std::sync::Arc;
-use actix_web::{App, guard, HttpResponse, HttpServer, Responder, web};
-use actix_web::web::JsonConfig;
+use actix_web::{App, HttpResponse, HttpServer, web};
 use anyhow::Context;
 use log::{info, trace};
 use serde::{Deserialize, Deserializer, Serialize};
@@ -25,16 +31,26 @@ use rustls_pemfile::{certs, pkcs8_private_keys};
 use generic_format_io::handler;
 use generic_format_io::handler::JsonHandler;
 use crate::generic_format_io::outgoing::GenericOutgoingSerializer;
-use crate::payload::todoist::{TodoistEvent, TodoistPayload};
-use crate::payload::discord::{DiscordWebhookPayload, Embed, EmbedCollection, EmbedField, EmbedFields};
+use crate::payload::todoist::{TodoistEvent, TodoistPayload, TodoistPriority};
+use crate::payload::discord::DiscordWebhookPayload;
 use crate::call::api_key::ApiKey;
 use crate::config::config::Config;
+use crate::payload::matrix::MatrixRoomMessage;
+use crate::payload::line::{FlexBubble, FlexMessage, UriAction};
+use crate::payload::push::PushNotification;
+use crate::payload::event::NormalizedEvent;
+use crate::render::message::MessageTemplate;
+use crate::sink::Sink;
+use crate::sink::discord::DiscordWebhook;
+use crate::sink::jsonl::{Record, RecordedPayload};
 
 type PhantomLifetime<'a> = PhantomData<&'a ()>;
 
-fn setup_logger() -> Result<(), fern::InitError> {
+/// `log_to_stderr` keeps stdout for the output of a JSON Lines sink
+fn setup_logger(log_to_stderr: bool) -> Result<(), fern::InitError> {
     use fern::colors::*;
     let mut colors = ColoredLevelConfig::new();
+    let console: fern::Output = if log_to_stderr { std::io::stderr().into() } else { std::io::stdout().into() };
 
     fern::Dispatch::new()
         .format(move |out, message, record| {
@@ -47,49 +63,222 @@ fn setup_logger() -> Result<(), fern::InitError> {
             ))
         })
         .level(log::LevelFilter::Trace)
-        .chain(std::io::stdout())
+        .chain(console)
         .chain(fern::log_file("output.log")?)
         .apply()?;
     Ok(())
 }
 
 fn todoist_to_webhook(incoming_data: TodoistPayload) -> DiscordWebhookPayload {
-    let username = Some("Todoist".to_string());
-    let avatar_url = Some("https://upload.wikimedia.org/wikipedia/commons/thumb/e/e1/Cib-todoist_%28CoreUI_Icons_v1.0.0%29.svg/240px-Cib-todoist_%28CoreUI_Icons_v1.0.0%29.svg.png".to_string());
-    let content = "abx".to_string();
-    let tts = false;
+    let event = todoist_to_event(incoming_data);
+    MessageTemplate::todoist_default(&event.event)
+        .render(&event)
+        .expect("built-in templates must be valid")
+}
+
+fn todoist_to_line(incoming_data: TodoistPayload) -> FlexMessage {
+    let event_name = incoming_data.event.name();
+    // the webhook carries only the ID of the project, which means nothing to readers, so no row shows the project
+    let (title, accent, rows, link) = match incoming_data.event {
+        TodoistEvent::ItemAdded(item)
+        | TodoistEvent::ItemRemoved(item)
+        | TodoistEvent::ItemDeleted(item)
+        | TodoistEvent::ItemCompleted(item)
+        | TodoistEvent::ItemUncompleted(item) => {
+            let rows = vec![
+                ("期限".to_string(), item.due.map_or_else(|| "なし".to_string(), |due| due.date)),
+                ("優先度".to_string(), item.priority.to_string()),
+            ];
+            (item.content, Some(item.priority.color()), rows, Some(item.id.url()))
+        }
+        TodoistEvent::NoteAdded(note)
+        | TodoistEvent::NoteUpdated(note)
+        | TodoistEvent::NoteDeleted(note) => {
+            let rows = vec![("コメント".to_string(), note.content)];
+            (event_name.to_string(), None, rows, Some(note.item_id.url()))
+        }
+        _ => (event_name.to_string(), None, vec![], None)
+    };
+
+    FlexMessage {
+        alt_text: format!("[Todoist] {title}").chars().take(400).collect(),
+        contents: FlexBubble::key_value(
+            title,
+            accent,
+            rows,
+            link.map(|uri| UriAction { label: "Todoistで開く".to_string(), uri })
+        ),
+    }
+}
+
+fn todoist_to_push(incoming_data: TodoistPayload) -> PushNotification {
+    let event_name = incoming_data.event.name();
+    match incoming_data.event {
+        TodoistEvent::ReminderFired(reminder) => {
+            PushNotification {
+                title: "Reminder".to_string(),
+                message: reminder.due.map_or_else(|| "A task is due".to_string(), |due| format!("Due: {}", due.string)),
+                priority: None,
+                click: Some(reminder.item_id.url()),
+                tags: vec!["alarm_clock".to_string()],
+            }
+        }
+        TodoistEvent::ItemAdded(item)
+        | TodoistEvent::ItemRemoved(item)
+        | TodoistEvent::ItemDeleted(item)
+        | TodoistEvent::ItemCompleted(item)
+        | TodoistEvent::ItemUncompleted(item) => {
+            PushNotification {
+                title: event_name.to_string(),
+                message: item.content,
+                priority: Some(item.priority),
+                click: Some(item.id.url()),
+                tags: vec![],
+            }
+        }
+        TodoistEvent::NoteAdded(note)
+        | TodoistEvent::NoteUpdated(note)
+        | TodoistEvent::NoteDeleted(note) => {
+            PushNotification {
+                title: event_name.to_string(),
+                message: note.content,
+                priority: None,
+                click: Some(note.item_id.url()),
+                tags: vec!["speech_balloon".to_string()],
+            }
+        }
+        _ => {
+            PushNotification {
+                title: event_name.to_string(),
+                message: event_name.to_string(),
+                priority: None,
+                click: None,
+                tags: vec![],
+            }
+        }
+    }
+}
+
+/// turns an event of a source without a dedicated conversion into a LINE message
+fn event_to_line(event: &NormalizedEvent) -> FlexMessage {
+    let mut rows = vec![];
+    if let Some(body) = &event.body {
+        rows.push(("内容".to_string(), body.clone()));
+    }
+    if let Some(project) = &event.project {
+        rows.push(("プロジェクト".to_string(), project.clone()));
+    }
+    if !event.labels.is_empty() {
+        rows.push(("ラベル".to_string(), event.labels.join(", ")));
+    }
+
+    FlexMessage {
+        alt_text: format!("[{}] {}", event.source, event.title).chars().take(400).collect(),
+        contents: FlexBubble::key_value(
+            event.title.clone(),
+            event.priority.map(TodoistPriority::color),
+            rows,
+            event.url.clone().map(|uri| UriAction { label: "開く".to_string(), uri })
+        ),
+    }
+}
+
+/// turns an event of a source without a dedicated conversion into a push notification
+fn event_to_push(event: &NormalizedEvent) -> PushNotification {
+    PushNotification {
+        title: event.title.clone(),
+        message: event.body.clone().unwrap_or_else(|| event.event.clone()),
+        priority: event.priority,
+        click: event.url.clone(),
+        tags: event.labels.clone(),
+    }
+}
+
+fn todoist_to_event(incoming_data: TodoistPayload) -> NormalizedEvent {
+    let event = incoming_data.event.name().to_string();
+    let initiator = Some(incoming_data.initiator.full_name);
+    let received_at = chrono::Utc::now().to_rfc3339();
     match incoming_data.event {
-        TodoistEvent::NoteAdded(note) => {
-            DiscordWebhookPayload {
-                content,
-                username,
-                avatar_url,
-                tts,
-                embeds: EmbedCollection(vec![
-                    Embed {
-                        title: Some("Note added".to_string()),
-                        description: None,
-                        url: Some("test".to_string()),
-                        color: Some(0xFF34eb5e),
-                        footer: None,
-                        image: None,
-                        thumbnail: None,
-                        video: None,
-                        provider: None,
-                        author: None,
-                        fields: (EmbedFields(vec![EmbedField {
-                            name: "description".to_string(),
-                            value: note.content
-                        }]))
-                    }
-                ]),
-                components: Default::default()
+        TodoistEvent::ItemAdded(item)
+        | TodoistEvent::ItemRemoved(item)
+        | TodoistEvent::ItemDeleted(item)
+        | TodoistEvent::ItemCompleted(item)
+        | TodoistEvent::ItemUncompleted(item) => {
+            NormalizedEvent {
+                source: "todoist",
+                event,
+                title: item.content,
+                body: Some(item.description).filter(|description| !description.is_empty()),
+                url: Some(item.id.url()),
+                project: Some(item.project_id.to_string()),
+                priority: Some(item.priority),
+                labels: item.labels,
+                initiator,
+                received_at,
+            }
+        }
+        TodoistEvent::NoteAdded(note)
+        | TodoistEvent::NoteUpdated(note)
+        | TodoistEvent::NoteDeleted(note) => {
+            NormalizedEvent {
+                source: "todoist",
+                title: event.clone(),
+                event,
+                body: Some(note.content),
+                url: Some(note.item_id.url()),
+                project: Some(note.project_id.to_string()),
+                priority: None,
+                labels: vec![],
+                initiator,
+                received_at,
+            }
+        }
+        TodoistEvent::ReminderFired(reminder) => {
+            NormalizedEvent {
+                source: "todoist",
+                title: event.clone(),
+                event,
+                body: reminder.due.map(|due| due.string),
+                url: Some(reminder.item_id.url()),
+                project: None,
+                priority: None,
+                labels: vec![],
+                initiator,
+                received_at,
+            }
+        }
+        _ => {
+            NormalizedEvent {
+                source: "todoist",
+                title: event.clone(),
+                event,
+                body: None,
+                url: None,
+                project: None,
+                priority: None,
+                labels: vec![],
+                initiator,
+                received_at,
             }
         }
-        _ => unreachable!("oops")
     }
 }
 
+/// route to `/api/from/todoist/to/<name>`. The sink is built only once at startup.
+fn todoist_route<S, F, K>(name: &str, sink: K, mapper: F) -> actix_web::Resource
+where
+    S: Serialize + 'static,
+    F: 'static + Copy + FnOnce(TodoistPayload) -> S,
+    K: Sink<S> + 'static,
+{
+    let json_handler = Arc::new(JsonHandler::new(sink, mapper));
+    web::resource(format!("/api/from/todoist/to/{name}"))
+        .route(
+            web::post()
+                .to(move |a, b, c| handler::handle(Arc::clone(&json_handler), a, b, c))
+        )
+}
+
 static RUNNING_CONFIG: OnceCell<Config> = OnceCell::new();
 
 #[actix_web::main]
@@ -97,8 +286,16 @@ async fn main() -> std::io::Result<()> {
     // This function contains code snippet which is licensed with Apache License 2.0
     // from https://github.com/actix/examples.
     // See https://www.apache.org/licenses/LICENSE-2.0.txt for full text.
-    println!("starting");
-    match setup_logger().context("failed to setup logger") {
+    // read before the logger is set up, which needs to know whether stdout is taken by a sink
+    let running_config = File::open("data/config.json").unwrap();
+    let mut running_config: Config = serde_json::from_reader(BufReader::new(running_config)).unwrap();
+    let log_to_stderr = running_config.writes_jsonl_to_stdout();
+    if log_to_stderr {
+        eprintln!("starting");
+    } else {
+        println!("starting");
+    }
+    match setup_logger(log_to_stderr).context("failed to setup logger") {
         Ok(_) => {}
         Err(err) => {
             eprintln!("failed to initialize logger: {:?}", err);
@@ -123,33 +320,85 @@ async fn main() -> std::io::Result<()> {
             .with_single_cert(cert_chain, keys.remove(0)).unwrap()
     };
 
-    trace!("Reading config...");
-    let running_config = File::open("data/config.json").unwrap();
-    RUNNING_CONFIG.set(serde_json::from_reader(BufReader::new(running_config)).unwrap());
+    running_config.add_fixed_routes();
+    RUNNING_CONFIG.set(running_config);
     trace!("building HttpServer");
     let mut http_server = HttpServer::new(|| {
-        App::new()
-            .wrap(actix_web::middleware::Logger::default())
-            .app_data(
-                JsonConfig::default().error_handler(handler::json_error_handler)
-            )
-            .service(
-                web::resource("/api/from/todoist/to/discord")
-                    .route(
-                        web::post()
-                            .guard(guard::Header("content-type", "application/json"))
-                            .to(|a, b| handler::handle(Arc::new(JsonHandler::new(
-                                RUNNING_CONFIG.get().unwrap().discord_webhook.clone().unwrap(),
-                                todoist_to_webhook
-                            )), a, b))
-                    )
+        let config = RUNNING_CONFIG.get().unwrap();
+        let mut app = App::new()
+            .wrap(actix_web::middleware::Logger::default());
+        // no route for sinks which are not configured
+        if let Some(url) = &config.discord_webhook {
+            app = app.service(todoist_route("discord", DiscordWebhook(url.clone()), todoist_to_webhook));
+        }
+        if let Some(sink) = &config.matrix {
+            app = app.service(todoist_route("matrix", sink.clone(), |incoming| MatrixRoomMessage::from(&todoist_to_webhook(incoming))));
+        }
+        if let Some(sink) = &config.telegram {
+            app = app.service(todoist_route("telegram", sink.clone(), todoist_to_webhook));
+        }
+        if let Some(sink) = &config.line {
+            app = app.service(todoist_route("line", sink.clone(), todoist_to_line));
+        }
+        if let Some(sink) = &config.misskey {
+            app = app.service(todoist_route("misskey", sink.clone(), todoist_to_webhook));
+        }
+        if let Some(sink) = &config.mastodon {
+            app = app.service(todoist_route("mastodon", sink.clone(), todoist_to_webhook));
+        }
+        if let Some(sink) = &config.mattermost {
+            app = app.service(todoist_route("mattermost", sink.clone(), todoist_to_webhook));
+        }
+        if let Some(sink) = &config.rocketchat {
+            app = app.service(todoist_route("rocketchat", sink.clone(), todoist_to_webhook));
+        }
+        if let Some(sink) = &config.email {
+            app = app.service(todoist_route("email", sink.clone(), todoist_to_webhook));
+        }
+        if let Some(sink) = &config.ntfy {
+            app = app.service(todoist_route("ntfy", sink.clone(), todoist_to_push));
+        }
+        if let Some(sink) = &config.gotify {
+            app = app.service(todoist_route("gotify", sink.clone(), todoist_to_push));
+        }
+        if let Some(sink) = &config.pushover {
+            app = app.service(todoist_route("pushover", sink.clone(), todoist_to_push));
+        }
+        if let Some(sink) = &config.http {
+            app = app.service(todoist_route("http", sink.clone(), todoist_to_event));
+        }
+        if let Some(sink) = &config.mqtt {
+            app = app.service(todoist_route("mqtt", sink.clone(), todoist_to_event));
+        }
+        if let Some(sink) = &config.amqp {
+            app = app.service(todoist_route("amqp", sink.clone(), todoist_to_event));
+        }
+        if let Some(sink) = &config.redis_stream {
+            app = app.service(todoist_route("redis", sink.clone(), todoist_to_event));
+        }
+        if let Some(sink) = &config.jsonl {
+            let record = sink.record;
+            app = app.service(todoist_route("jsonl", sink.clone(), move |incoming| match record {
+                Record::Event => RecordedPayload::Event(Cow::Owned(todoist_to_event(incoming))),
+                Record::Discord => RecordedPayload::Discord(Cow::Owned(todoist_to_webhook(incoming))),
+            }));
+        }
+        if let Some(sink) = &config.exec {
+            app = app.service(todoist_route("exec", sink.clone(), todoist_to_event));
+        }
+        for route in &config.routes {
+            trace!("registering fan-out route {}", route.path);
+            app = app.service(
+                web::resource(route.path.as_str())
                     .route(
                         web::post()
-                            .to(|| async {
-                                HttpResponse::BadRequest().body("Content-Type header must be included")
-                            })
+                            .to(move |a, b| handler::handle_fan_out(route, a, b))
                     )
-            )
+                    // Trello checks that the callback URL answers HEAD when a webhook is created
+                    .route(web::head().to(HttpResponse::Ok))
+            );
+        }
+        app
     });
     trace!("binding ports");
     http_server
@@ -161,3 +410,46 @@ async fn main() -> std::io::Result<()> {
     info!("stopped");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `note:added` whose comment is `content`
+    fn note_added(content: &str) -> TodoistPayload {
+        serde_json::from_value(serde_json::json!({
+            "user_id": 1,
+            "event_name": "note:added",
+            "version_number": "8",
+            "initiator": {"id": 1, "email": "alice@example.com", "full_name": "Alice", "image_id": null},
+            "event_data": {
+                "id": 1, "legacy_id": 1, "posted_uid": 1,
+                "item_id": 2_995_104_339_i64, "legacy_item_id": 33_511_505,
+                "project_id": 2_203_306_141_i64, "legacy_project_id": 128_501_470,
+                "content": content,
+                "file_attachment": {"file_name": "a.txt", "file_size": 1, "file_type": "text/plain", "file_url": "https://example.com/a.txt", "upload_state": "completed"},
+                "uids_to_notify": null,
+                "is_deleted": 0,
+                "posted": "2026-10-19T00:00:00Z",
+                "reactions": {},
+            },
+        })).unwrap()
+    }
+
+    #[test]
+    fn line_shows_the_comment_without_project_id() {
+        let message = serde_json::to_value(todoist_to_line(note_added("Two bottles"))).unwrap();
+        assert_eq!(message["altText"], "[Todoist] note:added");
+        let rows = message["contents"]["body"]["contents"].as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["contents"][1]["text"], "Two bottles");
+        assert!(!message.to_string().contains("2203306141"));
+    }
+
+    #[test]
+    fn line_leaves_out_the_body_of_an_empty_comment() {
+        let message = serde_json::to_value(todoist_to_line(note_added(""))).unwrap();
+        assert!(message["contents"].get("body").is_none(), "{message}");
+        assert_eq!(message["contents"]["footer"]["contents"][0]["action"]["uri"], "https://todoist.com/showTask?id=2995104339");
+    }
+}
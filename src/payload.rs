@@ -1,2 +1,24 @@
 pub mod todoist;
-pub mod discord;
\ No newline at end of file
+pub mod discord;
+pub mod matrix;
+pub mod telegram;
+pub mod line;
+pub mod misskey;
+pub mod mastodon;
+pub mod mattermost;
+pub mod rocketchat;
+pub mod email;
+pub mod push;
+pub mod ntfy;
+pub mod gotify;
+pub mod pushover;
+pub mod event;
+pub mod github;
+pub mod gitlab;
+pub mod alertmanager;
+pub mod uptime_kuma;
+pub mod sentry;
+pub mod backlog;
+pub mod jira;
+pub mod linear;
+pub mod trello;
@@ -0,0 +1,387 @@
+use std::collections::BTreeMap;
+use serde::Deserialize;
+use crate::payload::discord::{DiscordWebhookPayload, EmbedField};
+use crate::payload::event::NormalizedEvent;
+use crate::render::color::{BLUE, GREEN, RED, YELLOW};
+use crate::render::summary::{field, Service, Summary};
+
+pub(crate) const ALERTMANAGER: Service = Service {
+    name: "Alertmanager",
+    avatar_url: "https://prometheus.io/assets/favicons/android-chrome-192x192.png",
+};
+
+pub(crate) const GRAFANA: Service = Service {
+    name: "Grafana",
+    avatar_url: "https://grafana.com/static/assets/img/fav32.png",
+};
+
+/// a group of alerts. Grafana alerts are sent in the same format with some extra fields.
+/// for more information, see https://prometheus.io/docs/alerting/latest/configuration/#webhook_config
+/// and https://grafana.com/docs/grafana/latest/alerting/configure-notifications/manage-contact-points/integrations/webhook-notifier/
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AlertmanagerPayload {
+    pub(crate) status: AlertStatus,
+    pub(crate) receiver: String,
+    #[serde(default)]
+    pub(crate) group_labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub(crate) common_labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub(crate) common_annotations: BTreeMap<String, String>,
+    #[serde(rename = "externalURL")]
+    pub(crate) external_url: String,
+    pub(crate) alerts: Vec<Alert>,
+    /// number of alerts dropped by `max_alerts` of the receiver
+    #[serde(default)]
+    pub(crate) truncated_alerts: usize,
+    /// Grafana only
+    #[serde(default)]
+    pub(crate) title: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Alert {
+    pub(crate) status: AlertStatus,
+    #[serde(default)]
+    pub(crate) labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub(crate) annotations: BTreeMap<String, String>,
+    /// RFC 3339
+    pub(crate) starts_at: String,
+    #[serde(rename = "generatorURL", default)]
+    pub(crate) generator_url: String,
+    /// Grafana only
+    #[serde(rename = "panelURL", default)]
+    pub(crate) panel_url: Option<String>,
+    /// Grafana only
+    #[serde(rename = "dashboardURL", default)]
+    pub(crate) dashboard_url: Option<String>,
+    /// Grafana only
+    #[serde(rename = "silenceURL", default)]
+    pub(crate) silence_url: Option<String>,
+    /// Grafana only. values of the queries and expressions, e.g. `{"B": 22}`
+    #[serde(default)]
+    pub(crate) values: BTreeMap<String, serde_json::Value>,
+}
+
+impl Alert {
+    /// link to the panel if any, otherwise to the dashboard, otherwise to the evaluated expression
+    fn url(&self) -> Option<&str> {
+        [self.panel_url.as_deref(), self.dashboard_url.as_deref(), Some(self.generator_url.as_str())]
+            .into_iter()
+            .flatten()
+            .find(|url| !url.is_empty())
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum AlertStatus {
+    Firing,
+    Resolved,
+}
+
+impl AlertStatus {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Firing => "firing",
+            Self::Resolved => "resolved",
+        }
+    }
+}
+
+/// joins `key="value"` with commas
+fn label_set<'a>(labels: impl IntoIterator<Item = (&'a String, &'a String)>) -> String {
+    labels.into_iter().map(|(key, value)| format!("{key}=\"{value}\"")).collect::<Vec<_>>().join(", ")
+}
+
+/// human-written description. `summary` is preferred.
+fn annotation(annotations: &BTreeMap<String, String>) -> Option<&String> {
+    annotations.get("summary").or_else(|| annotations.get("description"))
+}
+
+impl AlertmanagerPayload {
+    fn alert_name(&self) -> &str {
+        self.group_labels.get("alertname").or_else(|| self.common_labels.get("alertname")).map_or("alert", String::as_str)
+    }
+
+    /// same as the default title of Alertmanager's notifications, e.g. `[FIRING:2] HighLatency (job="api")`
+    fn title(&self) -> String {
+        if let Some(title) = &self.title {
+            return title.clone()
+        }
+        let count = self.alerts.len() + self.truncated_alerts;
+        let title = format!("[{}:{count}] {}", self.status.as_str().to_uppercase(), self.alert_name());
+        let others = self.group_labels.iter().filter(|(key, _)| *key != "alertname").collect::<Vec<_>>();
+        if others.is_empty() {
+            title
+        } else {
+            format!("{title} ({})", label_set(others))
+        }
+    }
+
+    fn color(&self) -> u32 {
+        if self.status == AlertStatus::Resolved {
+            return GREEN
+        }
+        match self.common_labels.get("severity").map(String::as_str) {
+            Some("warning") => YELLOW,
+            Some("info" | "none") => BLUE,
+            _ => RED,
+        }
+    }
+
+    /// one per alert. The common labels are left out, and only the labels telling the alerts apart are used as the name.
+    fn alert_field(&self, alert: &Alert) -> EmbedField {
+        let distinct = alert.labels.iter().filter(|(key, _)| !self.common_labels.contains_key(*key)).collect::<Vec<_>>();
+        let name = if distinct.is_empty() { self.alert_name().to_string() } else { label_set(distinct) };
+        let mut lines = vec![];
+        if let Some(annotation) = annotation(&alert.annotations) {
+            lines.push(annotation.clone());
+        }
+        if let Ok(starts_at) = chrono::DateTime::parse_from_rfc3339(&alert.starts_at) {
+            lines.push(format!("since <t:{}:R>", starts_at.timestamp()));
+        }
+        if !alert.values.is_empty() {
+            lines.push(alert.values.iter().map(|(name, value)| format!("{name}={value}")).collect::<Vec<_>>().join(", "));
+        }
+        let anchors = [("source", alert.url()), ("silence", alert.silence_url.as_deref())]
+            .into_iter()
+            .filter_map(|(name, url)| url.filter(|url| !url.is_empty()).map(|url| format!("[{name}]({url})")))
+            .collect::<Vec<_>>();
+        if !anchors.is_empty() {
+            lines.push(anchors.join(" | "));
+        }
+        field(&format!("[{}] {name}", alert.status.as_str().to_uppercase()), lines.join("\n"))
+    }
+
+    /// the panel etc. of the alert if there is only one, otherwise Alertmanager (Grafana) itself
+    fn url(&self) -> String {
+        match &self.alerts[..] {
+            [alert] => alert.url().unwrap_or(&self.external_url).to_string(),
+            _ => self.external_url.clone(),
+        }
+    }
+
+    /// `service` is either [`ALERTMANAGER`] or [`GRAFANA`]
+    pub(crate) fn to_webhook(&self, service: &Service) -> DiscordWebhookPayload {
+        Summary::from(self).into_webhook(service, None, format!("receiver: {}", self.receiver))
+    }
+
+    /// `source` is either `alertmanager` or `grafana`
+    pub(crate) fn to_event(&self, source: &'static str) -> NormalizedEvent {
+        NormalizedEvent {
+            source,
+            event: self.status.as_str().to_string(),
+            title: self.title(),
+            body: annotation(&self.common_annotations).cloned(),
+            url: Some(self.url()),
+            project: Some(self.receiver.clone()),
+            priority: None,
+            labels: self.common_labels.iter().map(|(key, value)| format!("{key}={value}")).collect(),
+            initiator: None,
+            received_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+impl From<&AlertmanagerPayload> for Summary {
+    fn from(payload: &AlertmanagerPayload) -> Self {
+        Self {
+            title: payload.title(),
+            url: payload.url(),
+            description: annotation(&payload.common_annotations).cloned(),
+            color: payload.color(),
+            fields: payload.alerts.iter().map(|alert| payload.alert_field(alert)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    /// sent by Grafana Alerting to a webhook contact point, from its documentation
+    fn grafana() -> AlertmanagerPayload {
+        AlertmanagerPayload::deserialize(json!({
+            "receiver": "My Super Webhook",
+            "status": "firing",
+            "orgId": 1,
+            "alerts": [
+                {
+                    "status": "firing",
+                    "labels": {"alertname": "High memory usage", "team": "blue", "zone": "us-1"},
+                    "annotations": {
+                        "description": "The system has high memory usage",
+                        "runbook_url": "https://myrunbook.com/runbook/1234",
+                        "summary": "This alert was triggered for zone us-1",
+                    },
+                    "startsAt": "2021-10-12T09:51:03.157076+02:00",
+                    "endsAt": "0001-01-01T00:00:00Z",
+                    "generatorURL": "https://play.grafana.org/alerting/1afz29v7z/edit",
+                    "fingerprint": "c6eadffa33fcdf37",
+                    "silenceURL": "https://play.grafana.org/alerting/silence/new?alertmanager=grafana&matchers=alertname%3DT2%2Cteam%3Dblue%2Czone%3Dus-1",
+                    "dashboardURL": "",
+                    "panelURL": "",
+                    "values": {"B": 44.239_437_375_419_08, "C": 1},
+                },
+                {
+                    "status": "firing",
+                    "labels": {"alertname": "High CPU usage", "team": "blue", "zone": "eu-1"},
+                    "annotations": {
+                        "description": "The system has high CPU usage",
+                        "runbook_url": "https://myrunbook.com/runbook/1234",
+                        "summary": "This alert was triggered for zone eu-1",
+                    },
+                    "startsAt": "2021-10-12T09:56:03.157076+02:00",
+                    "endsAt": "0001-01-01T00:00:00Z",
+                    "generatorURL": "https://play.grafana.org/alerting/d1rdpdv7k/edit",
+                    "fingerprint": "bc97ff14869b13e3",
+                    "silenceURL": "https://play.grafana.org/alerting/silence/new?alertmanager=grafana&matchers=alertname%3DT1%2Cteam%3Dblue%2Czone%3Deu-1",
+                    "dashboardURL": "https://play.grafana.org/d/000000012/grafana-play-home",
+                    "panelURL": "https://play.grafana.org/d/000000012/grafana-play-home?viewPanel=4",
+                    "values": {"B": 44.239_437_375_419_08, "C": 1},
+                },
+            ],
+            "groupLabels": {},
+            "commonLabels": {"team": "blue"},
+            "commonAnnotations": {},
+            "externalURL": "https://play.grafana.org/",
+            "version": "1",
+            "groupKey": "{}:{}",
+            "truncatedAlerts": 0,
+            "title": "[FIRING:2]  (blue)",
+            "state": "alerting",
+            "message": "**Firing**\n\nValue: B=44.23943737541908, C=1\nLabels:\n - alertname = High memory usage\n",
+        })).unwrap()
+    }
+
+    #[test]
+    fn grafana_title_is_kept() {
+        let payload = grafana();
+        let summary = Summary::from(&payload);
+        assert_eq!(summary.title, "[FIRING:2]  (blue)");
+        // more than one alert, so the link is to Grafana itself
+        assert_eq!(summary.url, "https://play.grafana.org/");
+        assert_eq!(summary.color, RED);
+        assert_eq!(summary.description, None);
+    }
+
+    #[test]
+    fn grafana_alerts_show_values_and_links() {
+        let summary = Summary::from(&grafana());
+        let [memory, cpu] = &summary.fields[..] else { panic!("2 fields are expected") };
+        // `team` is common to both, so it is left out
+        assert_eq!(memory.name, "[FIRING] alertname=\"High memory usage\", zone=\"us-1\"");
+        assert_eq!(memory.value, [
+            "This alert was triggered for zone us-1",
+            "since <t:1634025063:R>",
+            "B=44.23943737541908, C=1",
+            "[source](https://play.grafana.org/alerting/1afz29v7z/edit) | [silence](https://play.grafana.org/alerting/silence/new?alertmanager=grafana&matchers=alertname%3DT2%2Cteam%3Dblue%2Czone%3Dus-1)",
+        ].join("\n"));
+        // the panel is preferred to the dashboard and the rule
+        assert!(cpu.value.contains("[source](https://play.grafana.org/d/000000012/grafana-play-home?viewPanel=4)"), "{}", cpu.value);
+    }
+
+    #[test]
+    fn grafana_event_is_normalized() {
+        let event = grafana().to_event("grafana");
+        assert_eq!((event.source, event.event.as_str()), ("grafana", "firing"));
+        assert_eq!(event.project.as_deref(), Some("My Super Webhook"));
+        assert_eq!(event.labels, ["team=blue"]);
+        let webhook = grafana().to_webhook(&GRAFANA);
+        assert_eq!(webhook.username.as_deref(), Some("Grafana"));
+        assert_eq!(webhook.embeds.0[0].footer.as_ref().unwrap().text, "receiver: My Super Webhook");
+    }
+
+    /// sent by Alertmanager for a group of two alerts by `alertname` and `job`
+    fn group(status: &str) -> AlertmanagerPayload {
+        AlertmanagerPayload::deserialize(json!({
+            "version": "4",
+            "groupKey": "{}:{alertname=\"InstanceDown\", job=\"node\"}",
+            "truncatedAlerts": 0,
+            "status": status,
+            "receiver": "webhook",
+            "groupLabels": {"alertname": "InstanceDown", "job": "node"},
+            "commonLabels": {"alertname": "InstanceDown", "job": "node", "severity": "warning"},
+            "commonAnnotations": {"summary": "Instances are down"},
+            "externalURL": "http://alertmanager:9093",
+            "alerts": [
+                {
+                    "status": status,
+                    "labels": {"alertname": "InstanceDown", "instance": "web-1:9100", "job": "node", "severity": "warning"},
+                    "annotations": {"description": "web-1 has been down for more than 5 minutes."},
+                    "startsAt": "2026-10-19T00:00:00Z",
+                    "endsAt": "0001-01-01T00:00:00Z",
+                    "generatorURL": "http://prometheus:9090/graph?g0.expr=up+%3D%3D+0",
+                    "fingerprint": "1a2b3c4d5e6f7a8b",
+                },
+                {
+                    "status": status,
+                    "labels": {"alertname": "InstanceDown", "instance": "web-2:9100", "job": "node", "severity": "warning"},
+                    "annotations": {},
+                    "startsAt": "2026-10-19T00:01:00Z",
+                    "endsAt": "0001-01-01T00:00:00Z",
+                    "generatorURL": "",
+                    "fingerprint": "8b7a6f5e4d3c2b1a",
+                },
+            ],
+        })).unwrap()
+    }
+
+    #[test]
+    fn firing_group_is_rendered() {
+        let summary = Summary::from(&group("firing"));
+        assert_eq!(summary.title, "[FIRING:2] InstanceDown (job=\"node\")");
+        assert_eq!(summary.url, "http://alertmanager:9093");
+        assert_eq!(summary.description.as_deref(), Some("Instances are down"));
+        assert_eq!(summary.color, YELLOW);
+        let fields = summary.fields.iter().map(|field| (field.name.as_str(), field.value.as_str())).collect::<Vec<_>>();
+        assert_eq!(fields, [
+            (
+                "[FIRING] instance=\"web-1:9100\"",
+                "web-1 has been down for more than 5 minutes.\nsince <t:1792368000:R>\n[source](http://prometheus:9090/graph?g0.expr=up+%3D%3D+0)",
+            ),
+            // nothing to link to
+            ("[FIRING] instance=\"web-2:9100\"", "since <t:1792368060:R>"),
+        ]);
+    }
+
+    #[test]
+    fn resolved_group_is_green() {
+        let payload = group("resolved");
+        let summary = Summary::from(&payload);
+        assert_eq!(summary.title, "[RESOLVED:2] InstanceDown (job=\"node\")");
+        assert_eq!(summary.color, GREEN);
+        assert!(summary.fields.iter().all(|field| field.name.starts_with("[RESOLVED] ")));
+
+        let event = payload.to_event("alertmanager");
+        assert_eq!(event.event, "resolved");
+        assert_eq!(event.labels, ["alertname=InstanceDown", "job=node", "severity=warning"]);
+    }
+
+    #[test]
+    fn single_alert_links_to_its_source() {
+        let mut payload = group("firing");
+        payload.alerts.truncate(1);
+        payload.truncated_alerts = 3;
+        let summary = Summary::from(&payload);
+        // alerts dropped by `max_alerts` are still counted
+        assert_eq!(summary.title, "[FIRING:4] InstanceDown (job=\"node\")");
+        assert_eq!(summary.url, "http://prometheus:9090/graph?g0.expr=up+%3D%3D+0");
+    }
+
+    #[test]
+    fn severity_decides_the_color() {
+        let mut payload = group("firing");
+        for (severity, color) in [("critical", RED), ("info", BLUE), ("none", BLUE)] {
+            payload.common_labels.insert("severity".to_string(), severity.to_string());
+            assert_eq!(Summary::from(&payload).color, color, "{severity}");
+        }
+        payload.common_labels.remove("severity");
+        assert_eq!(Summary::from(&payload).color, RED);
+    }
+}
@@ -0,0 +1,467 @@
+use serde::Deserialize;
+use crate::payload::discord::{DiscordWebhookPayload, EmbedField};
+use crate::payload::event::NormalizedEvent;
+use crate::render::color::{BLUE, GREEN, GREY, RED};
+use crate::render::summary::{change, field, Actor, Service, Summary};
+
+const BACKLOG: Service = Service {
+    name: "Backlog",
+    avatar_url: "https://nulab.com/static/backlog-logo.png",
+};
+
+/// for more information, see https://developer.nulab.com/ja/docs/backlog/api/2/get-recent-updates/
+#[derive(Deserialize, Clone)]
+#[serde(try_from = "RawBacklogPayload")]
+pub(crate) struct BacklogPayload {
+    pub(crate) project: BacklogProject,
+    pub(crate) event: BacklogEvent,
+    pub(crate) created_user: BacklogUser,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawBacklogPayload {
+    project: BacklogProject,
+    #[serde(rename = "type")]
+    kind: u16,
+    content: serde_json::Value,
+    created_user: BacklogUser,
+}
+
+impl TryFrom<RawBacklogPayload> for BacklogPayload {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawBacklogPayload) -> Result<Self, Self::Error> {
+        Ok(Self {
+            project: raw.project,
+            event: BacklogEvent::parse(raw.kind, raw.content)?,
+            created_user: raw.created_user,
+        })
+    }
+}
+
+/// pair of `type` and `content`. Unsupported types keep only their number.
+#[derive(Clone)]
+pub(crate) enum BacklogEvent {
+    // raw: 1
+    IssueCreated(BacklogIssue),
+    // raw: 2
+    IssueUpdated(BacklogIssue),
+    // raw: 3
+    IssueCommented(BacklogIssue),
+    // raw: 4
+    IssueDeleted(BacklogIssue),
+    // raw: 5
+    WikiCreated(BacklogWiki),
+    // raw: 6
+    WikiUpdated(BacklogWiki),
+    // raw: 7
+    WikiDeleted(BacklogWiki),
+    Other(u16),
+}
+
+impl BacklogEvent {
+    fn parse(kind: u16, content: serde_json::Value) -> serde_json::Result<Self> {
+        Ok(match kind {
+            1 => Self::IssueCreated(serde_json::from_value(content)?),
+            2 => Self::IssueUpdated(serde_json::from_value(content)?),
+            3 => Self::IssueCommented(serde_json::from_value(content)?),
+            4 => Self::IssueDeleted(serde_json::from_value(content)?),
+            5 => Self::WikiCreated(serde_json::from_value(content)?),
+            6 => Self::WikiUpdated(serde_json::from_value(content)?),
+            7 => Self::WikiDeleted(serde_json::from_value(content)?),
+            _ => Self::Other(kind),
+        })
+    }
+
+    /// e.g. `issue:created`
+    pub(crate) fn name(&self) -> String {
+        match self {
+            Self::IssueCreated(_) => "issue:created".to_string(),
+            Self::IssueUpdated(_) => "issue:updated".to_string(),
+            Self::IssueCommented(_) => "issue:commented".to_string(),
+            Self::IssueDeleted(_) => "issue:deleted".to_string(),
+            Self::WikiCreated(_) => "wiki:created".to_string(),
+            Self::WikiUpdated(_) => "wiki:updated".to_string(),
+            Self::WikiDeleted(_) => "wiki:deleted".to_string(),
+            Self::Other(kind) => format!("type:{kind}"),
+        }
+    }
+
+    /// action for display, e.g. `課題を追加`
+    const fn label(&self) -> &'static str {
+        match self {
+            Self::IssueCreated(_) => "課題を追加",
+            Self::IssueUpdated(_) => "課題を更新",
+            Self::IssueCommented(_) => "課題にコメント",
+            Self::IssueDeleted(_) => "課題を削除",
+            Self::WikiCreated(_) => "Wikiを追加",
+            Self::WikiUpdated(_) => "Wikiを更新",
+            Self::WikiDeleted(_) => "Wikiを削除",
+            Self::Other(_) => "更新",
+        }
+    }
+
+    const fn color(&self) -> u32 {
+        match self {
+            Self::IssueCreated(_) | Self::WikiCreated(_) => GREEN,
+            Self::IssueUpdated(_) | Self::IssueCommented(_) | Self::WikiUpdated(_) => BLUE,
+            Self::IssueDeleted(_) | Self::WikiDeleted(_) => RED,
+            Self::Other(_) => GREY,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BacklogProject {
+    /// e.g. `PROJ`
+    pub(crate) project_key: String,
+    pub(crate) name: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct BacklogUser {
+    pub(crate) name: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Named {
+    pub(crate) name: String,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BacklogIssue {
+    /// number in the project, `12` of `PROJ-12`
+    #[serde(rename = "key_id")]
+    pub(crate) key_id: u64,
+    #[serde(default)]
+    pub(crate) summary: Option<String>,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    #[serde(default)]
+    pub(crate) issue_type: Option<Named>,
+    #[serde(default)]
+    pub(crate) priority: Option<Named>,
+    #[serde(default)]
+    pub(crate) status: Option<Named>,
+    #[serde(default)]
+    pub(crate) assignee: Option<Named>,
+    #[serde(default)]
+    pub(crate) due_date: Option<String>,
+    #[serde(default)]
+    pub(crate) comment: Option<BacklogComment>,
+    #[serde(default)]
+    pub(crate) changes: Vec<BacklogChange>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct BacklogComment {
+    pub(crate) id: u64,
+    #[serde(default)]
+    pub(crate) content: Option<String>,
+}
+
+/// an updated field
+#[derive(Deserialize, Clone)]
+pub(crate) struct BacklogChange {
+    /// e.g. `status`, `assigner`, `limitDate`
+    pub(crate) field: String,
+    #[serde(default)]
+    pub(crate) old_value: String,
+    #[serde(default)]
+    pub(crate) new_value: String,
+}
+
+impl BacklogChange {
+    /// display name of the field
+    fn field_label(&self) -> &str {
+        match self.field.as_str() {
+            "summary" => "件名",
+            "description" => "詳細",
+            "status" => "状態",
+            "assigner" => "担当者",
+            "priority" => "優先度",
+            "issueType" => "種別",
+            "resolution" => "完了理由",
+            "startDate" => "開始日",
+            "limitDate" => "期限日",
+            "estimatedHours" => "予定時間",
+            "actualHours" => "実績時間",
+            "milestone" => "マイルストーン",
+            "version" => "発生バージョン",
+            "component" => "カテゴリー",
+            "parentIssue" => "親課題",
+            "attachment" => "添付ファイル",
+            field => field,
+        }
+    }
+
+    /// the status is sent as its number, so only the built-in statuses are named
+    fn value_label(&self, value: &str) -> String {
+        let label = match (self.field.as_str(), value) {
+            ("status", "1") => "未対応",
+            ("status", "2") => "処理中",
+            ("status", "3") => "処理済み",
+            ("status", "4") => "完了",
+            (_, "") => "なし",
+            (_, value) => value,
+        };
+        label.to_string()
+    }
+
+    fn to_field(&self) -> EmbedField {
+        // the description is often long, so only the fact that it changed is shown
+        if self.field == "description" {
+            return field(self.field_label(), "変更あり")
+        }
+        change(self.field_label(), Some(&self.value_label(&self.old_value)), &self.value_label(&self.new_value))
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct BacklogWiki {
+    pub(crate) id: u64,
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) content: Option<String>,
+}
+
+impl BacklogPayload {
+    /// `space_url` is e.g. `https://example.backlog.jp`. the link is left empty when omitted
+    fn summary(&self, space_url: Option<&str>) -> Summary {
+        let space_url = space_url.map(|space_url| space_url.trim_end_matches('/'));
+        let link = |path: String| space_url.map(|space_url| format!("{space_url}{path}")).unwrap_or_default();
+        let project = &self.project;
+        let title = |subject: &str| format!("[{}] {}: {subject}", project.name, self.event.label());
+        match &self.event {
+            BacklogEvent::IssueCreated(issue) | BacklogEvent::IssueUpdated(issue) | BacklogEvent::IssueCommented(issue) | BacklogEvent::IssueDeleted(issue) => {
+                let key = format!("{}-{}", project.project_key, issue.key_id);
+                let anchor = issue.comment.as_ref().map(|comment| format!("#comment-{}", comment.id)).unwrap_or_default();
+                let description = match &self.event {
+                    BacklogEvent::IssueCreated(_) => issue.description.clone(),
+                    _ => issue.comment.as_ref().and_then(|comment| comment.content.clone()),
+                };
+                let fields = match &self.event {
+                    BacklogEvent::IssueCreated(_) => [
+                        ("種別", &issue.issue_type),
+                        ("優先度", &issue.priority),
+                        ("状態", &issue.status),
+                        ("担当者", &issue.assignee),
+                    ].into_iter()
+                        .filter_map(|(name, value)| value.as_ref().map(|value| field(name, value.name.clone())))
+                        .chain(issue.due_date.iter().map(|due_date| field("期限日", due_date.clone())))
+                        .collect(),
+                    _ => issue.changes.iter().map(BacklogChange::to_field).collect(),
+                };
+                // deleted issues come without the summary
+                let subject = match issue.summary.as_deref() {
+                    Some(summary) if !summary.is_empty() => format!("{key} {summary}"),
+                    _ => key.clone(),
+                };
+                Summary {
+                    title: title(&subject),
+                    url: link(format!("/view/{key}{anchor}")),
+                    description,
+                    color: self.event.color(),
+                    fields,
+                }
+            }
+            BacklogEvent::WikiCreated(wiki) | BacklogEvent::WikiUpdated(wiki) | BacklogEvent::WikiDeleted(wiki) => Summary {
+                title: title(&wiki.name),
+                url: link(format!("/alias/wiki/{}", wiki.id)),
+                description: wiki.content.clone().filter(|_| matches!(self.event, BacklogEvent::WikiCreated(_))),
+                color: self.event.color(),
+                fields: vec![],
+            },
+            BacklogEvent::Other(_) => Summary {
+                title: title(&self.event.name()),
+                url: link(format!("/projects/{}", project.project_key)),
+                description: None,
+                color: self.event.color(),
+                fields: vec![],
+            },
+        }
+    }
+
+    pub(crate) fn to_webhook(&self, space_url: Option<&str>) -> DiscordWebhookPayload {
+        let actor = Actor {
+            name: self.created_user.name.clone(),
+            url: None,
+            avatar_url: None,
+        };
+        self.summary(space_url).into_webhook(&BACKLOG, Some(actor), self.event.name())
+    }
+
+    pub(crate) fn to_event(&self, space_url: Option<&str>) -> NormalizedEvent {
+        let summary = self.summary(space_url);
+        NormalizedEvent {
+            source: "backlog",
+            event: self.event.name(),
+            title: summary.title,
+            body: summary.description,
+            url: Some(summary.url).filter(|url| !url.is_empty()),
+            project: Some(self.project.name.clone()),
+            priority: None,
+            labels: vec![],
+            initiator: Some(self.created_user.name.clone()),
+            received_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use super::*;
+
+    const SPACE_URL: &str = "https://example.backlog.jp/";
+
+    /// the example of `type: 2` on https://developer.nulab.com/ja/docs/backlog/api/2/get-recent-updates/
+    const ISSUE_UPDATED: &str = r#"{
+        "id": 3153,
+        "project": {
+            "id": 92,
+            "projectKey": "SUB",
+            "name": "サブタスク",
+            "chartEnabled": true,
+            "subtaskingEnabled": true,
+            "projectLeaderCanEditProjectLeader": false,
+            "textFormattingRule": null,
+            "archived": false,
+            "displayOrder": 0
+        },
+        "type": 2,
+        "content": {
+            "id": 4809,
+            "key_id": 121,
+            "summary": "コメント",
+            "description": "",
+            "comment": {"id": 7237, "content": ""},
+            "changes": [
+                {"field": "milestone", "new_value": " R2014-07-23", "old_value": "", "type": "standard"},
+                {"field": "status", "new_value": "4", "old_value": "1", "type": "standard"}
+            ]
+        },
+        "notifications": [],
+        "createdUser": {
+            "id": 1,
+            "userId": "admin",
+            "name": "admin",
+            "roleType": 1,
+            "lang": "ja",
+            "mailAddress": "eguchi@nulab.example"
+        },
+        "created": "2013-12-27T07:50:44Z"
+    }"#;
+
+    /// `ISSUE_UPDATED` with another `type` and `content`
+    fn payload(kind: u16, content: Value) -> BacklogPayload {
+        let mut raw: Value = serde_json::from_str(ISSUE_UPDATED).unwrap();
+        raw["type"] = kind.into();
+        raw["content"] = content;
+        BacklogPayload::deserialize(raw).unwrap()
+    }
+
+    fn fields(summary: &Summary) -> Vec<(&str, &str)> {
+        summary.fields.iter().map(|field| (field.name.as_str(), field.value.as_str())).collect()
+    }
+
+    #[test]
+    fn issue_created_shows_the_attributes() {
+        let payload = payload(1, json!({
+            "id": 4810,
+            "key_id": 122,
+            "summary": "ログイン画面のデザイン修正",
+            "description": "ボタンの色を変更する",
+            "issueType": {"id": 1, "projectId": 92, "name": "タスク", "color": "#7ea800", "displayOrder": 0},
+            "priority": {"id": 3, "name": "中"},
+            "status": {"id": 1, "name": "未対応"},
+            "assignee": {"id": 2, "userId": "alice", "name": "Alice", "roleType": 2},
+            "dueDate": "2026-10-31",
+        }));
+        let summary = payload.summary(Some(SPACE_URL));
+        assert_eq!(summary.title, "[サブタスク] 課題を追加: SUB-122 ログイン画面のデザイン修正");
+        assert_eq!(summary.url, "https://example.backlog.jp/view/SUB-122");
+        assert_eq!(summary.description.as_deref(), Some("ボタンの色を変更する"));
+        assert_eq!(summary.color, GREEN);
+        assert_eq!(fields(&summary), [("種別", "タスク"), ("優先度", "中"), ("状態", "未対応"), ("担当者", "Alice"), ("期限日", "2026-10-31")]);
+    }
+
+    #[test]
+    fn issue_updated_shows_the_changes() {
+        let payload = BacklogPayload::deserialize(serde_json::from_str::<Value>(ISSUE_UPDATED).unwrap()).unwrap();
+        let summary = payload.summary(Some(SPACE_URL));
+        assert_eq!(summary.title, "[サブタスク] 課題を更新: SUB-121 コメント");
+        assert_eq!(summary.url, "https://example.backlog.jp/view/SUB-121#comment-7237");
+        assert_eq!(summary.color, BLUE);
+        // the status is sent as its number
+        assert_eq!(fields(&summary), [("マイルストーン", "なし →  R2014-07-23"), ("状態", "未対応 → 完了")]);
+
+        let event = payload.to_event(Some(SPACE_URL));
+        assert_eq!(event.event, "issue:updated");
+        assert_eq!(event.project.as_deref(), Some("サブタスク"));
+        assert_eq!(event.initiator.as_deref(), Some("admin"));
+    }
+
+    #[test]
+    fn issue_commented_shows_the_comment() {
+        let payload = payload(3, json!({
+            "id": 4809,
+            "key_id": 121,
+            "summary": "コメント",
+            "comment": {"id": 7238, "content": "確認しました"},
+            "changes": [{"field": "description", "new_value": "新しい詳細", "old_value": "古い詳細", "type": "standard"}],
+        }));
+        let summary = payload.summary(Some(SPACE_URL));
+        assert_eq!(summary.title, "[サブタスク] 課題にコメント: SUB-121 コメント");
+        assert_eq!(summary.url, "https://example.backlog.jp/view/SUB-121#comment-7238");
+        assert_eq!(summary.description.as_deref(), Some("確認しました"));
+        // the description is often long, so only the fact is shown
+        assert_eq!(fields(&summary), [("詳細", "変更あり")]);
+    }
+
+    #[test]
+    fn issue_deleted_is_titled_by_its_key() {
+        let summary = payload(4, json!({"id": 4809, "key_id": 121})).summary(Some(SPACE_URL));
+        assert_eq!(summary.title, "[サブタスク] 課題を削除: SUB-121");
+        assert_eq!(summary.color, RED);
+    }
+
+    #[test]
+    fn wiki_created_shows_the_content() {
+        let payload = payload(5, json!({"id": 80, "name": "リリース手順", "content": "1. タグを打つ"}));
+        let summary = payload.summary(Some(SPACE_URL));
+        assert_eq!(summary.title, "[サブタスク] Wikiを追加: リリース手順");
+        assert_eq!(summary.url, "https://example.backlog.jp/alias/wiki/80");
+        assert_eq!(summary.description.as_deref(), Some("1. タグを打つ"));
+        assert_eq!(summary.color, GREEN);
+    }
+
+    #[test]
+    fn wiki_updated_leaves_out_the_content() {
+        let summary = payload(6, json!({"id": 80, "name": "リリース手順", "content": "1. タグを打つ\n2. 告知する"})).summary(Some(SPACE_URL));
+        assert_eq!(summary.title, "[サブタスク] Wikiを更新: リリース手順");
+        assert_eq!(summary.description, None);
+        assert_eq!(summary.color, BLUE);
+    }
+
+    #[test]
+    fn other_types_link_to_the_project() {
+        let payload = payload(14, json!({"tx_id": 1, "comment": {"content": ""}, "link": [], "changes": []}));
+        let summary = payload.summary(Some(SPACE_URL));
+        assert_eq!(summary.title, "[サブタスク] 更新: type:14");
+        assert_eq!(summary.url, "https://example.backlog.jp/projects/SUB");
+        assert_eq!(summary.color, GREY);
+    }
+
+    #[test]
+    fn link_is_left_out_without_space_url() {
+        let payload = payload(4, json!({"id": 4809, "key_id": 121}));
+        assert_eq!(payload.summary(None).url, "");
+        assert_eq!(payload.to_event(None).url, None);
+        let webhook = payload.to_webhook(None);
+        assert_eq!(webhook.embeds.0[0].author.as_ref().unwrap().name, "admin");
+    }
+}
@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use iso8601::DateTime as ISO8601DateTime;
 /// for more information, see https://discord.com/developers/docs/resources/webhook#execute-webhook
@@ -13,8 +13,9 @@ struct DiscordWebhookQueryPayload {
 struct ThreadID(u64);
 
 /// for more information, see https://discord.com/developers/docs/resources/webhook
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub(crate) struct DiscordWebhookPayload {
+    #[serde(default)]
     pub(crate) content: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) username: Option<String>,
@@ -33,19 +34,19 @@ pub(crate) struct DiscordWebhookPayload {
     // attatchments: PartialAttachment,
 }
 
-#[derive(Serialize, Default)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Components(Vec<Component>);
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Component {
 
 }
 
-#[derive(Serialize, Default)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct EmbedCollection(pub Vec<Embed>);
 
 /// https://discord.com/developers/docs/resources/channel#embed-object
-#[derive(Serialize, Default)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Embed {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
@@ -74,18 +75,18 @@ pub struct Embed {
 }
 
 /// https://discord.com/developers/docs/resources/channel#embed-object-embed-footer-structure
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct EmbedFooter {
-    text: String,
+    pub(crate) text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    icon_url: Option<String>,
+    pub(crate) icon_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    proxy_icon_url: Option<String>
+    pub(crate) proxy_icon_url: Option<String>
 }
 
 //noinspection DuplicatedCode
 /// https://discord.com/developers/docs/resources/channel#embed-object-embed-image-structure
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct EmbedImage {
     url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -98,7 +99,7 @@ pub struct EmbedImage {
 
 //noinspection DuplicatedCode
 /// https://discord.com/developers/docs/resources/channel#embed-object-embed-thumbnail-structure
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct EmbedThumbnail {
     url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -109,7 +110,7 @@ pub struct EmbedThumbnail {
     width: Option<usize>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct EmbedVideo {
     #[serde(skip_serializing_if = "Option::is_none")]
     url: Option<String>,
@@ -122,29 +123,29 @@ pub struct EmbedVideo {
 }
 
 /// https://discord.com/developers/docs/resources/channel#embed-object-embed-author-structure
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct EmbedProvider {
     name: Option<String>,
     url: Option<String>,
 }
 
 /// https://discord.com/developers/docs/resources/channel#embed-object-embed-author-structure
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct EmbedAuthor {
-    name: String,
+    pub(crate) name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    url: Option<String>,
+    pub(crate) url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    icon_url: Option<String>,
+    pub(crate) icon_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    proxy_icon_url: Option<String>,
+    pub(crate) proxy_icon_url: Option<String>,
 }
 
-#[derive(Serialize, Eq, PartialEq, Clone, Hash, Default)]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Hash, Default)]
 pub struct EmbedFields(pub Vec<EmbedField>);
 
 /// https://discord.com/developers/docs/resources/channel#embed-object-embed-field-structure
-#[derive(Serialize, Eq, PartialEq, Clone, Hash)]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Hash)]
 pub struct EmbedField {
     pub(crate) name: String,
     pub(crate) value: String,
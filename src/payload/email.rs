@@ -0,0 +1,129 @@
+use std::fmt::Write;
+use serde::Serialize;
+use crate::payload::discord::{DiscordWebhookPayload, Embed};
+use crate::render::{color, html};
+use crate::render::template::Template;
+
+/// `text/html` part of multipart/alternative message.
+/// Mail clients strip `<style>`, so every style is inlined.
+pub(crate) fn render_html(payload: &DiscordWebhookPayload) -> String {
+    let mut body = String::new();
+    if !payload.content.is_empty() {
+        let _ = write!(body, "<p>{}</p>", html::escape(&payload.content).replace('\n', "<br>"));
+    }
+    for embed in &payload.embeds.0 {
+        body.push_str(&embed_to_html(embed));
+    }
+    format!("<!DOCTYPE html><html><body style=\"font-family:sans-serif\">{body}</body></html>")
+}
+
+fn embed_to_html(embed: &Embed) -> String {
+    // writing into String never fails
+    let mut inner = String::new();
+    if let Some(author) = &embed.author {
+        let _ = write!(inner, "<div style=\"color:#666666;font-size:small\">{}</div>", html::escape(&author.name));
+    }
+    match (&embed.title, &embed.url) {
+        (Some(title), Some(url)) => {
+            let _ = write!(inner, "<h3 style=\"margin:4px 0\"><a href=\"{}\">{}</a></h3>", html::escape(url), html::escape(title));
+        }
+        (Some(title), None) => {
+            let _ = write!(inner, "<h3 style=\"margin:4px 0\">{}</h3>", html::escape(title));
+        }
+        (None, Some(url)) => {
+            let _ = write!(inner, "<p><a href=\"{0}\">{0}</a></p>", html::escape(url));
+        }
+        (None, None) => {}
+    }
+    if let Some(description) = &embed.description {
+        let _ = write!(inner, "<p>{}</p>", html::escape(description).replace('\n', "<br>"));
+    }
+    if !embed.fields.0.is_empty() {
+        inner.push_str("<table style=\"border-collapse:collapse\">");
+        for field in &embed.fields.0 {
+            let _ = write!(
+                inner,
+                "<tr><th style=\"text-align:left;padding:2px 8px 2px 0;vertical-align:top\">{}</th><td style=\"padding:2px 0\">{}</td></tr>",
+                html::escape(&field.name),
+                html::escape(&field.value).replace('\n', "<br>")
+            );
+        }
+        inner.push_str("</table>");
+    }
+    if let Some(footer) = &embed.footer {
+        let _ = write!(inner, "<div style=\"color:#666666;font-size:small;margin-top:8px\">{}</div>", html::escape(&footer.text));
+    }
+    let border = embed.color.map_or_else(|| "#CCCCCC".to_string(), color::hex);
+    format!("<div style=\"border-left:4px solid {border};padding:4px 12px;margin:8px 0\">{inner}</div>")
+}
+
+/// variables passed to the subject template. `title` and `url` are those of the first embed.
+#[derive(Serialize)]
+struct SubjectContext<'a> {
+    title: Option<&'a str>,
+    url: Option<&'a str>,
+    content: &'a str,
+    username: Option<&'a str>,
+    source: &'a str,
+}
+
+/// renders the subject with a Jinja2 template, e.g. `[Todoist] {{ title }}`
+pub(crate) fn render_subject(template: &Template, payload: &DiscordWebhookPayload, source: &str) -> anyhow::Result<String> {
+    let embed = payload.embeds.0.first();
+    let context = SubjectContext {
+        title: embed.and_then(|embed| embed.title.as_deref()),
+        url: embed.and_then(|embed| embed.url.as_deref()),
+        content: &payload.content,
+        username: payload.username.as_deref(),
+        source,
+    };
+    let subject = template.render(context)?;
+    // header injection guard: subject must be a single line
+    Ok(subject.lines().collect::<Vec<_>>().join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::summary::{Service, Summary};
+
+    const SERVICE: Service = Service {
+        name: "Todoist",
+        avatar_url: "https://example.com/avatar.png",
+    };
+
+    fn payload(title: &str) -> DiscordWebhookPayload {
+        Summary {
+            title: title.to_string(),
+            url: "https://todoist.com/showTask?id=1".to_string(),
+            description: None,
+            color: 0,
+            fields: vec![],
+        }.into_webhook(&SERVICE, None, "item:added".to_string())
+    }
+
+    #[test]
+    fn subject_is_rendered_with_minijinja() {
+        let subject = render_subject(&Template::built_in("[{{ username }}] {{ title }} <{{ url }}>"), &payload("Buy milk"), "todoist").unwrap();
+        assert_eq!(subject, "[Todoist] Buy milk <https://todoist.com/showTask?id=1>");
+    }
+
+    #[test]
+    fn missing_values_are_empty() {
+        let mut payload = payload("Buy milk");
+        payload.embeds.0.clear();
+        let subject = render_subject(&Template::built_in("[Todoist] {{ title }}{{ content }}"), &payload, "todoist").unwrap();
+        assert_eq!(subject, "[Todoist] ");
+    }
+
+    #[test]
+    fn subject_is_a_single_line() {
+        let subject = render_subject(&Template::built_in("{{ title }}"), &payload("Buy milk\nBcc: victim@example.com"), "todoist").unwrap();
+        assert_eq!(subject, "Buy milk Bcc: victim@example.com");
+    }
+
+    #[test]
+    fn invalid_template_is_an_error() {
+        assert!(Template::new("{{ title").is_err());
+    }
+}
@@ -0,0 +1,68 @@
+use minijinja::context;
+use serde::Serialize;
+use crate::render::color;
+use crate::payload::todoist::TodoistPriority;
+
+/// an event normalized into a shape independent of the source service.
+/// it is forwarded as is to sinks other than chats (e.g. your own HTTP service).
+#[derive(Serialize, Clone)]
+pub(crate) struct NormalizedEvent {
+    /// e.g. `todoist`
+    pub(crate) source: &'static str,
+    /// event name defined by the source, e.g. `item:completed`
+    pub(crate) event: String,
+    pub(crate) title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) project: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) priority: Option<TodoistPriority>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) labels: Vec<String>,
+    /// who caused the event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) initiator: Option<String>,
+    /// RFC 3339 timestamp when this proxy received the event
+    pub(crate) received_at: String,
+}
+
+impl NormalizedEvent {
+    /// variables referred to from templates and filter expressions. Missing values become `none`.
+    pub(crate) fn template_context(&self) -> minijinja::Value {
+        context! {
+            source => self.source,
+            event => &self.event,
+            title => &self.title,
+            body => &self.body,
+            url => &self.url,
+            project => &self.project,
+            priority => self.priority.map(|priority| priority.to_string()),
+            priority_color => self.priority.map(|priority| color::hex(priority.color())),
+            labels => &self.labels,
+            initiator => &self.initiator,
+            received_at => &self.received_at,
+        }
+    }
+}
+
+#[cfg(test)]
+impl NormalizedEvent {
+    /// an event for tests
+    pub(crate) fn example() -> Self {
+        Self {
+            source: "todoist",
+            event: "item:completed".to_string(),
+            title: "Buy milk".to_string(),
+            body: None,
+            url: Some("https://todoist.com/showTask?id=1".to_string()),
+            project: Some("Inbox".to_string()),
+            priority: Some(TodoistPriority::P1),
+            labels: vec!["errand".to_string()],
+            initiator: Some("Alice".to_string()),
+            received_at: "2026-10-19T00:00:00+00:00".to_string(),
+        }
+    }
+}
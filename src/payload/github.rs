@@ -0,0 +1,667 @@
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::event::NormalizedEvent;
+use crate::render::color::{BLUE, GREEN, GREY, PURPLE, RED, YELLOW};
+use crate::render::git::{branch_name, commit_list, short_sha, CommitLine};
+use crate::render::summary::{field, Actor, Service, Summary};
+
+pub(crate) const GITHUB: Service = Service {
+    name: "GitHub",
+    avatar_url: "https://github.githubassets.com/images/modules/logos_page/GitHub-Mark.png",
+};
+
+/// a webhook event whose kind is given by the `X-GitHub-Event` header.
+/// for more information, see https://docs.github.com/en/webhooks/webhook-events-and-payloads
+#[derive(Clone)]
+pub(crate) enum GitHubEvent {
+    Push(PushEvent),
+    PullRequest(PullRequestEvent),
+    Issues(IssuesEvent),
+    IssueComment(IssueCommentEvent),
+    Release(ReleaseEvent),
+    WorkflowRun(WorkflowRunEvent),
+    CheckSuite(CheckSuiteEvent),
+    Status(StatusEvent),
+    /// e.g. `ping`, which is sent when the webhook is created
+    Other { name: String, common: Common },
+}
+
+impl GitHubEvent {
+    pub(crate) fn parse(name: &str, raw: serde_json::Value) -> serde_json::Result<Self> {
+        Ok(match name {
+            "push" => Self::Push(serde_json::from_value(raw)?),
+            "pull_request" => Self::PullRequest(serde_json::from_value(raw)?),
+            "issues" => Self::Issues(serde_json::from_value(raw)?),
+            "issue_comment" => Self::IssueComment(serde_json::from_value(raw)?),
+            "release" => Self::Release(serde_json::from_value(raw)?),
+            "workflow_run" => Self::WorkflowRun(serde_json::from_value(raw)?),
+            "check_suite" => Self::CheckSuite(serde_json::from_value(raw)?),
+            "status" => Self::Status(serde_json::from_value(raw)?),
+            _ => Self::Other { name: name.to_string(), common: serde_json::from_value(raw)? },
+        })
+    }
+
+    const fn common(&self) -> &Common {
+        match self {
+            Self::Push(event) => &event.common,
+            Self::PullRequest(event) => &event.common,
+            Self::Issues(event) => &event.common,
+            Self::IssueComment(event) => &event.common,
+            Self::Release(event) => &event.common,
+            Self::WorkflowRun(event) => &event.common,
+            Self::CheckSuite(event) => &event.common,
+            Self::Status(event) => &event.common,
+            Self::Other { common, .. } => common,
+        }
+    }
+
+    /// e.g. `push`, `pull_request.opened`
+    pub(crate) fn name(&self) -> String {
+        let with_action = |name: &str, action: &str| format!("{name}.{action}");
+        match self {
+            Self::Push(_) => "push".to_string(),
+            Self::PullRequest(event) => with_action("pull_request", &event.action),
+            Self::Issues(event) => with_action("issues", &event.action),
+            Self::IssueComment(event) => with_action("issue_comment", &event.action),
+            Self::Release(event) => with_action("release", &event.action),
+            Self::WorkflowRun(event) => with_action("workflow_run", &event.action),
+            Self::CheckSuite(event) => with_action("check_suite", &event.action),
+            Self::Status(event) => with_action("status", &event.state),
+            Self::Other { name, .. } => name.clone(),
+        }
+    }
+}
+
+/// fields included in every event
+#[derive(Deserialize, Clone)]
+pub(crate) struct Common {
+    /// absent on some organization-level events
+    #[serde(default)]
+    pub(crate) repository: Option<Repository>,
+    pub(crate) sender: User,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Repository {
+    pub(crate) full_name: String,
+    pub(crate) html_url: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct User {
+    pub(crate) login: String,
+    /// absent on older Gitea
+    #[serde(default)]
+    pub(crate) html_url: Option<String>,
+    #[serde(default)]
+    pub(crate) avatar_url: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Label {
+    pub(crate) name: String,
+}
+
+/// https://docs.github.com/en/webhooks/webhook-events-and-payloads#push
+#[derive(Deserialize, Clone)]
+pub(crate) struct PushEvent {
+    /// e.g. `refs/heads/main`
+    #[serde(rename = "ref")]
+    pub(crate) git_ref: String,
+    #[serde(alias = "compare_url")]
+    pub(crate) compare: String,
+    #[serde(default)]
+    pub(crate) created: bool,
+    #[serde(default)]
+    pub(crate) deleted: bool,
+    #[serde(default)]
+    pub(crate) forced: bool,
+    #[serde(default)]
+    pub(crate) commits: Vec<Commit>,
+    /// Gitea only; the number of commits may exceed `commits`
+    #[serde(default)]
+    pub(crate) total_commits: Option<usize>,
+    #[serde(flatten)]
+    pub(crate) common: Common,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Commit {
+    pub(crate) id: String,
+    pub(crate) message: String,
+    pub(crate) url: String,
+    pub(crate) author: CommitAuthor,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct CommitAuthor {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) username: Option<String>,
+}
+
+/// https://docs.github.com/en/webhooks/webhook-events-and-payloads#pull_request
+#[derive(Deserialize, Clone)]
+pub(crate) struct PullRequestEvent {
+    pub(crate) action: String,
+    pub(crate) pull_request: PullRequest,
+    #[serde(flatten)]
+    pub(crate) common: Common,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct PullRequest {
+    pub(crate) number: u64,
+    pub(crate) html_url: String,
+    pub(crate) title: String,
+    #[serde(default)]
+    pub(crate) body: Option<String>,
+    #[serde(default)]
+    pub(crate) merged: bool,
+    #[serde(default)]
+    pub(crate) draft: bool,
+    pub(crate) head: Branch,
+    pub(crate) base: Branch,
+    #[serde(default)]
+    pub(crate) labels: Vec<Label>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Branch {
+    #[serde(rename = "ref")]
+    pub(crate) git_ref: String,
+}
+
+/// https://docs.github.com/en/webhooks/webhook-events-and-payloads#issues
+#[derive(Deserialize, Clone)]
+pub(crate) struct IssuesEvent {
+    pub(crate) action: String,
+    pub(crate) issue: Issue,
+    #[serde(flatten)]
+    pub(crate) common: Common,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Issue {
+    pub(crate) number: u64,
+    pub(crate) html_url: String,
+    pub(crate) title: String,
+    #[serde(default)]
+    pub(crate) body: Option<String>,
+    #[serde(default)]
+    pub(crate) labels: Vec<Label>,
+}
+
+/// https://docs.github.com/en/webhooks/webhook-events-and-payloads#issue_comment
+#[derive(Deserialize, Clone)]
+pub(crate) struct IssueCommentEvent {
+    pub(crate) action: String,
+    pub(crate) issue: Issue,
+    pub(crate) comment: Comment,
+    #[serde(flatten)]
+    pub(crate) common: Common,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Comment {
+    pub(crate) html_url: String,
+    pub(crate) body: String,
+}
+
+/// https://docs.github.com/en/webhooks/webhook-events-and-payloads#release
+#[derive(Deserialize, Clone)]
+pub(crate) struct ReleaseEvent {
+    pub(crate) action: String,
+    pub(crate) release: Release,
+    #[serde(flatten)]
+    pub(crate) common: Common,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Release {
+    pub(crate) tag_name: String,
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    pub(crate) html_url: String,
+    #[serde(default)]
+    pub(crate) body: Option<String>,
+    #[serde(default)]
+    pub(crate) prerelease: bool,
+}
+
+/// https://docs.github.com/en/webhooks/webhook-events-and-payloads#workflow_run
+#[derive(Deserialize, Clone)]
+pub(crate) struct WorkflowRunEvent {
+    pub(crate) action: String,
+    pub(crate) workflow_run: WorkflowRun,
+    #[serde(flatten)]
+    pub(crate) common: Common,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct WorkflowRun {
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    pub(crate) html_url: String,
+    #[serde(default)]
+    pub(crate) head_branch: Option<String>,
+    pub(crate) run_number: u64,
+    /// what triggered the run, e.g. `push`
+    pub(crate) event: String,
+    #[serde(default)]
+    pub(crate) conclusion: Option<String>,
+}
+
+/// https://docs.github.com/en/webhooks/webhook-events-and-payloads#check_suite
+#[derive(Deserialize, Clone)]
+pub(crate) struct CheckSuiteEvent {
+    pub(crate) action: String,
+    pub(crate) check_suite: CheckSuite,
+    #[serde(flatten)]
+    pub(crate) common: Common,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct CheckSuite {
+    pub(crate) head_sha: String,
+    #[serde(default)]
+    pub(crate) head_branch: Option<String>,
+    #[serde(default)]
+    pub(crate) conclusion: Option<String>,
+    #[serde(default)]
+    pub(crate) app: Option<App>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct App {
+    pub(crate) name: String,
+}
+
+/// commit status reported by CI. Gitea uses this for its pipelines.
+/// https://docs.github.com/en/webhooks/webhook-events-and-payloads#status
+#[derive(Deserialize, Clone)]
+pub(crate) struct StatusEvent {
+    pub(crate) sha: String,
+    /// `pending`, `success`, `failure` or `error`
+    pub(crate) state: String,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    #[serde(default)]
+    pub(crate) target_url: Option<String>,
+    /// e.g. `ci/woodpecker/push/build`
+    pub(crate) context: String,
+    #[serde(flatten)]
+    pub(crate) common: Common,
+}
+
+/// colour for a conclusion such as `success`
+fn conclusion_color(conclusion: Option<&str>) -> u32 {
+    match conclusion {
+        Some("success") => GREEN,
+        Some("failure" | "error" | "timed_out" | "startup_failure") => RED,
+        Some("action_required" | "pending") => YELLOW,
+        _ => GREY,
+    }
+}
+
+fn action_color(action: &str) -> u32 {
+    match action {
+        "opened" | "reopened" | "created" | "published" => GREEN,
+        "closed" | "deleted" => RED,
+        _ => BLUE,
+    }
+}
+
+fn labels(labels: &[Label]) -> Vec<String> {
+    labels.iter().map(|label| label.name.clone()).collect()
+}
+
+fn push(repository_name: &str, push: &PushEvent) -> Summary {
+    let branch = branch_name(&push.git_ref);
+    let total = push.total_commits.unwrap_or(push.commits.len());
+    let (title, color) = if push.deleted {
+        (format!("[{repository_name}] {branch} deleted"), RED)
+    } else if push.created && total == 0 {
+        (format!("[{repository_name}] {branch} created"), GREEN)
+    } else {
+        let plural = if total == 1 { "" } else { "s" };
+        let forced = if push.forced { " (force-pushed)" } else { "" };
+        (format!("[{repository_name}:{branch}] {total} new commit{plural}{forced}"), if push.forced { RED } else { BLUE })
+    };
+    let commits = push.commits.iter().map(|commit| CommitLine {
+        id: &commit.id,
+        message: &commit.message,
+        url: &commit.url,
+        author: commit.author.username.as_deref().filter(|username| !username.is_empty()).unwrap_or(&commit.author.name),
+    }).collect::<Vec<_>>();
+    Summary {
+        title,
+        url: push.compare.clone(),
+        description: Some(commit_list(&commits, total)).filter(|list| !list.is_empty()),
+        color,
+        fields: vec![],
+    }
+}
+
+fn pull_request(repository_name: &str, event: &PullRequestEvent) -> Summary {
+    let pull_request = &event.pull_request;
+    let (action, color) = match event.action.as_str() {
+        "closed" if pull_request.merged => ("merged", PURPLE),
+        action => (action, if pull_request.draft { GREY } else { action_color(action) }),
+    };
+    Summary {
+        title: format!("[{repository_name}] Pull request {action}: #{} {}", pull_request.number, pull_request.title),
+        url: pull_request.html_url.clone(),
+        description: pull_request.body.clone(),
+        color,
+        fields: vec![field("Branch", format!("{} ← {}", pull_request.base.git_ref, pull_request.head.git_ref))],
+    }
+}
+
+fn run(repository_name: &str, event: &WorkflowRunEvent) -> Summary {
+    let run = &event.workflow_run;
+    let state = run.conclusion.as_deref().unwrap_or(&event.action);
+    let mut fields = vec![field("Trigger", run.event.clone())];
+    if let Some(branch) = &run.head_branch {
+        fields.push(field("Branch", branch.clone()));
+    }
+    Summary {
+        title: format!("[{repository_name}] {} #{} {state}", run.name.as_deref().unwrap_or("Workflow"), run.run_number),
+        url: run.html_url.clone(),
+        description: None,
+        color: conclusion_color(run.conclusion.as_deref()),
+        fields,
+    }
+}
+
+fn check_suite(repository_name: &str, repository_url: &str, event: &CheckSuiteEvent) -> Summary {
+    let suite = &event.check_suite;
+    let app = suite.app.as_ref().map_or("Check suite", |app| &app.name);
+    let state = suite.conclusion.as_deref().unwrap_or(&event.action);
+    let mut fields = vec![field("Commit", short_sha(&suite.head_sha))];
+    if let Some(branch) = &suite.head_branch {
+        fields.push(field("Branch", branch.clone()));
+    }
+    Summary {
+        title: format!("[{repository_name}] {app} {state}"),
+        url: format!("{repository_url}/commit/{}/checks", suite.head_sha),
+        description: None,
+        color: conclusion_color(suite.conclusion.as_deref()),
+        fields,
+    }
+}
+
+impl From<&GitHubEvent> for Summary {
+    fn from(event: &GitHubEvent) -> Self {
+        let repository = event.common().repository.as_ref();
+        let repository_name = repository.map_or("GitHub", |repository| &repository.full_name);
+        let repository_url = repository.map_or("https://github.com", |repository| &repository.html_url);
+
+        match event {
+            GitHubEvent::Push(event) => push(repository_name, event),
+            GitHubEvent::PullRequest(event) => pull_request(repository_name, event),
+            GitHubEvent::Issues(event) => Self {
+                title: format!("[{repository_name}] Issue {}: #{} {}", event.action, event.issue.number, event.issue.title),
+                url: event.issue.html_url.clone(),
+                description: event.issue.body.clone(),
+                color: action_color(&event.action),
+                fields: vec![],
+            },
+            GitHubEvent::IssueComment(event) => Self {
+                title: format!("[{repository_name}] Comment {} on #{} {}", event.action, event.issue.number, event.issue.title),
+                url: event.comment.html_url.clone(),
+                description: Some(event.comment.body.clone()),
+                color: action_color(&event.action),
+                fields: vec![],
+            },
+            GitHubEvent::Release(event) => {
+                let release = &event.release;
+                let name = release.name.as_deref().filter(|name| !name.is_empty()).unwrap_or(&release.tag_name);
+                let prerelease = if release.prerelease { "Pre-release" } else { "Release" };
+                Self {
+                    title: format!("[{repository_name}] {prerelease} {}: {name}", event.action),
+                    url: release.html_url.clone(),
+                    description: release.body.clone(),
+                    color: action_color(&event.action),
+                    fields: vec![field("Tag", release.tag_name.clone())],
+                }
+            }
+            GitHubEvent::WorkflowRun(event) => run(repository_name, event),
+            GitHubEvent::CheckSuite(event) => check_suite(repository_name, repository_url, event),
+            GitHubEvent::Status(event) => Self {
+                title: format!("[{repository_name}] {} {}", event.context, event.state),
+                url: event.target_url.clone().unwrap_or_else(|| format!("{repository_url}/commit/{}", event.sha)),
+                description: event.description.clone(),
+                color: conclusion_color(Some(&event.state)),
+                fields: vec![field("Commit", short_sha(&event.sha))],
+            },
+            GitHubEvent::Other { name, .. } => Self {
+                title: format!("[{repository_name}] {name}"),
+                url: repository_url.to_string(),
+                description: None,
+                color: GREY,
+                fields: vec![],
+            },
+        }
+    }
+}
+
+impl GitHubEvent {
+    /// `service` is either GitHub or a compatible one such as Gitea
+    pub(crate) fn to_webhook(&self, service: &Service) -> DiscordWebhookPayload {
+        let sender = &self.common().sender;
+        let actor = Actor {
+            name: sender.login.clone(),
+            url: sender.html_url.clone(),
+            avatar_url: sender.avatar_url.clone(),
+        };
+        Summary::from(self).into_webhook(service, Some(actor), self.name())
+    }
+
+    /// `source` is either `github` or a compatible one such as `gitea`
+    pub(crate) fn to_event(&self, source: &'static str) -> NormalizedEvent {
+        let common = self.common();
+        let labels = match self {
+            Self::PullRequest(event) => labels(&event.pull_request.labels),
+            Self::Issues(event) => labels(&event.issue.labels),
+            Self::IssueComment(event) => labels(&event.issue.labels),
+            _ => vec![],
+        };
+        let summary = Summary::from(self);
+        NormalizedEvent {
+            source,
+            event: self.name(),
+            title: summary.title,
+            body: summary.description,
+            url: Some(summary.url),
+            project: common.repository.as_ref().map(|repository| repository.full_name.clone()),
+            priority: None,
+            labels,
+            initiator: Some(common.sender.login.clone()),
+            received_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use super::*;
+
+    /// `repository` and `sender` shared by the captured payloads
+    fn payload(event: Value) -> Value {
+        let mut payload = json!({
+            "repository": {"full_name": "octocat/Hello-World", "html_url": "https://github.com/octocat/Hello-World", "private": false},
+            "sender": {"login": "octocat", "id": 1, "html_url": "https://github.com/octocat", "avatar_url": "https://github.com/images/error/octocat_happy.gif", "type": "User"},
+        });
+        if let (Value::Object(payload), Value::Object(event)) = (&mut payload, event) {
+            payload.extend(event);
+        }
+        payload
+    }
+
+    fn summary(name: &str, event: Value) -> (String, Summary) {
+        let event = GitHubEvent::parse(name, payload(event)).unwrap();
+        (event.name(), Summary::from(&event))
+    }
+
+    #[test]
+    fn push_is_parsed() {
+        let (name, summary) = summary("push", json!({
+            "ref": "refs/heads/main",
+            "before": "6113728f27ae82c7b1a177c8d03f9e96e0adf246",
+            "after": "0000000000000000000000000000000000000001",
+            "created": false,
+            "deleted": false,
+            "forced": false,
+            "compare": "https://github.com/octocat/Hello-World/compare/6113728f27ae...000000000001",
+            "commits": [{
+                "id": "0000000000000000000000000000000000000001",
+                "message": "Fix all the bugs",
+                "timestamp": "2024-01-01T00:00:00Z",
+                "url": "https://github.com/octocat/Hello-World/commit/0000000000000000000000000000000000000001",
+                "author": {"name": "The Octocat", "email": "octocat@github.com", "username": "octocat"},
+            }],
+        }));
+        assert_eq!(name, "push");
+        assert_eq!(summary.title, "[octocat/Hello-World:main] 1 new commit");
+        assert_eq!(summary.url, "https://github.com/octocat/Hello-World/compare/6113728f27ae...000000000001");
+        assert!(summary.description.unwrap().contains("Fix all the bugs"));
+    }
+
+    #[test]
+    fn pull_request_is_parsed() {
+        let (name, summary) = summary("pull_request", json!({
+            "action": "closed",
+            "number": 1347,
+            "pull_request": {
+                "number": 1347,
+                "html_url": "https://github.com/octocat/Hello-World/pull/1347",
+                "state": "closed",
+                "title": "Amazing new feature",
+                "body": "Please pull these awesome changes in!",
+                "draft": false,
+                "merged": true,
+                "labels": [{"name": "bug", "color": "f29513"}],
+                "head": {"label": "octocat:new-topic", "ref": "new-topic", "sha": "6dcb09b5b57875f334f61aebed695e2e4193db5e"},
+                "base": {"label": "octocat:master", "ref": "master", "sha": "6dcb09b5b57875f334f61aebed695e2e4193db5e"},
+            },
+        }));
+        assert_eq!(name, "pull_request.closed");
+        assert_eq!(summary.title, "[octocat/Hello-World] Pull request merged: #1347 Amazing new feature");
+        assert_eq!(summary.color, PURPLE);
+        assert_eq!(summary.fields[0].value, "master ← new-topic");
+    }
+
+    #[test]
+    fn issues_is_parsed() {
+        let event = GitHubEvent::parse("issues", payload(json!({
+            "action": "opened",
+            "issue": {
+                "number": 1347,
+                "html_url": "https://github.com/octocat/Hello-World/issues/1347",
+                "title": "Found a bug",
+                "body": "I'm having a problem with this.",
+                "state": "open",
+                "labels": [{"name": "bug", "color": "f29513"}],
+            },
+        }))).unwrap();
+        assert_eq!(event.name(), "issues.opened");
+        let normalized = event.to_event("github");
+        assert_eq!(normalized.title, "[octocat/Hello-World] Issue opened: #1347 Found a bug");
+        assert_eq!(normalized.labels, ["bug"]);
+        assert_eq!(normalized.project.as_deref(), Some("octocat/Hello-World"));
+        assert_eq!(normalized.initiator.as_deref(), Some("octocat"));
+    }
+
+    #[test]
+    fn issue_comment_is_parsed() {
+        let (name, summary) = summary("issue_comment", json!({
+            "action": "created",
+            "issue": {
+                "number": 1347,
+                "html_url": "https://github.com/octocat/Hello-World/issues/1347",
+                "title": "Found a bug",
+                "body": "I'm having a problem with this.",
+            },
+            "comment": {
+                "id": 1,
+                "html_url": "https://github.com/octocat/Hello-World/issues/1347#issuecomment-1",
+                "body": "Me too",
+            },
+        }));
+        assert_eq!(name, "issue_comment.created");
+        assert_eq!(summary.title, "[octocat/Hello-World] Comment created on #1347 Found a bug");
+        assert_eq!(summary.url, "https://github.com/octocat/Hello-World/issues/1347#issuecomment-1");
+        assert_eq!(summary.description.as_deref(), Some("Me too"));
+    }
+
+    #[test]
+    fn release_is_parsed() {
+        let (name, summary) = summary("release", json!({
+            "action": "published",
+            "release": {
+                "id": 1,
+                "tag_name": "v1.0.0",
+                "target_commitish": "master",
+                "name": null,
+                "html_url": "https://github.com/octocat/Hello-World/releases/v1.0.0",
+                "body": "Description of the release",
+                "draft": false,
+                "prerelease": true,
+            },
+        }));
+        assert_eq!(name, "release.published");
+        assert_eq!(summary.title, "[octocat/Hello-World] Pre-release published: v1.0.0");
+        assert_eq!(summary.fields[0].value, "v1.0.0");
+    }
+
+    #[test]
+    fn workflow_run_is_parsed() {
+        let (name, summary) = summary("workflow_run", json!({
+            "action": "completed",
+            "workflow_run": {
+                "name": "Build",
+                "head_branch": "main",
+                "head_sha": "acb5820ced9479c074f688cc328bf03f341a511d",
+                "run_number": 562,
+                "event": "push",
+                "status": "completed",
+                "conclusion": "failure",
+                "html_url": "https://github.com/octocat/Hello-World/actions/runs/30433642",
+            },
+        }));
+        assert_eq!(name, "workflow_run.completed");
+        assert_eq!(summary.title, "[octocat/Hello-World] Build #562 failure");
+        assert_eq!(summary.color, RED);
+    }
+
+    #[test]
+    fn check_suite_is_parsed() {
+        let (name, summary) = summary("check_suite", json!({
+            "action": "completed",
+            "check_suite": {
+                "head_branch": "changes",
+                "head_sha": "ec26c3e57ca3a959ca5aad62de7213c562f8c821",
+                "status": "completed",
+                "conclusion": "success",
+                "app": {"id": 29310, "slug": "octoapps", "name": "Octocat App"},
+            },
+        }));
+        assert_eq!(name, "check_suite.completed");
+        assert_eq!(summary.title, "[octocat/Hello-World] Octocat App success");
+        assert_eq!(summary.url, "https://github.com/octocat/Hello-World/commit/ec26c3e57ca3a959ca5aad62de7213c562f8c821/checks");
+    }
+
+    #[test]
+    fn unknown_event_keeps_its_name() {
+        let (name, summary) = summary("ping", json!({"zen": "Keep it logically awesome.", "hook_id": 1}));
+        assert_eq!(name, "ping");
+        assert_eq!(summary.title, "[octocat/Hello-World] ping");
+    }
+
+    #[test]
+    fn missing_required_field_is_an_error() {
+        assert!(GitHubEvent::parse("pull_request", payload(json!({"action": "opened"}))).is_err());
+    }
+}
@@ -0,0 +1,579 @@
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::event::NormalizedEvent;
+use crate::render::color::{BLUE, GREEN, GREY, PURPLE, RED, YELLOW};
+use crate::render::git::{branch_name, commit_list, short_sha, CommitLine};
+use crate::render::summary::{field, Actor, Service, Summary};
+
+pub(crate) const GITLAB: Service = Service {
+    name: "GitLab",
+    avatar_url: "https://about.gitlab.com/images/press/logo/png/gitlab-icon-rgb.png",
+};
+
+/// `before` / `after` of a push which creates / deletes a branch
+const NULL_SHA: &str = "0000000000000000000000000000000000000000";
+
+/// the kind is given by `object_kind` in the body.
+/// for more information, see https://docs.gitlab.com/ee/user/project/integrations/webhook_events.html
+#[derive(Clone)]
+pub(crate) enum GitLabEvent {
+    Push(PushEvent),
+    TagPush(PushEvent),
+    MergeRequest(MergeRequestEvent),
+    Issue(IssueEvent),
+    Note(NoteEvent),
+    Pipeline(PipelineEvent),
+    Other { kind: String, common: Common },
+}
+
+impl GitLabEvent {
+    pub(crate) fn parse(raw: serde_json::Value) -> serde_json::Result<Self> {
+        let kind = raw.get("object_kind").and_then(serde_json::Value::as_str).unwrap_or_default().to_string();
+        Ok(match kind.as_str() {
+            "push" => Self::Push(serde_json::from_value(raw)?),
+            "tag_push" => Self::TagPush(serde_json::from_value(raw)?),
+            "merge_request" => Self::MergeRequest(serde_json::from_value(raw)?),
+            "issue" => Self::Issue(serde_json::from_value(raw)?),
+            "note" => Self::Note(serde_json::from_value(raw)?),
+            "pipeline" => Self::Pipeline(serde_json::from_value(raw)?),
+            _ => Self::Other { common: serde_json::from_value(raw)?, kind },
+        })
+    }
+
+    const fn project(&self) -> &Project {
+        match self {
+            Self::Push(event) | Self::TagPush(event) => &event.project,
+            Self::MergeRequest(event) => &event.common.project,
+            Self::Issue(event) => &event.common.project,
+            Self::Note(event) => &event.common.project,
+            Self::Pipeline(event) => &event.common.project,
+            Self::Other { common, .. } => &common.project,
+        }
+    }
+
+    fn actor(&self) -> Actor {
+        let user = match self {
+            Self::Push(event) | Self::TagPush(event) => {
+                return Actor {
+                    name: event.user_username.clone().unwrap_or_else(|| event.user_name.clone()),
+                    url: None,
+                    avatar_url: event.user_avatar.clone(),
+                };
+            }
+            Self::MergeRequest(event) => &event.common.user,
+            Self::Issue(event) => &event.common.user,
+            Self::Note(event) => &event.common.user,
+            Self::Pipeline(event) => &event.common.user,
+            Self::Other { common, .. } => &common.user,
+        };
+        Actor {
+            name: user.username.clone(),
+            url: None,
+            avatar_url: user.avatar_url.clone(),
+        }
+    }
+
+    /// e.g. `push`, `merge_request.open`
+    fn name(&self) -> String {
+        match self {
+            Self::Push(_) => "push".to_string(),
+            Self::TagPush(_) => "tag_push".to_string(),
+            Self::MergeRequest(event) => format!("merge_request.{}", event.object_attributes.action.as_deref().unwrap_or("update")),
+            Self::Issue(event) => format!("issue.{}", event.object_attributes.action.as_deref().unwrap_or("update")),
+            Self::Note(event) => format!("note.{}", event.object_attributes.noteable_type),
+            Self::Pipeline(event) => format!("pipeline.{}", event.object_attributes.status),
+            Self::Other { kind, .. } => kind.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Common {
+    pub(crate) user: User,
+    pub(crate) project: Project,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct User {
+    pub(crate) username: String,
+    #[serde(default)]
+    pub(crate) avatar_url: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Project {
+    pub(crate) path_with_namespace: String,
+    pub(crate) web_url: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Label {
+    pub(crate) title: String,
+}
+
+/// https://docs.gitlab.com/ee/user/project/integrations/webhook_events.html#push-events
+#[derive(Deserialize, Clone)]
+pub(crate) struct PushEvent {
+    pub(crate) before: String,
+    pub(crate) after: String,
+    #[serde(rename = "ref")]
+    pub(crate) git_ref: String,
+    pub(crate) user_name: String,
+    #[serde(default)]
+    pub(crate) user_username: Option<String>,
+    #[serde(default)]
+    pub(crate) user_avatar: Option<String>,
+    pub(crate) project: Project,
+    /// at most 20 commits
+    #[serde(default)]
+    pub(crate) commits: Vec<Commit>,
+    pub(crate) total_commits_count: usize,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Commit {
+    pub(crate) id: String,
+    pub(crate) message: String,
+    pub(crate) url: String,
+    pub(crate) author: CommitAuthor,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct CommitAuthor {
+    pub(crate) name: String,
+}
+
+/// https://docs.gitlab.com/ee/user/project/integrations/webhook_events.html#merge-request-events
+#[derive(Deserialize, Clone)]
+pub(crate) struct MergeRequestEvent {
+    pub(crate) object_attributes: MergeRequest,
+    #[serde(default)]
+    pub(crate) labels: Vec<Label>,
+    #[serde(flatten)]
+    pub(crate) common: Common,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct MergeRequest {
+    pub(crate) iid: u64,
+    pub(crate) title: String,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    pub(crate) url: String,
+    /// `open`, `close`, `reopen`, `update`, `approved`, `unapproved`, `merge` etc.
+    #[serde(default)]
+    pub(crate) action: Option<String>,
+    pub(crate) source_branch: String,
+    pub(crate) target_branch: String,
+    #[serde(default)]
+    pub(crate) draft: bool,
+}
+
+/// https://docs.gitlab.com/ee/user/project/integrations/webhook_events.html#issue-events
+#[derive(Deserialize, Clone)]
+pub(crate) struct IssueEvent {
+    pub(crate) object_attributes: Issue,
+    #[serde(default)]
+    pub(crate) labels: Vec<Label>,
+    #[serde(flatten)]
+    pub(crate) common: Common,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Issue {
+    pub(crate) iid: u64,
+    pub(crate) title: String,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    pub(crate) url: String,
+    /// `open`, `close`, `reopen` or `update`
+    #[serde(default)]
+    pub(crate) action: Option<String>,
+}
+
+/// https://docs.gitlab.com/ee/user/project/integrations/webhook_events.html#comment-events
+#[derive(Deserialize, Clone)]
+pub(crate) struct NoteEvent {
+    pub(crate) object_attributes: Note,
+    #[serde(default)]
+    pub(crate) merge_request: Option<Noteable>,
+    #[serde(default)]
+    pub(crate) issue: Option<Noteable>,
+    #[serde(default)]
+    pub(crate) commit: Option<NotedCommit>,
+    #[serde(flatten)]
+    pub(crate) common: Common,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Note {
+    #[serde(rename = "note")]
+    pub(crate) body: String,
+    /// `Commit`, `MergeRequest`, `Issue` or `Snippet`
+    pub(crate) noteable_type: String,
+    pub(crate) url: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Noteable {
+    pub(crate) iid: u64,
+    pub(crate) title: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct NotedCommit {
+    pub(crate) id: String,
+}
+
+/// https://docs.gitlab.com/ee/user/project/integrations/webhook_events.html#pipeline-events
+#[derive(Deserialize, Clone)]
+pub(crate) struct PipelineEvent {
+    pub(crate) object_attributes: Pipeline,
+    #[serde(flatten)]
+    pub(crate) common: Common,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Pipeline {
+    pub(crate) id: u64,
+    #[serde(rename = "ref")]
+    pub(crate) git_ref: String,
+    pub(crate) sha: String,
+    /// e.g. `success`, `failed`, `running`
+    pub(crate) status: String,
+    #[serde(default)]
+    pub(crate) duration: Option<u64>,
+    /// absent on older GitLab
+    #[serde(default)]
+    pub(crate) url: Option<String>,
+}
+
+fn action_color(action: Option<&str>) -> u32 {
+    match action {
+        Some("open" | "reopen") => GREEN,
+        Some("close") => RED,
+        Some("merge") => PURPLE,
+        _ => BLUE,
+    }
+}
+
+/// `open` -> `opened`
+fn past_tense(action: Option<&str>) -> &str {
+    match action {
+        Some("open") => "opened",
+        Some("close") => "closed",
+        Some("reopen") => "reopened",
+        Some("merge") => "merged",
+        Some("update") | None => "updated",
+        Some(action) => action,
+    }
+}
+
+fn push(project: &Project, push: &PushEvent, tag: bool) -> Summary {
+    let name = branch_name(&push.git_ref);
+    let kind = if tag { "tag" } else { "branch" };
+    let (title, url, color) = if push.after == NULL_SHA {
+        (format!("[{}] {kind} {name} deleted", project.path_with_namespace), project.web_url.clone(), RED)
+    } else if push.before == NULL_SHA {
+        (format!("[{}] {kind} {name} created", project.path_with_namespace), format!("{}/-/tree/{name}", project.web_url), GREEN)
+    } else {
+        let total = push.total_commits_count;
+        let plural = if total == 1 { "" } else { "s" };
+        (
+            format!("[{}:{name}] {total} new commit{plural}", project.path_with_namespace),
+            format!("{}/-/compare/{}...{}", project.web_url, push.before, push.after),
+            BLUE,
+        )
+    };
+    let commits = push.commits.iter().map(|commit| CommitLine {
+        id: &commit.id,
+        message: &commit.message,
+        url: &commit.url,
+        author: &commit.author.name,
+    }).collect::<Vec<_>>();
+    Summary {
+        title,
+        url,
+        description: Some(commit_list(&commits, push.total_commits_count)).filter(|list| !list.is_empty()),
+        color,
+        fields: vec![],
+    }
+}
+
+fn note(project: &Project, event: &NoteEvent) -> Summary {
+    let target = match (&event.merge_request, &event.issue, &event.commit) {
+        (Some(merge_request), _, _) => format!("!{} {}", merge_request.iid, merge_request.title),
+        (_, Some(issue), _) => format!("#{} {}", issue.iid, issue.title),
+        (_, _, Some(commit)) => short_sha(&commit.id).to_string(),
+        _ => event.object_attributes.noteable_type.clone(),
+    };
+    Summary {
+        title: format!("[{}] Comment on {target}", project.path_with_namespace),
+        url: event.object_attributes.url.clone(),
+        description: Some(event.object_attributes.body.clone()),
+        color: BLUE,
+        fields: vec![],
+    }
+}
+
+fn pipeline(project: &Project, event: &PipelineEvent) -> Summary {
+    let pipeline = &event.object_attributes;
+    let color = match pipeline.status.as_str() {
+        "success" => GREEN,
+        "failed" => RED,
+        "running" | "pending" | "created" => YELLOW,
+        _ => GREY,
+    };
+    let mut fields = vec![field("Ref", pipeline.git_ref.clone()), field("Commit", short_sha(&pipeline.sha))];
+    if let Some(duration) = pipeline.duration {
+        fields.push(field("Duration", format!("{duration}s")));
+    }
+    Summary {
+        title: format!("[{}] Pipeline #{} {}", project.path_with_namespace, pipeline.id, pipeline.status),
+        url: pipeline.url.clone().unwrap_or_else(|| format!("{}/-/pipelines/{}", project.web_url, pipeline.id)),
+        description: None,
+        color,
+        fields,
+    }
+}
+
+impl From<&GitLabEvent> for Summary {
+    fn from(event: &GitLabEvent) -> Self {
+        match event {
+            GitLabEvent::Push(event) => push(&event.project, event, false),
+            GitLabEvent::TagPush(event) => push(&event.project, event, true),
+            GitLabEvent::MergeRequest(event) => {
+                let merge_request = &event.object_attributes;
+                let action = merge_request.action.as_deref();
+                Self {
+                    title: format!("[{}] Merge request {}: !{} {}", event.common.project.path_with_namespace, past_tense(action), merge_request.iid, merge_request.title),
+                    url: merge_request.url.clone(),
+                    description: merge_request.description.clone(),
+                    color: if merge_request.draft { GREY } else { action_color(action) },
+                    fields: vec![field("Branch", format!("{} ← {}", merge_request.target_branch, merge_request.source_branch))],
+                }
+            }
+            GitLabEvent::Issue(event) => {
+                let issue = &event.object_attributes;
+                let action = issue.action.as_deref();
+                Self {
+                    title: format!("[{}] Issue {}: #{} {}", event.common.project.path_with_namespace, past_tense(action), issue.iid, issue.title),
+                    url: issue.url.clone(),
+                    description: issue.description.clone(),
+                    color: action_color(action),
+                    fields: vec![],
+                }
+            }
+            GitLabEvent::Note(event) => note(&event.common.project, event),
+            GitLabEvent::Pipeline(event) => pipeline(&event.common.project, event),
+            GitLabEvent::Other { kind, common } => Self {
+                title: format!("[{}] {kind}", common.project.path_with_namespace),
+                url: common.project.web_url.clone(),
+                description: None,
+                color: GREY,
+                fields: vec![],
+            },
+        }
+    }
+}
+
+impl GitLabEvent {
+    pub(crate) fn to_webhook(&self) -> DiscordWebhookPayload {
+        Summary::from(self).into_webhook(&GITLAB, Some(self.actor()), self.name())
+    }
+
+    pub(crate) fn to_event(&self) -> NormalizedEvent {
+        let labels = match self {
+            Self::MergeRequest(event) => &event.labels[..],
+            Self::Issue(event) => &event.labels[..],
+            _ => &[],
+        };
+        let summary = Summary::from(self);
+        NormalizedEvent {
+            source: "gitlab",
+            event: self.name(),
+            title: summary.title,
+            body: summary.description,
+            url: Some(summary.url),
+            project: Some(self.project().path_with_namespace.clone()),
+            priority: None,
+            labels: labels.iter().map(|label| label.title.clone()).collect(),
+            initiator: Some(self.actor().name),
+            received_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use super::*;
+
+    /// `user` and `project` shared by the captured payloads, except push
+    fn payload(kind: &str, event: Value) -> Value {
+        let mut payload = json!({
+            "object_kind": kind,
+            "event_type": kind,
+            "user": {"id": 1, "name": "Administrator", "username": "root", "avatar_url": "https://www.gravatar.com/avatar/e64c7d89f26bd1972efa854d13d7dd61?s=40&d=identicon"},
+            "project": {
+                "id": 1,
+                "name": "Gitlab Test",
+                "web_url": "http://example.com/gitlabhq/gitlab-test",
+                "path_with_namespace": "gitlabhq/gitlab-test",
+                "default_branch": "master",
+            },
+        });
+        if let (Value::Object(payload), Value::Object(event)) = (&mut payload, event) {
+            payload.extend(event);
+        }
+        payload
+    }
+
+    #[test]
+    fn push_is_parsed() {
+        let event = GitLabEvent::parse(json!({
+            "object_kind": "push",
+            "event_name": "push",
+            "before": "95790bf891e76fee5e1747ab589903a6a1f80f22",
+            "after": "da1560886d4f094c3e6c9ef40349f7d38b5d27d7",
+            "ref": "refs/heads/master",
+            "user_name": "John Smith",
+            "user_username": "jsmith",
+            "user_avatar": "https://s.gravatar.com/avatar/d4c74594d841139328695756648b6bd6?s=80",
+            "project": {
+                "id": 15,
+                "name": "Diaspora",
+                "web_url": "http://example.com/mike/diaspora",
+                "path_with_namespace": "mike/diaspora",
+            },
+            "commits": [{
+                "id": "da1560886d4f094c3e6c9ef40349f7d38b5d27d7",
+                "message": "fixed readme",
+                "title": "fixed readme",
+                "timestamp": "2012-01-03T23:36:29+02:00",
+                "url": "http://example.com/mike/diaspora/commit/da1560886d4f094c3e6c9ef40349f7d38b5d27d7",
+                "author": {"name": "GitLab dev user", "email": "gitlabdev@dv6700.(none)"},
+            }],
+            "total_commits_count": 4,
+        })).unwrap();
+        assert_eq!(event.name(), "push");
+        let summary = Summary::from(&event);
+        assert_eq!(summary.title, "[mike/diaspora:master] 4 new commits");
+        assert_eq!(summary.url, "http://example.com/mike/diaspora/-/compare/95790bf891e76fee5e1747ab589903a6a1f80f22...da1560886d4f094c3e6c9ef40349f7d38b5d27d7");
+        let description = summary.description.unwrap();
+        assert!(description.contains("fixed readme - GitLab dev user"));
+        assert!(description.ends_with("... and 3 more"));
+        assert_eq!(event.actor().name, "jsmith");
+    }
+
+    #[test]
+    fn created_branch_is_not_a_list_of_commits() {
+        let event = GitLabEvent::parse(json!({
+            "object_kind": "push",
+            "before": NULL_SHA,
+            "after": "da1560886d4f094c3e6c9ef40349f7d38b5d27d7",
+            "ref": "refs/heads/feature",
+            "user_name": "John Smith",
+            "project": {"web_url": "http://example.com/mike/diaspora", "path_with_namespace": "mike/diaspora"},
+            "commits": [],
+            "total_commits_count": 0,
+        })).unwrap();
+        let summary = Summary::from(&event);
+        assert_eq!(summary.title, "[mike/diaspora] branch feature created");
+        assert_eq!(summary.url, "http://example.com/mike/diaspora/-/tree/feature");
+        assert!(summary.description.is_none());
+    }
+
+    #[test]
+    fn merge_request_is_parsed() {
+        let event = GitLabEvent::parse(payload("merge_request", json!({
+            "object_attributes": {
+                "id": 99,
+                "iid": 1,
+                "target_branch": "master",
+                "source_branch": "ms-viewport",
+                "title": "MS-Viewport",
+                "description": "",
+                "state": "merged",
+                "draft": false,
+                "url": "http://example.com/diaspora/merge_requests/1",
+                "action": "merge",
+            },
+            "labels": [{"id": 206, "title": "API", "color": "#ffffff", "type": "ProjectLabel"}],
+        }))).unwrap();
+        assert_eq!(event.name(), "merge_request.merge");
+        let normalized = event.to_event();
+        assert_eq!(normalized.title, "[gitlabhq/gitlab-test] Merge request merged: !1 MS-Viewport");
+        assert_eq!(normalized.labels, ["API"]);
+        assert_eq!(normalized.initiator.as_deref(), Some("root"));
+        let summary = Summary::from(&event);
+        assert_eq!(summary.color, PURPLE);
+        assert_eq!(summary.fields[0].value, "master ← ms-viewport");
+    }
+
+    #[test]
+    fn issue_is_parsed() {
+        let event = GitLabEvent::parse(payload("issue", json!({
+            "object_attributes": {
+                "id": 301,
+                "iid": 23,
+                "title": "New API: create/update/delete file",
+                "description": "Create new API for manipulations with repository",
+                "state": "opened",
+                "url": "http://example.com/diaspora/issues/23",
+                "action": "open",
+            },
+            "labels": [{"id": 206, "title": "API", "color": "#ffffff", "type": "ProjectLabel"}],
+        }))).unwrap();
+        assert_eq!(event.name(), "issue.open");
+        let summary = Summary::from(&event);
+        assert_eq!(summary.title, "[gitlabhq/gitlab-test] Issue opened: #23 New API: create/update/delete file");
+        assert_eq!(summary.url, "http://example.com/diaspora/issues/23");
+        assert_eq!(summary.color, GREEN);
+    }
+
+    #[test]
+    fn note_is_parsed() {
+        let event = GitLabEvent::parse(payload("note", json!({
+            "object_attributes": {
+                "id": 1244,
+                "note": "This MR needs work.",
+                "noteable_type": "MergeRequest",
+                "url": "http://example.com/gitlab-org/gitlab-test/merge_requests/1#note_1244",
+            },
+            "merge_request": {"id": 7, "iid": 1, "title": "Tempora et eos debitis quae laborum et.", "state": "opened"},
+        }))).unwrap();
+        assert_eq!(event.name(), "note.MergeRequest");
+        let summary = Summary::from(&event);
+        assert_eq!(summary.title, "[gitlabhq/gitlab-test] Comment on !1 Tempora et eos debitis quae laborum et.");
+        assert_eq!(summary.description.as_deref(), Some("This MR needs work."));
+    }
+
+    #[test]
+    fn pipeline_is_parsed() {
+        let event = GitLabEvent::parse(payload("pipeline", json!({
+            "object_attributes": {
+                "id": 31,
+                "iid": 3,
+                "ref": "master",
+                "tag": false,
+                "sha": "bcbb5ec396a2c0f828686f14fac9b80b780504f2",
+                "status": "failed",
+                "duration": 63,
+                "url": "http://example.com/gitlab-org/gitlab-test/-/pipelines/31",
+            },
+        }))).unwrap();
+        assert_eq!(event.name(), "pipeline.failed");
+        let summary = Summary::from(&event);
+        assert_eq!(summary.title, "[gitlabhq/gitlab-test] Pipeline #31 failed");
+        assert_eq!(summary.color, RED);
+        assert_eq!(summary.fields.iter().map(|field| field.value.as_str()).collect::<Vec<_>>(), ["master", "bcbb5ec", "63s"]);
+    }
+
+    #[test]
+    fn unknown_kind_keeps_its_name() {
+        let event = GitLabEvent::parse(payload("wiki_page", json!({"object_attributes": {"title": "Awesome"}}))).unwrap();
+        assert_eq!(event.name(), "wiki_page");
+        assert_eq!(Summary::from(&event).url, "http://example.com/gitlabhq/gitlab-test");
+    }
+}
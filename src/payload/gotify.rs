@@ -0,0 +1,68 @@
+use serde::Serialize;
+use crate::payload::todoist::TodoistPriority;
+
+/// for more information, see https://gotify.net/api-docs#/message/createMessage
+#[derive(Serialize)]
+pub(crate) struct GotifyMessage<'a> {
+    pub(crate) title: &'a str,
+    pub(crate) message: &'a str,
+    /// 0 to 10. Android client does not show a notification below 1, and plays a sound from 4.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) priority: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) extras: Option<GotifyExtras<'a>>,
+}
+
+/// https://gotify.net/docs/msgextras
+#[derive(Serialize)]
+pub(crate) struct GotifyExtras<'a> {
+    #[serde(rename = "client::notification")]
+    pub(crate) notification: GotifyNotificationExtras<'a>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct GotifyNotificationExtras<'a> {
+    pub(crate) click: GotifyClick<'a>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct GotifyClick<'a> {
+    pub(crate) url: &'a str,
+}
+
+pub(crate) const fn priority(priority: TodoistPriority) -> u8 {
+    match priority {
+        TodoistPriority::P1 => 8,
+        TodoistPriority::P2 => 6,
+        TodoistPriority::P3 => 4,
+        TodoistPriority::P4 => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn priority_is_mapped_from_todoist() {
+        let levels = [TodoistPriority::P1, TodoistPriority::P2, TodoistPriority::P3, TodoistPriority::P4].map(priority);
+        assert_eq!(levels, [8, 6, 4, 2]);
+    }
+
+    #[test]
+    fn click_url_goes_to_the_extras() {
+        let message = GotifyMessage {
+            title: "Buy milk",
+            message: "item:completed",
+            priority: Some(8),
+            extras: Some(GotifyExtras { notification: GotifyNotificationExtras { click: GotifyClick { url: "https://todoist.com/showTask?id=1" } } }),
+        };
+        assert_eq!(serde_json::to_value(&message).unwrap(), json!({
+            "title": "Buy milk",
+            "message": "item:completed",
+            "priority": 8,
+            "extras": {"client::notification": {"click": {"url": "https://todoist.com/showTask?id=1"}}},
+        }));
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::event::NormalizedEvent;
+use crate::render::color::{BLUE, GREEN, GREY, RED};
+use crate::render::summary::{change, field, Actor, Service, Summary};
+
+const JIRA: Service = Service {
+    name: "Jira",
+    avatar_url: "https://wac-cdn.atlassian.com/assets/img/favicons/atlassian/apple-touch-icon-152x152.png",
+};
+
+/// for more information, see https://developer.atlassian.com/server/jira/platform/webhooks/
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JiraWebhook {
+    /// e.g. `jira:issue_created`, `jira:issue_updated`, `comment_created`
+    pub(crate) webhook_event: String,
+    #[serde(default)]
+    pub(crate) user: Option<JiraUser>,
+    pub(crate) issue: JiraIssue,
+    #[serde(default)]
+    pub(crate) changelog: Option<Changelog>,
+    #[serde(default)]
+    pub(crate) comment: Option<JiraComment>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JiraUser {
+    pub(crate) display_name: String,
+    /// keyed by size, e.g. `48x48`
+    #[serde(default)]
+    pub(crate) avatar_urls: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct JiraIssue {
+    /// e.g. `PROJ-1`
+    pub(crate) key: String,
+    /// REST API URL of the issue, e.g. `https://example.atlassian.net/rest/api/2/issue/10001`
+    #[serde(rename = "self")]
+    pub(crate) api_url: String,
+    pub(crate) fields: JiraFields,
+}
+
+impl JiraIssue {
+    /// `https://example.atlassian.net/browse/PROJ-1`
+    fn browse_url(&self) -> String {
+        let base = self.api_url.split_once("/rest/").map_or(self.api_url.as_str(), |(base, _)| base);
+        format!("{base}/browse/{}", self.key)
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Named {
+    pub(crate) name: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct JiraFields {
+    #[serde(default)]
+    pub(crate) summary: String,
+    /// plain text on API v2, a document object on v3
+    #[serde(default)]
+    pub(crate) description: Option<serde_json::Value>,
+    #[serde(default)]
+    pub(crate) issuetype: Option<Named>,
+    #[serde(default)]
+    pub(crate) priority: Option<Named>,
+    #[serde(default)]
+    pub(crate) status: Option<Named>,
+    #[serde(default)]
+    pub(crate) assignee: Option<JiraUser>,
+    #[serde(default)]
+    pub(crate) project: Option<JiraProject>,
+    #[serde(default)]
+    pub(crate) labels: Vec<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct JiraProject {
+    pub(crate) key: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Changelog {
+    #[serde(default)]
+    pub(crate) items: Vec<ChangeItem>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct ChangeItem {
+    /// e.g. `status`, `assignee`, `summary`
+    pub(crate) field: String,
+    #[serde(rename = "fromString", default)]
+    pub(crate) from: Option<String>,
+    #[serde(rename = "toString", default)]
+    pub(crate) to: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct JiraComment {
+    pub(crate) id: String,
+    /// plain text on API v2, a document object on v3
+    #[serde(default)]
+    pub(crate) body: Option<serde_json::Value>,
+}
+
+/// document objects (Atlassian Document Format) are not shown
+fn plain_text(value: Option<&serde_json::Value>) -> Option<String> {
+    value.and_then(serde_json::Value::as_str).map(ToString::to_string)
+}
+
+impl JiraWebhook {
+    fn actor(&self) -> Option<Actor> {
+        self.user.as_ref().map(|user| Actor {
+            name: user.display_name.clone(),
+            url: None,
+            avatar_url: user.avatar_urls.get("48x48").cloned(),
+        })
+    }
+
+    pub(crate) fn to_webhook(&self) -> DiscordWebhookPayload {
+        Summary::from(self).into_webhook(&JIRA, self.actor(), self.webhook_event.clone())
+    }
+
+    pub(crate) fn to_event(&self) -> NormalizedEvent {
+        let summary = Summary::from(self);
+        NormalizedEvent {
+            source: "jira",
+            event: self.webhook_event.clone(),
+            title: summary.title,
+            body: summary.description,
+            url: Some(summary.url),
+            project: self.issue.fields.project.as_ref().map(|project| project.key.clone()),
+            priority: None,
+            labels: self.issue.fields.labels.clone(),
+            initiator: self.user.as_ref().map(|user| user.display_name.clone()),
+            received_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+impl From<&JiraWebhook> for Summary {
+    fn from(webhook: &JiraWebhook) -> Self {
+        let issue = &webhook.issue;
+        let fields = &issue.fields;
+        let comment = webhook.comment.as_ref();
+        let title = |action: &str| format!("[{}] {action}: {}", issue.key, fields.summary);
+        match webhook.webhook_event.as_str() {
+            "jira:issue_created" => Self {
+                title: title("Created"),
+                url: issue.browse_url(),
+                description: plain_text(fields.description.as_ref()),
+                color: GREEN,
+                fields: [
+                    ("Type", fields.issuetype.as_ref().map(|named| &named.name)),
+                    ("Priority", fields.priority.as_ref().map(|named| &named.name)),
+                    ("Status", fields.status.as_ref().map(|named| &named.name)),
+                    ("Assignee", fields.assignee.as_ref().map(|user| &user.display_name)),
+                ].into_iter()
+                    .filter_map(|(name, value)| value.map(|value| field(name, value.clone())))
+                    .collect(),
+            },
+            "jira:issue_updated" => Self {
+                title: title("Updated"),
+                url: issue.browse_url(),
+                // a comment added along with the update, e.g. on a transition
+                description: comment.and_then(|comment| plain_text(comment.body.as_ref())),
+                color: BLUE,
+                fields: webhook.changelog.iter().flat_map(|changelog| &changelog.items).map(|item| change(
+                    &item.field,
+                    Some(item.from.as_deref().unwrap_or("none")),
+                    item.to.as_deref().unwrap_or("none"),
+                )).collect(),
+            },
+            "jira:issue_deleted" => Self {
+                title: title("Deleted"),
+                url: issue.browse_url(),
+                description: None,
+                color: RED,
+                fields: vec![],
+            },
+            name if name.starts_with("comment_") => Self {
+                title: title("Comment"),
+                url: comment.map_or_else(|| issue.browse_url(), |comment| format!("{}?focusedCommentId={}", issue.browse_url(), comment.id)),
+                description: comment.and_then(|comment| plain_text(comment.body.as_ref())),
+                color: BLUE,
+                fields: vec![],
+            },
+            name => Self {
+                title: title(name),
+                url: issue.browse_url(),
+                description: None,
+                color: GREY,
+                fields: vec![],
+            },
+        }
+    }
+}
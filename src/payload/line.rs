@@ -0,0 +1,221 @@
+use serde::Serialize;
+use crate::render::color;
+
+/// for more information, see https://developers.line.biz/en/reference/messaging-api/#send-push-message
+#[derive(Serialize)]
+pub(crate) struct PushMessage<'a> {
+    /// ID of the target recipient. Use a userId, groupId, or roomId value returned in a webhook event object.
+    pub(crate) to: &'a str,
+    /// Max: 5
+    pub(crate) messages: &'a [FlexMessage],
+}
+
+/// https://developers.line.biz/en/reference/messaging-api/#flex-message
+#[derive(Serialize)]
+#[serde(tag = "type", rename = "flex")]
+pub(crate) struct FlexMessage {
+    /// shown in push notifications and chat lists. Max character limit: 400
+    #[serde(rename = "altText")]
+    pub(crate) alt_text: String,
+    pub(crate) contents: FlexBubble,
+}
+
+/// https://developers.line.biz/en/reference/messaging-api/#bubble
+#[derive(Serialize)]
+#[serde(tag = "type", rename = "bubble")]
+pub(crate) struct FlexBubble {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) header: Option<FlexBox>,
+    /// LINE rejects a box without contents, so it is left out instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) body: Option<FlexBox>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) footer: Option<FlexBox>,
+}
+
+/// https://developers.line.biz/en/reference/messaging-api/#box
+#[derive(Serialize)]
+pub(crate) struct FlexBox {
+    pub(crate) layout: FlexLayout,
+    pub(crate) contents: Vec<FlexComponent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) spacing: Option<FlexSize>,
+    #[serde(rename = "backgroundColor", skip_serializing_if = "Option::is_none")]
+    pub(crate) background_color: Option<String>,
+}
+
+#[derive(Serialize, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum FlexLayout {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Serialize, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum FlexSize {
+    Sm,
+    Lg,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub(crate) enum FlexComponent {
+    Box(FlexBox),
+    Text(FlexText),
+    Button(FlexButton),
+}
+
+/// https://developers.line.biz/en/reference/messaging-api/#f-text
+#[derive(Serialize, Default)]
+pub(crate) struct FlexText {
+    pub(crate) text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) size: Option<FlexSize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) weight: Option<FlexWeight>,
+    /// `#RRGGBB`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) color: Option<String>,
+    /// ratio of the width within the parent horizontal box
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) flex: Option<u32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) wrap: bool,
+}
+
+#[derive(Serialize, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum FlexWeight {
+    Bold,
+}
+
+/// https://developers.line.biz/en/reference/messaging-api/#button
+#[derive(Serialize)]
+pub(crate) struct FlexButton {
+    pub(crate) action: UriAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) style: Option<FlexButtonStyle>,
+}
+
+#[derive(Serialize, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum FlexButtonStyle {
+    Link,
+}
+
+/// https://developers.line.biz/en/reference/messaging-api/#uri-action
+#[derive(Serialize)]
+#[serde(tag = "type", rename = "uri")]
+pub(crate) struct UriAction {
+    /// Max character limit: 20
+    pub(crate) label: String,
+    pub(crate) uri: String,
+}
+
+/// shown instead of an empty title
+const UNTITLED: &str = "(タイトルなし)";
+
+impl FlexBubble {
+    /// header with a title, body with `key: value` rows and an optional link button at footer.
+    /// LINE rejects empty text, so rows with an empty value are left out, and an empty title is replaced.
+    pub(crate) fn key_value(title: String, accent: Option<u32>, rows: Vec<(String, String)>, link: Option<UriAction>) -> Self {
+        let header = FlexBox {
+            layout: FlexLayout::Vertical,
+            contents: vec![FlexComponent::Text(FlexText {
+                text: if title.trim().is_empty() { UNTITLED.to_string() } else { title },
+                size: Some(FlexSize::Lg),
+                weight: Some(FlexWeight::Bold),
+                wrap: true,
+                ..FlexText::default()
+            })],
+            spacing: None,
+            background_color: None,
+        };
+        let rows = rows.into_iter()
+            .filter(|(_, value)| !value.trim().is_empty())
+            .map(|(key, value)| FlexComponent::Box(FlexBox {
+                layout: FlexLayout::Horizontal,
+                contents: vec![
+                    FlexComponent::Text(FlexText {
+                        text: key,
+                        size: Some(FlexSize::Sm),
+                        color: Some("#AAAAAA".to_string()),
+                        flex: Some(2),
+                        ..FlexText::default()
+                    }),
+                    FlexComponent::Text(FlexText {
+                        text: value,
+                        size: Some(FlexSize::Sm),
+                        color: accent.map(color::hex),
+                        flex: Some(5),
+                        wrap: true,
+                        ..FlexText::default()
+                    }),
+                ],
+                spacing: Some(FlexSize::Sm),
+                background_color: None,
+            }))
+            .collect::<Vec<_>>();
+        let body = (!rows.is_empty()).then_some(FlexBox {
+            layout: FlexLayout::Vertical,
+            contents: rows,
+            spacing: Some(FlexSize::Sm),
+            background_color: None,
+        });
+        let footer = link.map(|action| FlexBox {
+            layout: FlexLayout::Vertical,
+            contents: vec![FlexComponent::Button(FlexButton {
+                action,
+                style: Some(FlexButtonStyle::Link),
+            })],
+            spacing: None,
+            background_color: None,
+        });
+
+        Self {
+            header: Some(header),
+            body,
+            footer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    fn row(key: &str, value: &str) -> (String, String) {
+        (key.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn rows_are_key_value_boxes() {
+        let bubble = FlexBubble::key_value("Buy milk".to_string(), Some(0x00D1_453B), vec![row("期限", "2026-10-19")], None);
+        let bubble = serde_json::to_value(bubble).unwrap();
+        assert_eq!(bubble["type"], "bubble");
+        assert_eq!(bubble["header"]["contents"][0]["text"], "Buy milk");
+        assert_eq!(bubble["body"]["contents"][0], json!({
+            "type": "box",
+            "layout": "horizontal",
+            "contents": [
+                { "type": "text", "text": "期限", "size": "sm", "color": "#AAAAAA", "flex": 2 },
+                { "type": "text", "text": "2026-10-19", "size": "sm", "color": "#D1453B", "flex": 5, "wrap": true },
+            ],
+            "spacing": "sm",
+        }));
+        assert!(bubble.get("footer").is_none());
+    }
+
+    #[test]
+    fn empty_text_is_not_sent() {
+        let link = UriAction { label: "開く".to_string(), uri: "https://todoist.com/showTask?id=1".to_string() };
+        let bubble = FlexBubble::key_value(String::new(), None, vec![row("コメント", ""), row("ラベル", " ")], Some(link));
+        let bubble = serde_json::to_value(bubble).unwrap();
+        assert_eq!(bubble["header"]["contents"][0]["text"], UNTITLED);
+        // a box without contents is rejected as well
+        assert!(bubble.get("body").is_none(), "{bubble}");
+        assert_eq!(bubble["footer"]["contents"][0]["action"]["uri"], "https://todoist.com/showTask?id=1");
+    }
+}
@@ -0,0 +1,236 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use crate::payload::discord::{DiscordWebhookPayload, EmbedField};
+use crate::payload::event::NormalizedEvent;
+use crate::render::color::{BLUE, GREEN, GREY, RED};
+use crate::render::summary::{change, Actor, Service, Summary};
+
+const LINEAR: Service = Service {
+    name: "Linear",
+    avatar_url: "https://linear.app/static/apple-touch-icon.png",
+};
+
+/// fields which change automatically and are not shown in the diff
+const IGNORED_CHANGES: [&str; 4] = ["updatedAt", "sortOrder", "subIssueSortOrder", "prioritySortOrder"];
+
+/// for more information, see https://developers.linear.app/docs/graphql/webhooks
+#[derive(Deserialize, Clone)]
+#[serde(try_from = "RawLinearWebhook")]
+pub(crate) struct LinearWebhook {
+    /// `create`, `update` or `remove`
+    pub(crate) action: String,
+    pub(crate) data: LinearData,
+    pub(crate) actor: Option<LinearActor>,
+    pub(crate) url: Option<String>,
+    /// previous values of the updated fields
+    pub(crate) updated_from: Map<String, Value>,
+    /// `data` as is, to look up the new values of the updated fields
+    pub(crate) raw_data: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawLinearWebhook {
+    action: String,
+    #[serde(rename = "type")]
+    kind: String,
+    data: Value,
+    #[serde(default)]
+    actor: Option<LinearActor>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    updated_from: Map<String, Value>,
+}
+
+impl TryFrom<RawLinearWebhook> for LinearWebhook {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawLinearWebhook) -> Result<Self, Self::Error> {
+        let data = match raw.kind.as_str() {
+            "Issue" => LinearData::Issue(LinearIssue::deserialize(&raw.data)?),
+            "Comment" => LinearData::Comment(LinearComment::deserialize(&raw.data)?),
+            _ => LinearData::Other(raw.kind),
+        };
+        Ok(Self {
+            action: raw.action,
+            data,
+            actor: raw.actor,
+            url: raw.url,
+            updated_from: raw.updated_from,
+            raw_data: raw.data,
+        })
+    }
+}
+
+/// `data` for each `type`
+#[derive(Clone)]
+pub(crate) enum LinearData {
+    Issue(LinearIssue),
+    Comment(LinearComment),
+    Other(String),
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct LinearActor {
+    pub(crate) name: String,
+    #[serde(default, rename = "avatarUrl")]
+    pub(crate) avatar_url: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Named {
+    pub(crate) name: String,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LinearIssue {
+    /// e.g. `ENG-123`
+    pub(crate) identifier: String,
+    pub(crate) title: String,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    #[serde(default)]
+    pub(crate) state: Option<Named>,
+    #[serde(default)]
+    pub(crate) assignee: Option<Named>,
+    #[serde(default)]
+    pub(crate) labels: Vec<Named>,
+    #[serde(default)]
+    pub(crate) team: Option<LinearTeam>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct LinearTeam {
+    pub(crate) key: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct LinearComment {
+    pub(crate) body: String,
+    #[serde(default)]
+    pub(crate) issue: Option<CommentedIssue>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct CommentedIssue {
+    #[serde(default)]
+    pub(crate) identifier: Option<String>,
+    pub(crate) title: String,
+}
+
+/// shows `null` as `none`, strings as they are, and anything else as JSON
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => "none".to_string(),
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// `create` -> `created`
+fn past_tense(action: &str) -> String {
+    match action {
+        "create" => "created".to_string(),
+        "update" => "updated".to_string(),
+        "remove" => "removed".to_string(),
+        action => action.to_string(),
+    }
+}
+
+impl LinearWebhook {
+    /// e.g. `Issue.update`
+    fn name(&self) -> String {
+        let kind = match &self.data {
+            LinearData::Issue(_) => "Issue",
+            LinearData::Comment(_) => "Comment",
+            LinearData::Other(kind) => kind,
+        };
+        format!("{kind}.{}", self.action)
+    }
+
+    /// relations such as `stateId` come with only the old ID, so only the new name is shown
+    fn changes(&self, issue: &LinearIssue) -> Vec<EmbedField> {
+        self.updated_from.iter().filter(|(key, _)| !IGNORED_CHANGES.contains(&key.as_str())).map(|(key, old)| match key.as_str() {
+            "stateId" => change("state", None, issue.state.as_ref().map_or("none", |state| &state.name)),
+            "assigneeId" => change("assignee", None, issue.assignee.as_ref().map_or("none", |assignee| &assignee.name)),
+            "labelIds" => change("labels", None, &issue.labels.iter().map(|label| label.name.as_str()).collect::<Vec<_>>().join(", ")),
+            // long text is just noted as changed
+            "description" => change("description", None, "(edited)"),
+            key => change(key, Some(&display(old)), &display(self.raw_data.get(key).unwrap_or(&Value::Null))),
+        }).collect()
+    }
+
+    pub(crate) fn to_webhook(&self) -> DiscordWebhookPayload {
+        let actor = self.actor.as_ref().map(|actor| Actor {
+            name: actor.name.clone(),
+            url: None,
+            avatar_url: actor.avatar_url.clone(),
+        });
+        Summary::from(self).into_webhook(&LINEAR, actor, self.name())
+    }
+
+    pub(crate) fn to_event(&self) -> NormalizedEvent {
+        let summary = Summary::from(self);
+        let (project, labels) = match &self.data {
+            LinearData::Issue(issue) => (
+                issue.team.as_ref().map(|team| team.key.clone()),
+                issue.labels.iter().map(|label| label.name.clone()).collect(),
+            ),
+            _ => (None, vec![]),
+        };
+        NormalizedEvent {
+            source: "linear",
+            event: self.name(),
+            title: summary.title,
+            body: summary.description,
+            url: Some(summary.url).filter(|url| !url.is_empty()),
+            project,
+            priority: None,
+            labels,
+            initiator: self.actor.as_ref().map(|actor| actor.name.clone()),
+            received_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+impl From<&LinearWebhook> for Summary {
+    fn from(webhook: &LinearWebhook) -> Self {
+        let color = match webhook.action.as_str() {
+            "create" => GREEN,
+            "update" => BLUE,
+            "remove" => RED,
+            _ => GREY,
+        };
+        let url = webhook.url.clone().unwrap_or_default();
+        match &webhook.data {
+            LinearData::Issue(issue) => Self {
+                title: format!("[{}] Issue {}: {}", issue.identifier, past_tense(&webhook.action), issue.title),
+                url,
+                description: if webhook.action == "create" { issue.description.clone() } else { None },
+                color,
+                fields: if webhook.action == "update" { webhook.changes(issue) } else { vec![] },
+            },
+            LinearData::Comment(comment) => {
+                let target = comment.issue.as_ref().map_or_else(String::new, |issue| {
+                    issue.identifier.as_ref().map_or_else(|| format!(" on {}", issue.title), |identifier| format!(" on [{identifier}] {}", issue.title))
+                });
+                Self {
+                    title: format!("Comment {}{target}", past_tense(&webhook.action)),
+                    url,
+                    description: Some(comment.body.clone()),
+                    color,
+                    fields: vec![],
+                }
+            }
+            LinearData::Other(kind) => Self {
+                title: format!("{kind} {}", past_tense(&webhook.action)),
+                url,
+                description: None,
+                color,
+                fields: vec![],
+            },
+        }
+    }
+}
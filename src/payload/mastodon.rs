@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// for more information, see https://docs.joinmastodon.org/methods/statuses/#create
+#[derive(Serialize)]
+pub(crate) struct StatusCreate<'a> {
+    pub(crate) status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) visibility: Option<StatusVisibility>,
+    /// content warning. Mastodon hides the status behind this text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) spoiler_text: Option<&'a str>,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum StatusVisibility {
+    Public,
+    Unlisted,
+    Private,
+    Direct,
+}
@@ -0,0 +1,95 @@
+use std::fmt::Write;
+use serde::Serialize;
+use crate::payload::discord::{DiscordWebhookPayload, Embed};
+use crate::render::{color, html, plain};
+
+/// for more information, see https://spec.matrix.org/v1.8/client-server-api/#mroommessage
+#[derive(Serialize)]
+pub(crate) struct MatrixRoomMessage {
+    pub(crate) msgtype: MessageType,
+    /// fallback for clients which can not render `formatted_body`
+    pub(crate) body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) format: Option<MessageFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) formatted_body: Option<String>,
+}
+
+/// https://spec.matrix.org/v1.8/client-server-api/#mnotice
+#[derive(Serialize, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum MessageType {
+    /// bots should use this type to avoid loops between bots
+    #[serde(rename = "m.notice")]
+    Notice,
+}
+
+#[derive(Serialize, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum MessageFormat {
+    #[serde(rename = "org.matrix.custom.html")]
+    Html,
+}
+
+impl From<&DiscordWebhookPayload> for MatrixRoomMessage {
+    fn from(payload: &DiscordWebhookPayload) -> Self {
+        let mut formatted_body = vec![];
+        if !payload.content.is_empty() {
+            formatted_body.push(format!("<p>{}</p>", html::escape(&payload.content)));
+        }
+        for embed in &payload.embeds.0 {
+            formatted_body.push(embed_to_html(embed));
+        }
+
+        Self {
+            msgtype: MessageType::Notice,
+            body: plain::render(payload),
+            format: Some(MessageFormat::Html),
+            formatted_body: Some(formatted_body.join("")),
+        }
+    }
+}
+
+/// Matrix clients do not have embed, so it is rendered as blockquote.
+/// The color is preserved by `data-mx-color`, which is allowed on `<font>`.
+fn embed_to_html(embed: &Embed) -> String {
+    // writing into String never fails
+    let mut inner = String::new();
+    if let Some(author) = &embed.author {
+        let _ = write!(inner, "<p><sub>{}</sub></p>", html::escape(&author.name));
+    }
+    let title = embed.title.as_deref().map(html::escape);
+    let title = match (title, &embed.url) {
+        (Some(title), Some(url)) => Some(format!("<a href=\"{}\">{title}</a>", html::escape(url))),
+        (Some(title), None) => Some(title),
+        (None, Some(url)) => Some(format!("<a href=\"{0}\">{0}</a>", html::escape(url))),
+        (None, None) => None,
+    };
+    if let Some(title) = title {
+        match embed.color {
+            Some(color) => {
+                let _ = write!(inner, "<p><strong><font data-mx-color=\"{}\">{title}</font></strong></p>", color::hex(color));
+            }
+            None => {
+                let _ = write!(inner, "<p><strong>{title}</strong></p>");
+            }
+        }
+    }
+    if let Some(description) = &embed.description {
+        let _ = write!(inner, "<p>{}</p>", html::escape(description).replace('\n', "<br>"));
+    }
+    if !embed.fields.0.is_empty() {
+        inner.push_str("<ul>");
+        for field in &embed.fields.0 {
+            let _ = write!(
+                inner,
+                "<li><strong>{}</strong>: {}</li>",
+                html::escape(&field.name),
+                html::escape(&field.value).replace('\n', "<br>")
+            );
+        }
+        inner.push_str("</ul>");
+    }
+    if let Some(footer) = &embed.footer {
+        let _ = write!(inner, "<p><sub>{}</sub></p>", html::escape(&footer.text));
+    }
+    format!("<blockquote>{inner}</blockquote>")
+}
@@ -0,0 +1,139 @@
+use serde::Serialize;
+use crate::payload::discord::{DiscordWebhookPayload, Embed};
+use crate::render::{color, plain};
+
+/// for more information, see https://developers.mattermost.com/integrate/webhooks/incoming/#parameters
+#[derive(Serialize)]
+pub(crate) struct MattermostWebhookPayload {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub(crate) text: String,
+    /// overrides the channel the webhook is bound to, if the webhook allows it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) channel: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) icon_url: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) attachments: Vec<MattermostAttachment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) props: Option<MattermostProps>,
+}
+
+/// https://developers.mattermost.com/integrate/reference/message-attachments/
+#[derive(Serialize, Default)]
+pub(crate) struct MattermostAttachment {
+    /// plain-text summary shown in notifications
+    pub(crate) fallback: String,
+    /// `#RRGGBB`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) author_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) title_link: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) text: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) fields: Vec<MattermostAttachmentField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) footer: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct MattermostAttachmentField {
+    pub(crate) title: String,
+    pub(crate) value: String,
+    /// whether the field can be shown side-by-side with other fields
+    pub(crate) short: bool,
+}
+
+/// https://developers.mattermost.com/integrate/webhooks/incoming/#parameters
+#[derive(Serialize)]
+pub(crate) struct MattermostProps {
+    /// markdown shown in the RHS when "Message Info" is clicked
+    pub(crate) card: String,
+}
+
+impl From<&DiscordWebhookPayload> for MattermostWebhookPayload {
+    fn from(payload: &DiscordWebhookPayload) -> Self {
+        Self {
+            text: payload.content.clone(),
+            channel: None,
+            username: payload.username.clone(),
+            icon_url: payload.avatar_url.clone(),
+            attachments: payload.embeds.0.iter().map(MattermostAttachment::from).collect(),
+            props: None,
+        }
+    }
+}
+
+impl From<&Embed> for MattermostAttachment {
+    fn from(embed: &Embed) -> Self {
+        Self {
+            fallback: plain::embed(embed),
+            color: embed.color.map(color::hex),
+            author_name: embed.author.as_ref().map(|author| author.name.clone()),
+            title: embed.title.clone(),
+            title_link: embed.url.clone(),
+            text: embed.description.clone(),
+            fields: embed.fields.0.iter()
+                .map(|field| MattermostAttachmentField {
+                    title: field.name.clone(),
+                    value: field.value.clone(),
+                    short: field.value.chars().count() <= 40,
+                })
+                .collect(),
+            footer: embed.footer.as_ref().map(|footer| footer.text.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn embed_is_mapped_to_an_attachment() {
+        let payload: DiscordWebhookPayload = serde_json::from_value(json!({
+            "username": "Todoist",
+            "embeds": [{
+                "author": {"name": "Alice"},
+                "title": "Buy milk",
+                "url": "https://todoist.com/showTask?id=1",
+                "description": "2 bottles",
+                "color": 0x00D1_453B,
+                "fields": [{"name": "Priority", "value": "P1"}, {"name": "Note", "value": "x".repeat(41)}],
+                "footer": {"text": "item:completed"},
+            }],
+        })).unwrap();
+        let payload = MattermostWebhookPayload::from(&payload);
+        assert_eq!(serde_json::to_value(&payload).unwrap(), json!({
+            "username": "Todoist",
+            "attachments": [{
+                "fallback": format!("Alice\nBuy milk (https://todoist.com/showTask?id=1)\n2 bottles\nPriority: P1\nNote: {}\nitem:completed", "x".repeat(41)),
+                "color": "#D1453B",
+                "author_name": "Alice",
+                "title": "Buy milk",
+                "title_link": "https://todoist.com/showTask?id=1",
+                "text": "2 bottles",
+                // long values get a row of their own
+                "fields": [{"title": "Priority", "value": "P1", "short": true}, {"title": "Note", "value": "x".repeat(41), "short": false}],
+                "footer": "item:completed",
+            }],
+        }));
+    }
+
+    #[test]
+    fn embed_without_color_or_link_leaves_them_out() {
+        let payload: DiscordWebhookPayload = serde_json::from_value(json!({"content": "Heads up", "embeds": [{"title": "Buy milk"}]})).unwrap();
+        let payload = MattermostWebhookPayload::from(&payload);
+        assert_eq!(serde_json::to_value(&payload).unwrap(), json!({
+            "text": "Heads up",
+            "attachments": [{"fallback": "Buy milk", "title": "Buy milk"}],
+        }));
+    }
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// for more information, see https://misskey-hub.net/docs/api/endpoints/notes/create.html
+#[derive(Serialize)]
+pub(crate) struct NotesCreate<'a> {
+    /// access token
+    pub(crate) i: &'a str,
+    pub(crate) text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) visibility: Option<NoteVisibility>,
+    /// content warning
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) cw: Option<&'a str>,
+    #[serde(rename = "localOnly", skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) local_only: bool,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum NoteVisibility {
+    Public,
+    Home,
+    Followers,
+    Specified,
+}
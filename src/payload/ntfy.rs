@@ -0,0 +1,78 @@
+use serde::Serialize;
+use crate::payload::todoist::TodoistPriority;
+
+/// for more information, see https://docs.ntfy.sh/publish/#publish-as-json
+#[derive(Serialize)]
+pub(crate) struct NtfyPublish<'a> {
+    pub(crate) topic: &'a str,
+    pub(crate) title: &'a str,
+    pub(crate) message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) priority: Option<NtfyPriority>,
+    /// emoji short codes are shown as emoji, the others are shown as text
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tags: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) click: Option<&'a str>,
+}
+
+/// https://docs.ntfy.sh/publish/#message-priority
+#[derive(Serialize, Copy, Clone, Eq, PartialEq)]
+#[serde(into = "u8")]
+pub(crate) enum NtfyPriority {
+    Low,
+    Default,
+    High,
+    Max,
+}
+
+impl From<NtfyPriority> for u8 {
+    fn from(priority: NtfyPriority) -> Self {
+        match priority {
+            NtfyPriority::Low => 2,
+            NtfyPriority::Default => 3,
+            NtfyPriority::High => 4,
+            NtfyPriority::Max => 5,
+        }
+    }
+}
+
+impl From<TodoistPriority> for NtfyPriority {
+    fn from(priority: TodoistPriority) -> Self {
+        match priority {
+            TodoistPriority::P1 => Self::Max,
+            TodoistPriority::P2 => Self::High,
+            TodoistPriority::P3 => Self::Default,
+            TodoistPriority::P4 => Self::Low,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn priority_is_mapped_from_todoist() {
+        let levels = [TodoistPriority::P1, TodoistPriority::P2, TodoistPriority::P3, TodoistPriority::P4]
+            .map(|priority| u8::from(NtfyPriority::from(priority)));
+        assert_eq!(levels, [5, 4, 3, 2]);
+    }
+
+    #[test]
+    fn body_leaves_out_empty_fields() {
+        let publish = NtfyPublish { topic: "todo", title: "Buy milk", message: "item:completed", priority: None, tags: vec![], click: None };
+        assert_eq!(serde_json::to_value(&publish).unwrap(), json!({"topic": "todo", "title": "Buy milk", "message": "item:completed"}));
+
+        let publish = NtfyPublish { priority: Some(NtfyPriority::Max), tags: vec!["todoist"], click: Some("https://todoist.com/showTask?id=1"), ..publish };
+        assert_eq!(serde_json::to_value(&publish).unwrap(), json!({
+            "topic": "todo",
+            "title": "Buy milk",
+            "message": "item:completed",
+            "priority": 5,
+            "tags": ["todoist"],
+            "click": "https://todoist.com/showTask?id=1",
+        }));
+    }
+}
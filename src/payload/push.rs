@@ -0,0 +1,14 @@
+use serde::Serialize;
+use crate::payload::todoist::TodoistPriority;
+
+/// common representation of a push notification to phones. Each sink converts it into the format of its service.
+#[derive(Serialize)]
+pub(crate) struct PushNotification {
+    pub(crate) title: String,
+    pub(crate) message: String,
+    /// `None` means the default priority of each service
+    pub(crate) priority: Option<TodoistPriority>,
+    /// opened when the notification is tapped
+    pub(crate) click: Option<String>,
+    pub(crate) tags: Vec<String>,
+}
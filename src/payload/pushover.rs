@@ -0,0 +1,61 @@
+use serde::Serialize;
+use crate::payload::todoist::TodoistPriority;
+
+/// for more information, see https://pushover.net/api#messages
+#[derive(Serialize)]
+pub(crate) struct PushoverMessage<'a> {
+    /// application API token
+    pub(crate) token: &'a str,
+    /// user or group key
+    pub(crate) user: &'a str,
+    pub(crate) title: &'a str,
+    pub(crate) message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) priority: Option<i8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) url: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) device: Option<&'a str>,
+}
+
+/// Pushover's emergency priority (2) requires `retry` and `expire`, so it is not used here.
+pub(crate) const fn priority(priority: TodoistPriority) -> i8 {
+    match priority {
+        TodoistPriority::P1 => 1,
+        TodoistPriority::P2 | TodoistPriority::P3 => 0,
+        TodoistPriority::P4 => -1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn priority_is_mapped_from_todoist() {
+        let levels = [TodoistPriority::P1, TodoistPriority::P2, TodoistPriority::P3, TodoistPriority::P4].map(priority);
+        assert_eq!(levels, [1, 0, 0, -1]);
+    }
+
+    #[test]
+    fn body_carries_the_credentials() {
+        let message = PushoverMessage {
+            token: "app",
+            user: "user",
+            title: "Buy milk",
+            message: "item:completed",
+            priority: Some(-1),
+            url: Some("https://todoist.com/showTask?id=1"),
+            device: None,
+        };
+        assert_eq!(serde_json::to_value(&message).unwrap(), json!({
+            "token": "app",
+            "user": "user",
+            "title": "Buy milk",
+            "message": "item:completed",
+            "priority": -1,
+            "url": "https://todoist.com/showTask?id=1",
+        }));
+    }
+}
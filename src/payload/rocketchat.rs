@@ -0,0 +1,132 @@
+use serde::Serialize;
+use crate::payload::discord::{DiscordWebhookPayload, Embed};
+use crate::render::color;
+
+/// for more information, see https://docs.rocket.chat/use-rocket.chat/workspace-administration/integrations#incoming-webhook-script
+#[derive(Serialize)]
+pub(crate) struct RocketChatWebhookPayload {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub(crate) text: String,
+    /// `#channel` or `@user`; overrides the channel configured on the integration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) channel: Option<String>,
+    /// display name of the sender
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) alias: Option<String>,
+    /// avatar URL of the sender
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) avatar: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) attachments: Vec<RocketChatAttachment>,
+}
+
+/// https://developer.rocket.chat/reference/api/rest-api/endpoints/core-endpoints/chat-endpoints/postmessage#attachments-detail
+#[derive(Serialize, Default)]
+pub(crate) struct RocketChatAttachment {
+    /// `#RRGGBB`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) author_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) title_link: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) text: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) fields: Vec<RocketChatAttachmentField>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) collapsed: bool,
+}
+
+#[derive(Serialize)]
+pub(crate) struct RocketChatAttachmentField {
+    pub(crate) title: String,
+    pub(crate) value: String,
+    pub(crate) short: bool,
+}
+
+impl From<&DiscordWebhookPayload> for RocketChatWebhookPayload {
+    fn from(payload: &DiscordWebhookPayload) -> Self {
+        Self {
+            text: payload.content.clone(),
+            channel: None,
+            alias: payload.username.clone(),
+            avatar: payload.avatar_url.clone(),
+            attachments: payload.embeds.0.iter().map(RocketChatAttachment::from).collect(),
+        }
+    }
+}
+
+impl From<&Embed> for RocketChatAttachment {
+    fn from(embed: &Embed) -> Self {
+        // Rocket.Chat has no footer on attachment, so it is appended to the text
+        let text = match (&embed.description, &embed.footer) {
+            (Some(description), Some(footer)) => Some(format!("{description}\n\n_{}_", footer.text)),
+            (Some(description), None) => Some(description.clone()),
+            (None, Some(footer)) => Some(format!("_{}_", footer.text)),
+            (None, None) => None,
+        };
+        Self {
+            color: embed.color.map(color::hex),
+            author_name: embed.author.as_ref().map(|author| author.name.clone()),
+            title: embed.title.clone(),
+            title_link: embed.url.clone(),
+            text,
+            fields: embed.fields.0.iter()
+                .map(|field| RocketChatAttachmentField {
+                    title: field.name.clone(),
+                    value: field.value.clone(),
+                    short: field.value.chars().count() <= 40,
+                })
+                .collect(),
+            collapsed: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn embed_is_mapped_to_an_attachment() {
+        let payload: DiscordWebhookPayload = serde_json::from_value(json!({
+            "username": "Todoist",
+            "avatar_url": "https://example.com/todoist.png",
+            "embeds": [{
+                "author": {"name": "Alice"},
+                "title": "Buy milk",
+                "url": "https://todoist.com/showTask?id=1",
+                "description": "2 bottles",
+                "color": 0x00D1_453B,
+                "fields": [{"name": "Priority", "value": "P1"}, {"name": "Note", "value": "x".repeat(41)}],
+                "footer": {"text": "item:completed"},
+            }],
+        })).unwrap();
+        let payload = RocketChatWebhookPayload::from(&payload);
+        assert_eq!(serde_json::to_value(&payload).unwrap(), json!({
+            "alias": "Todoist",
+            "avatar": "https://example.com/todoist.png",
+            "attachments": [{
+                "color": "#D1453B",
+                "author_name": "Alice",
+                "title": "Buy milk",
+                "title_link": "https://todoist.com/showTask?id=1",
+                "text": "2 bottles\n\n_item:completed_",
+                "fields": [{"title": "Priority", "value": "P1", "short": true}, {"title": "Note", "value": "x".repeat(41), "short": false}],
+            }],
+        }));
+    }
+
+    #[test]
+    fn footer_alone_becomes_the_text() {
+        let payload: DiscordWebhookPayload = serde_json::from_value(json!({"embeds": [{"title": "Buy milk", "footer": {"text": "item:completed"}}]})).unwrap();
+        let payload = RocketChatWebhookPayload::from(&payload);
+        assert_eq!(serde_json::to_value(&payload).unwrap(), json!({
+            "attachments": [{"title": "Buy milk", "text": "_item:completed_"}],
+        }));
+    }
+}
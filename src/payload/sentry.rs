@@ -0,0 +1,246 @@
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::event::NormalizedEvent;
+use crate::render::color::{BLUE, GREEN, GREY, RED, YELLOW};
+use crate::render::summary::{field, Actor, Service, Summary};
+
+const SENTRY: Service = Service {
+    name: "Sentry",
+    avatar_url: "https://sentry-brand.storage.googleapis.com/sentry-glyph-black.png",
+};
+
+/// the kind is given by the `Sentry-Hook-Resource` header.
+/// for more information, see https://docs.sentry.io/organization/integrations/integration-platform/webhooks/
+#[derive(Clone)]
+pub(crate) enum SentryWebhook {
+    /// `created`, `resolved`, `assigned`, `archived` or `unresolved`
+    Issue { action: String, issue: Issue, actor: Option<SentryActor> },
+    /// an issue alert rule fired
+    EventAlert { event: Event, rule: Option<String> },
+    Error { action: String, error: Event },
+    Other { resource: String, action: String },
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    #[serde(default)]
+    action: String,
+    #[serde(default)]
+    data: serde_json::Value,
+    #[serde(default)]
+    actor: Option<SentryActor>,
+}
+
+#[derive(Deserialize)]
+struct IssueData {
+    issue: Issue,
+}
+
+#[derive(Deserialize)]
+struct EventAlertData {
+    event: Event,
+    #[serde(default)]
+    triggered_rule: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ErrorData {
+    error: Event,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct SentryActor {
+    /// `user`, `application` or `sentry`
+    #[serde(rename = "type")]
+    pub(crate) kind: String,
+    pub(crate) name: String,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Issue {
+    pub(crate) short_id: String,
+    pub(crate) title: String,
+    #[serde(default)]
+    pub(crate) culprit: Option<String>,
+    #[serde(default)]
+    pub(crate) level: Option<String>,
+    #[serde(default, rename = "web_url")]
+    pub(crate) web_url: Option<String>,
+    #[serde(default)]
+    pub(crate) permalink: Option<String>,
+    pub(crate) project: Project,
+    /// number of events as a decimal string
+    #[serde(default)]
+    pub(crate) count: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Project {
+    pub(crate) slug: String,
+}
+
+/// a single error (event)
+#[derive(Deserialize, Clone)]
+pub(crate) struct Event {
+    pub(crate) title: String,
+    #[serde(default)]
+    pub(crate) culprit: Option<String>,
+    #[serde(default)]
+    pub(crate) level: Option<String>,
+    #[serde(default)]
+    pub(crate) environment: Option<String>,
+    #[serde(default)]
+    pub(crate) release: Option<String>,
+    /// pairs of key and value, e.g. `[["environment", "production"]]`
+    #[serde(default)]
+    pub(crate) tags: Vec<(String, String)>,
+    #[serde(default)]
+    pub(crate) web_url: Option<String>,
+}
+
+impl Event {
+    fn tag(&self, key: &str) -> Option<&String> {
+        self.tags.iter().find(|(name, _)| name == key).map(|(_, value)| value)
+    }
+
+    fn environment(&self) -> Option<&String> {
+        self.environment.as_ref().or_else(|| self.tag("environment"))
+    }
+
+    fn release(&self) -> Option<&String> {
+        self.release.as_ref().or_else(|| self.tag("release"))
+    }
+}
+
+fn level_color(level: Option<&str>) -> u32 {
+    match level {
+        Some("fatal" | "error") => RED,
+        Some("warning") => YELLOW,
+        Some("info") => BLUE,
+        _ => GREY,
+    }
+}
+
+/// `culprit` is where the error happened, e.g. `app.views in index`
+fn culprit(culprit: Option<&String>) -> Option<String> {
+    culprit.filter(|culprit| !culprit.is_empty()).map(|culprit| format!("`{culprit}`"))
+}
+
+impl SentryWebhook {
+    pub(crate) fn parse(resource: &str, raw: serde_json::Value) -> serde_json::Result<Self> {
+        let envelope = Envelope::deserialize(raw)?;
+        Ok(match resource {
+            "issue" => {
+                let data = IssueData::deserialize(envelope.data)?;
+                Self::Issue { action: envelope.action, issue: data.issue, actor: envelope.actor }
+            }
+            "event_alert" => {
+                let data = EventAlertData::deserialize(envelope.data)?;
+                Self::EventAlert { event: data.event, rule: data.triggered_rule }
+            }
+            "error" => Self::Error { action: envelope.action, error: ErrorData::deserialize(envelope.data)?.error },
+            _ => Self::Other { resource: resource.to_string(), action: envelope.action },
+        })
+    }
+
+    /// e.g. `issue.created`
+    fn name(&self) -> String {
+        match self {
+            Self::Issue { action, .. } => format!("issue.{action}"),
+            Self::EventAlert { .. } => "event_alert.triggered".to_string(),
+            Self::Error { action, .. } => format!("error.{action}"),
+            Self::Other { resource, action } => format!("{resource}.{action}"),
+        }
+    }
+
+    fn actor(&self) -> Option<Actor> {
+        match self {
+            Self::Issue { actor: Some(actor), .. } if actor.kind == "user" => Some(Actor {
+                name: actor.name.clone(),
+                url: None,
+                avatar_url: None,
+            }),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn to_webhook(&self) -> DiscordWebhookPayload {
+        Summary::from(self).into_webhook(&SENTRY, self.actor(), self.name())
+    }
+
+    pub(crate) fn to_event(&self) -> NormalizedEvent {
+        let summary = Summary::from(self);
+        let (project, labels) = match self {
+            Self::Issue { issue, .. } => (Some(issue.project.slug.clone()), issue.level.iter().cloned().collect()),
+            Self::EventAlert { event, .. } | Self::Error { error: event, .. } => {
+                (None, event.environment().cloned().into_iter().chain(event.level.clone()).collect())
+            }
+            Self::Other { .. } => (None, vec![]),
+        };
+        NormalizedEvent {
+            source: "sentry",
+            event: self.name(),
+            title: summary.title,
+            body: summary.description,
+            url: Some(summary.url).filter(|url| !url.is_empty()),
+            project,
+            priority: None,
+            labels,
+            initiator: self.actor().map(|actor| actor.name),
+            received_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+fn event_summary(event: &Event, title: String) -> Summary {
+    let mut fields = vec![];
+    if let Some(environment) = event.environment() {
+        fields.push(field("Environment", environment.clone()));
+    }
+    if let Some(release) = event.release() {
+        fields.push(field("Release", release.clone()));
+    }
+    if let Some(level) = &event.level {
+        fields.push(field("Level", level.clone()));
+    }
+    Summary {
+        title,
+        url: event.web_url.clone().unwrap_or_default(),
+        description: culprit(event.culprit.as_ref()),
+        color: level_color(event.level.as_deref()),
+        fields,
+    }
+}
+
+impl From<&SentryWebhook> for Summary {
+    fn from(webhook: &SentryWebhook) -> Self {
+        match webhook {
+            SentryWebhook::Issue { action, issue, .. } => {
+                let mut fields = vec![field("Project", issue.project.slug.clone())];
+                if let Some(count) = &issue.count {
+                    fields.push(field("Events", count.clone()));
+                }
+                Self {
+                    title: format!("[{}] Issue {action}: {}", issue.short_id, issue.title),
+                    url: issue.web_url.clone().or_else(|| issue.permalink.clone()).unwrap_or_default(),
+                    description: culprit(issue.culprit.as_ref()),
+                    color: if action == "resolved" { GREEN } else { level_color(issue.level.as_deref()) },
+                    fields,
+                }
+            }
+            SentryWebhook::EventAlert { event, rule } => {
+                let title = rule.as_ref().map_or_else(|| event.title.clone(), |rule| format!("[{rule}] {}", event.title));
+                event_summary(event, title)
+            }
+            SentryWebhook::Error { error, .. } => event_summary(error, error.title.clone()),
+            SentryWebhook::Other { resource, action } => Self {
+                title: format!("{resource} {action}"),
+                url: String::new(),
+                description: None,
+                color: GREY,
+                fields: vec![],
+            },
+        }
+    }
+}
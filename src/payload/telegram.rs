@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use crate::payload::discord::{DiscordWebhookPayload, Embed};
+use crate::render::html;
+
+/// Text of the message to be sent, 1-4096 characters after entities parsing
+const MAX_TEXT_LENGTH: usize = 4096;
+
+/// for more information, see https://core.telegram.org/bots/api#sendmessage
+#[derive(Serialize)]
+pub(crate) struct SendMessage {
+    pub(crate) chat_id: ChatID,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) message_thread_id: Option<i64>,
+    pub(crate) text: String,
+    pub(crate) parse_mode: ParseMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) reply_markup: Option<InlineKeyboardMarkup>,
+}
+
+/// Unique identifier for the target chat or username of the target channel (in the format `@channelusername`)
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(untagged)]
+pub(crate) enum ChatID {
+    ID(i64),
+    Username(String),
+}
+
+/// https://core.telegram.org/bots/api#formatting-options
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Default)]
+pub(crate) enum ParseMode {
+    #[default]
+    #[serde(rename = "HTML")]
+    Html,
+    MarkdownV2,
+}
+
+/// https://core.telegram.org/bots/api#inlinekeyboardmarkup
+#[derive(Serialize)]
+pub(crate) struct InlineKeyboardMarkup {
+    /// Array of button rows
+    pub(crate) inline_keyboard: Vec<Vec<InlineKeyboardButton>>,
+}
+
+/// https://core.telegram.org/bots/api#inlinekeyboardbutton
+#[derive(Serialize)]
+pub(crate) struct InlineKeyboardButton {
+    pub(crate) text: String,
+    pub(crate) url: String,
+}
+
+impl SendMessage {
+    pub(crate) fn render(payload: &DiscordWebhookPayload, chat_id: ChatID, message_thread_id: Option<i64>, parse_mode: ParseMode) -> Self {
+        let mut blocks = vec![];
+        if !payload.content.is_empty() {
+            blocks.push(vec![Line::Text(&payload.content)]);
+        }
+        blocks.extend(payload.embeds.0.iter().map(|embed| render_embed(embed, parse_mode)));
+
+        // one button per row, so that long titles are not truncated
+        let buttons = payload.embeds.0.iter()
+            .filter_map(|embed| {
+                embed.url.as_ref().map(|url| vec![InlineKeyboardButton {
+                    text: embed.title.clone().unwrap_or_else(|| "Open".to_string()),
+                    url: url.clone(),
+                }])
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            chat_id,
+            message_thread_id,
+            text: join(&blocks, parse_mode),
+            parse_mode,
+            reply_markup: if buttons.is_empty() { None } else { Some(InlineKeyboardMarkup { inline_keyboard: buttons }) },
+        }
+    }
+}
+
+/// output for a single line
+enum Line<'a> {
+    /// never cut, as it contains bold text and links
+    Markup(String),
+    /// text before escaping. Cut in the middle when over the limit.
+    Text(&'a str),
+}
+
+/// Telegram counts the length in UTF-16 code units
+fn length(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// blocks are separated by a blank line, and lines by a line break.
+/// when over the limit, the text is cut by its escaped length and ends with `…`.
+/// the markup is counted as well, so the actual length may be shorter than the limit.
+fn join(blocks: &[Vec<Line>], parse_mode: ParseMode) -> String {
+    let lines = blocks.iter().enumerate().flat_map(|(i, block)| {
+        block.iter().enumerate().map(move |(j, line)| {
+            let separator = match (i, j) {
+                (0, 0) => "",
+                (_, 0) => "\n\n",
+                _ => "\n",
+            };
+            (separator, line)
+        })
+    });
+    let mut text = String::new();
+    for (separator, line) in lines {
+        let rendered = match line {
+            Line::Markup(markup) => markup.clone(),
+            Line::Text(raw) => parse_mode.escape(raw),
+        };
+        if length(&text) + length(separator) + length(&rendered) <= MAX_TEXT_LENGTH {
+            text.push_str(separator);
+            text.push_str(&rendered);
+            continue;
+        }
+        // room for the separator and `…`
+        let Some(budget) = MAX_TEXT_LENGTH.checked_sub(length(&text) + length(separator) + 1) else {
+            break
+        };
+        text.push_str(separator);
+        if let Line::Text(raw) = line {
+            let mut used = 0;
+            for c in raw.chars() {
+                let escaped = parse_mode.escape(c.encode_utf8(&mut [0; 4]));
+                used += length(&escaped);
+                if used > budget {
+                    break
+                }
+                text.push_str(&escaped);
+            }
+        }
+        text.push('…');
+        break
+    }
+    text
+}
+
+impl ParseMode {
+    fn escape(self, text: &str) -> String {
+        match self {
+            Self::Html => html::escape(text),
+            Self::MarkdownV2 => escape_markdown_v2(text),
+        }
+    }
+
+    fn bold(self, text: &str) -> String {
+        match self {
+            Self::Html => format!("<b>{}</b>", html::escape(text)),
+            Self::MarkdownV2 => format!("*{}*", escape_markdown_v2(text)),
+        }
+    }
+
+    fn italic(self, text: &str) -> String {
+        match self {
+            Self::Html => format!("<i>{}</i>", html::escape(text)),
+            Self::MarkdownV2 => format!("_{}_", escape_markdown_v2(text)),
+        }
+    }
+
+    fn link(self, text: &str, url: &str) -> String {
+        match self {
+            Self::Html => format!("<a href=\"{}\">{}</a>", html::escape(url), html::escape(text)),
+            Self::MarkdownV2 => format!("[{}]({})", escape_markdown_v2(text), escape_markdown_v2_url(url)),
+        }
+    }
+}
+
+fn render_embed(embed: &Embed, parse_mode: ParseMode) -> Vec<Line<'_>> {
+    let mut lines = vec![];
+    if let Some(author) = &embed.author {
+        lines.push(Line::Markup(parse_mode.italic(&author.name)));
+    }
+    match (&embed.title, &embed.url) {
+        (Some(title), Some(url)) => lines.push(Line::Markup(parse_mode.link(title, url))),
+        (Some(title), None) => lines.push(Line::Markup(parse_mode.bold(title))),
+        (None, Some(url)) => lines.push(Line::Markup(parse_mode.link(url, url))),
+        (None, None) => {}
+    }
+    if let Some(description) = &embed.description {
+        lines.push(Line::Text(description));
+    }
+    for field in &embed.fields.0 {
+        lines.push(Line::Markup(format!("{}: {}", parse_mode.bold(&field.name), parse_mode.escape(&field.value))));
+    }
+    if let Some(footer) = &embed.footer {
+        lines.push(Line::Markup(parse_mode.italic(&footer.text)));
+    }
+    lines
+}
+
+/// In all other places characters `_`, `*`, `[`, `]`, `(`, `)`, `~`, `` ` ``, `>`, `#`, `+`, `-`, `=`, `|`, `{`, `}`, `.`, `!`
+/// must be escaped with the preceding character `\`.
+fn escape_markdown_v2(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '_' | '*' | '[' | ']' | '(' | ')' | '~' | '`' | '>' | '#' | '+' | '-' | '=' | '|' | '{' | '}' | '.' | '!') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Inside `(...)` part of inline link, all `)` and `\` must be escaped with a preceding `\` character.
+fn escape_markdown_v2_url(url: &str) -> String {
+    let mut escaped = String::with_capacity(url.len());
+    for c in url.chars() {
+        if matches!(c, '\\' | ')') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::summary::{Service, Summary};
+
+    const SERVICE: Service = Service {
+        name: "Test",
+        avatar_url: "https://example.com/avatar.png",
+    };
+
+    fn payload(description: &str) -> DiscordWebhookPayload {
+        Summary {
+            title: "Deploy <prod>".to_string(),
+            url: "https://example.com/deploys/1".to_string(),
+            description: Some(description.to_string()),
+            color: 0,
+            fields: vec![],
+        }.into_webhook(&SERVICE, None, "deploy".to_string())
+    }
+
+    fn render(description: &str, parse_mode: ParseMode) -> String {
+        SendMessage::render(&payload(description), ChatID::ID(1), None, parse_mode).text
+    }
+
+    #[test]
+    fn short_text_is_kept() {
+        let text = render("done & dusted", ParseMode::Html);
+        assert!(text.contains("<a href=\"https://example.com/deploys/1\">Deploy &lt;prod&gt;</a>"));
+        assert!(text.contains("done &amp; dusted"));
+        assert!(!text.contains('…'));
+    }
+
+    #[test]
+    fn html_is_truncated_after_escaping() {
+        let text = render(&"&".repeat(4000), ParseMode::Html);
+        assert!(length(&text) <= MAX_TEXT_LENGTH);
+        assert!(text.contains("<a href=\"https://example.com/deploys/1\">Deploy &lt;prod&gt;</a>"));
+        // no half of `&amp;` before the ellipsis
+        assert!(text.ends_with("&amp;…"));
+    }
+
+    #[test]
+    fn markdown_v2_is_truncated_after_escaping() {
+        let text = render(&".".repeat(4000), ParseMode::MarkdownV2);
+        assert!(length(&text) <= MAX_TEXT_LENGTH);
+        assert!(text.contains("[Deploy <prod\\>](https://example.com/deploys/1)"));
+        assert!(text.ends_with("\\.…"));
+    }
+
+    #[test]
+    fn length_is_counted_in_utf16() {
+        let text = render(&"🎉".repeat(3000), ParseMode::Html);
+        assert!(length(&text) <= MAX_TEXT_LENGTH);
+        assert!(text.ends_with("🎉…"));
+    }
+}
@@ -1,9 +1,9 @@
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde::de::{Error, Unexpected};
 use iso8601::{DateTime};
 use crate::serde_integration::deserializers::*;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct TodoistPayload {
     pub user_id: i64,
     #[serde(flatten)]
@@ -14,176 +14,141 @@ pub struct TodoistPayload {
 }
 
 /// for all events, see https://developer.todoist.com/sync/v8/#configuration
-#[derive(Deserialize)]
-#[serde(tag = "event_name")]
+#[derive(Deserialize, Clone)]
+#[serde(tag = "event_name", content = "event_data")]
 pub enum TodoistEvent {
     // TODO: replace those boilerplate with proc-macro
     #[serde(rename = "item:added")]
-    ItemAdded(
-        #[serde(rename = "event_data")]
-        TodoistItem
-    ),
+    ItemAdded(TodoistItem),
     #[serde(rename = "item:removed")]
-    ItemRemoved(
-        #[serde(rename = "event_data")]
-        TodoistItem
-    ),
+    ItemRemoved(TodoistItem),
     #[serde(rename = "item:deleted")]
-    ItemDeleted(
-        #[serde(rename = "event_data")]
-        TodoistItem
-    ),
+    ItemDeleted(TodoistItem),
     #[serde(rename = "item:completed")]
-    ItemCompleted(
-        #[serde(rename = "event_data")]
-        TodoistItem
-    ),
+    ItemCompleted(TodoistItem),
     #[serde(rename = "item:uncompleted")]
-    ItemUncompleted(
-        #[serde(rename = "event_data")]
-        TodoistItem
-    ),
+    ItemUncompleted(TodoistItem),
     #[serde(rename = "note:added")]
-    NoteAdded(
-        #[serde(rename = "event_data")]
-        TodoistNote
-    ),
+    NoteAdded(TodoistNote),
     #[serde(rename = "note:updated")]
-    NoteUpdated(
-        #[serde(rename = "event_data")]
-        TodoistNote
-    ),
+    NoteUpdated(TodoistNote),
     #[serde(rename = "note:deleted")]
-    NoteDeleted(
-        #[serde(rename = "event_data")]
-        TodoistNote
-    ),
+    NoteDeleted(TodoistNote),
     #[serde(rename = "project:added")]
-    ProjectAdded(
-        #[serde(rename = "event_data")]
-        TodoistProject
-    ),
+    ProjectAdded(TodoistProject),
     #[serde(rename = "project:updated")]
-    ProjectUpdated(
-        #[serde(rename = "event_data")]
-        TodoistProject
-    ),
+    ProjectUpdated(TodoistProject),
     #[serde(rename = "project:deleted")]
-    ProjectDeleted(
-        #[serde(rename = "event_data")]
-        TodoistProject
-    ),
+    ProjectDeleted(TodoistProject),
     #[serde(rename = "project:archived")]
-    ProjectArchived(
-        #[serde(rename = "event_data")]
-        TodoistProject
-    ),
+    ProjectArchived(TodoistProject),
     #[serde(rename = "project:unarchived")]
-    ProjectUnarchived(
-        #[serde(rename = "event_data")]
-        TodoistProject
-    ),
+    ProjectUnarchived(TodoistProject),
     #[serde(rename = "section:added")]
-    SectionAdded(
-        #[serde(rename = "event_data")]
-        TodoistSession
-    ),
+    SectionAdded(TodoistSession),
     #[serde(rename = "section:updated")]
-    SectionUpdated(
-        #[serde(rename = "event_data")]
-        TodoistSession
-    ),
+    SectionUpdated(TodoistSession),
     #[serde(rename = "section:deleted")]
-    SectionDeleted(
-        #[serde(rename = "event_data")]
-        TodoistSession
-    ),
+    SectionDeleted(TodoistSession),
     #[serde(rename = "section:archived")]
-    SectionArchived(
-        #[serde(rename = "event_data")]
-        TodoistSession
-    ),
+    SectionArchived(TodoistSession),
     #[serde(rename = "section:unarchived")]
-    SectionUnarchived(
-        #[serde(rename = "event_data")]
-        TodoistSession
-    ),
+    SectionUnarchived(TodoistSession),
     #[serde(rename = "label:added")]
-    LabelAdded(
-        #[serde(rename = "event_data")]
-        TodoistSession
-    ),
+    LabelAdded(TodoistSession),
     #[serde(rename = "label:deleted")]
-    LabelDeleted(
-        #[serde(rename = "event_data")]
-        TodoistSession
-    ),
+    LabelDeleted(TodoistSession),
     #[serde(rename = "label:updated")]
-    LabelUpdated(
-        #[serde(rename = "event_data")]
-        TodoistSession
-    ),
+    LabelUpdated(TodoistSession),
     #[serde(rename = "filter:added")]
-    FilterAdded(
-        #[serde(rename = "event_data")]
-        TodoistFilter
-    ),
+    FilterAdded(TodoistFilter),
     #[serde(rename = "filter:deleted")]
-    FilterDeleted(
-        #[serde(rename = "event_data")]
-        TodoistFilter
-    ),
+    FilterDeleted(TodoistFilter),
     #[serde(rename = "filter:updated")]
-    FilterUpdated(
-        #[serde(rename = "event_data")]
-        TodoistFilter
-    ),
+    FilterUpdated(TodoistFilter),
     #[serde(rename = "reminder:fired")]
-    ReminderFired(
-        #[serde(rename = "event_data")]
-        TodoistReminder
-    ),
+    ReminderFired(TodoistReminder),
 
 
 }
 
+impl TodoistEvent {
+    /// `event_name` as sent by Todoist, e.g. `item:added`
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::ItemAdded(_) => "item:added",
+            Self::ItemRemoved(_) => "item:removed",
+            Self::ItemDeleted(_) => "item:deleted",
+            Self::ItemCompleted(_) => "item:completed",
+            Self::ItemUncompleted(_) => "item:uncompleted",
+            Self::NoteAdded(_) => "note:added",
+            Self::NoteUpdated(_) => "note:updated",
+            Self::NoteDeleted(_) => "note:deleted",
+            Self::ProjectAdded(_) => "project:added",
+            Self::ProjectUpdated(_) => "project:updated",
+            Self::ProjectDeleted(_) => "project:deleted",
+            Self::ProjectArchived(_) => "project:archived",
+            Self::ProjectUnarchived(_) => "project:unarchived",
+            Self::SectionAdded(_) => "section:added",
+            Self::SectionUpdated(_) => "section:updated",
+            Self::SectionDeleted(_) => "section:deleted",
+            Self::SectionArchived(_) => "section:archived",
+            Self::SectionUnarchived(_) => "section:unarchived",
+            Self::LabelAdded(_) => "label:added",
+            Self::LabelDeleted(_) => "label:deleted",
+            Self::LabelUpdated(_) => "label:updated",
+            Self::FilterAdded(_) => "filter:added",
+            Self::FilterDeleted(_) => "filter:deleted",
+            Self::FilterUpdated(_) => "filter:updated",
+            Self::ReminderFired(_) => "reminder:fired",
+        }
+    }
+}
+
 /// please see https://developer.todoist.com/sync/v8/#items
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct TodoistItem {
-    id: TaskID,
-    legacy_id: Option<LegacyTaskID>,
-    user_id: UserID,
-    project_id: ProjectID,
-    legacy_project_id: Option<LegacyProjectID>,
-    content: String,
-    description: String,
-    due: Due,
-    priority: TodoistPriority,
-    parent_id: Option<TaskID>,
-    legacy_parent_id: Option<LegacyTaskID>,
-    child_order: u32,
-    section_id: SectionID,
-    day_order: u32,
+    pub id: TaskID,
+    pub legacy_id: Option<LegacyTaskID>,
+    pub user_id: UserID,
+    pub project_id: ProjectID,
+    pub legacy_project_id: Option<LegacyProjectID>,
+    pub content: String,
+    pub description: String,
+    pub due: Option<Due>,
+    pub priority: TodoistPriority,
+    pub parent_id: Option<TaskID>,
+    pub legacy_parent_id: Option<LegacyTaskID>,
+    pub child_order: u32,
+    pub section_id: Option<SectionID>,
+    /// `-1` when the task is not in Today
+    pub day_order: i32,
     #[serde(deserialize_with = "deserialize_one_zero_bool")]
-    collapsed: bool,
-    labels: Vec<TaskID>,
+    pub collapsed: bool,
+    #[serde(deserialize_with = "deserialize_labels")]
+    pub labels: Vec<String>,
     /// The UserID who created the task. This value is set to null on tasks created before 2019/10/31.
-    added_by_uid: Option<UserID>,
-    assigned_by_uid: UserID,
-    responsible_uid: Option<UserID>,
+    pub added_by_uid: Option<UserID>,
+    pub assigned_by_uid: Option<UserID>,
+    pub responsible_uid: Option<UserID>,
     #[serde(rename = "checked", deserialize_with = "deserialize_one_zero_bool")]
-    completed: bool,
+    pub completed: bool,
     #[serde(deserialize_with = "deserialize_one_zero_bool")]
-    in_history: bool,
+    pub in_history: bool,
     #[serde(deserialize_with = "deserialize_one_zero_bool")]
-    is_deleted: bool,
-    sync_id: Option<SyncID>,
-    date_completed: TodoistDate,
-    date_added: TodoistDate,
+    pub is_deleted: bool,
+    pub sync_id: Option<SyncID>,
+    /// e.g. `2014-09-26T08:25:05Z`. `null` until the task is completed
+    #[serde(default)]
+    pub date_completed: Option<String>,
+    /// e.g. `2014-09-26T08:25:05Z`
+    #[serde(default)]
+    pub date_added: Option<String>,
 }
 
-#[derive(Deserialize)]
-enum TodoistPriority {
+#[derive(Deserialize, Serialize, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(try_from = "u8")]
+pub enum TodoistPriority {
     // raw: 4
     P1,
     // raw: 3
@@ -194,18 +159,58 @@ enum TodoistPriority {
     P4,
 }
 
+impl TryFrom<u8> for TodoistPriority {
+    type Error = String;
+
+    fn try_from(raw: u8) -> Result<Self, Self::Error> {
+        match raw {
+            4 => Ok(Self::P1),
+            3 => Ok(Self::P2),
+            2 => Ok(Self::P3),
+            1 => Ok(Self::P4),
+            _ => Err(format!("priority must be in 1..=4, but got {raw}"))
+        }
+    }
+}
+
+impl TodoistPriority {
+    /// colour of the priority flag in the Todoist clients
+    pub const fn color(self) -> u32 {
+        match self {
+            Self::P1 => 0x00D1_453B,
+            Self::P2 => 0x00EB_8909,
+            Self::P3 => 0x0024_6FE0,
+            Self::P4 => 0x0080_8080,
+        }
+    }
+}
+
+impl std::fmt::Display for TodoistPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Self::P1 => "P1",
+            Self::P2 => "P2",
+            Self::P3 => "P3",
+            Self::P4 => "P4",
+        };
+        f.write_str(label)
+    }
+}
+
 /// please see https://developer.todoist.com/sync/v8/#collaborators
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct TodoistCollaborator {
-    id: UserID,
-    email: String,
-    full_name: String,
-    timezone: String,
-    image_id: ImageID
+    pub id: UserID,
+    pub email: String,
+    pub full_name: String,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// `null` when the user has no avatar
+    pub image_id: Option<ImageID>
 }
 
 /// please see https://developer.todoist.com/sync/v8/#item-notes
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct TodoistNote {
     pub id: NoteID,
     pub legacy_id: LegacyNoteID,
@@ -227,7 +232,7 @@ pub struct TodoistNote {
 
 
 /// https://developer.todoist.com/sync/v8/#file-attachments
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct TodoistFileAttachment {
     #[serde(rename = "file_name")]
     name: String,
@@ -242,7 +247,7 @@ pub struct TodoistFileAttachment {
     upload_state: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct Reactions {
     #[serde(rename = "♥", default = "none")]
     love: Option<Vec<UserID>>,
@@ -254,58 +259,168 @@ const fn none<T>() -> Option<T> {
     None
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct TodoistProject {
     // TODO: fill fields
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct TodoistSession {
     // TODO: fill fields
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct TodoistFilter {
     // TODO: fill fields
 }
 
-#[derive(Deserialize)]
+/// please see https://developer.todoist.com/sync/v8/#reminders
+#[derive(Deserialize, Clone)]
 pub struct TodoistReminder {
-    // TODO: fill fields
+    pub id: ReminderID,
+    /// The user ID which should be notified of the reminder
+    pub notify_uid: UserID,
+    pub item_id: TaskID,
+    /// `relative`, `absolute` or `location`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// only for `absolute` reminders
+    pub due: Option<Due>,
+    /// only for `relative` reminders
+    pub mm_offset: Option<i32>,
+    #[serde(deserialize_with = "deserialize_one_zero_bool")]
+    pub is_deleted: bool,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct TaskID(i64);
 
-#[derive(Deserialize)]
+impl TaskID {
+    /// URL to open the task in a browser or the app
+    pub fn url(&self) -> String {
+        format!("https://todoist.com/showTask?id={}", self.0)
+    }
+}
+
+#[derive(Deserialize, Clone)]
 pub struct LegacyTaskID(i64);
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct UserID(i64);
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct ProjectID(i64);
 
-#[derive(Deserialize)]
+impl std::fmt::Display for ProjectID {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Deserialize, Clone)]
 pub struct LegacyProjectID(i64);
 
-#[derive(Deserialize)]
-pub struct Due(i64); // TODO: this seems invalid
+/// please see https://developer.todoist.com/sync/v8/#due-dates
+#[derive(Deserialize, Clone)]
+pub struct Due {
+    /// `YYYY-MM-DD` for full-day, `YYYY-MM-DDTHH:MM:SS` for floating, `YYYY-MM-DDTHH:MM:SSZ` for fixed timezone
+    pub date: String,
+    pub timezone: Option<String>,
+    /// human-readable representation, such as "every day"
+    pub string: String,
+    pub lang: String,
+    pub is_recurring: bool,
+}
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct SectionID(i64);
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct SyncID(i64); // TODO: this seems invalid
 
-#[derive(Deserialize)]
-pub struct ImageID(i64);
+#[derive(Deserialize, Clone)]
+pub struct ImageID(String);
 
-#[derive(Deserialize)]
-pub struct TodoistDate;
+#[derive(Deserialize, Clone)]
+pub struct ReminderID(i64);
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct NoteID(i64);
 
-#[derive(Deserialize)]
-pub struct LegacyNoteID(i64);
\ No newline at end of file
+#[derive(Deserialize, Clone)]
+pub struct LegacyNoteID(i64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the example of `item:added` on https://developer.todoist.com/sync/v8/#webhooks
+    const ITEM_ADDED: &str = r#"{
+        "event_name": "item:added",
+        "user_id": 1234,
+        "event_data": {
+            "added_by_uid": 1234,
+            "assigned_by_uid": null,
+            "checked": 0,
+            "child_order": 3,
+            "collapsed": 0,
+            "content": "Buy Milk",
+            "date_added": "2014-09-26T08:25:05Z",
+            "date_completed": null,
+            "day_order": -1,
+            "description": "",
+            "due": null,
+            "id": 2995104339,
+            "in_history": 0,
+            "is_deleted": 0,
+            "labels": [],
+            "legacy_id": 33511505,
+            "legacy_parent_id": null,
+            "legacy_project_id": 128501470,
+            "parent_id": null,
+            "priority": 1,
+            "project_id": 2203306141,
+            "responsible_uid": null,
+            "section_id": null,
+            "sync_id": null,
+            "user_id": 1234
+        },
+        "initiator": {
+            "email": "alice@example.com",
+            "full_name": "Alice",
+            "id": 1234,
+            "image_id": "ad38375bdb094286af59f1eab36d8f20",
+            "is_premium": true
+        },
+        "version_number": "8"
+    }"#;
+
+    #[test]
+    fn parse_item_added() {
+        let payload: TodoistPayload = serde_json::from_str(ITEM_ADDED).unwrap();
+        assert_eq!(payload.event.name(), "item:added");
+        let TodoistEvent::ItemAdded(item) = payload.event else { panic!("not item:added") };
+        assert_eq!(item.content, "Buy Milk");
+        assert!(item.priority == TodoistPriority::P4);
+        assert_eq!(item.date_added.as_deref(), Some("2014-09-26T08:25:05Z"));
+        assert!(item.date_completed.is_none());
+        assert!(item.section_id.is_none());
+        assert_eq!(item.id.url(), "https://todoist.com/showTask?id=2995104339");
+        assert_eq!(payload.initiator.full_name, "Alice");
+    }
+
+    #[test]
+    fn parse_item_completed() {
+        let raw = ITEM_ADDED
+            .replace("\"item:added\"", "\"item:completed\"")
+            .replace("\"checked\": 0", "\"checked\": 1")
+            .replace("\"date_completed\": null", "\"date_completed\": \"2014-09-27T10:00:00Z\"")
+            .replace("\"section_id\": null", "\"section_id\": 7025")
+            .replace("\"labels\": []", "\"labels\": [2156154810]");
+        let payload: TodoistPayload = serde_json::from_str(&raw).unwrap();
+        let TodoistEvent::ItemCompleted(item) = payload.event else { panic!("not item:completed") };
+        assert_eq!(item.date_completed.as_deref(), Some("2014-09-27T10:00:00Z"));
+        assert!(item.section_id.is_some());
+        assert_eq!(item.labels, ["2156154810"]);
+    }
+}
@@ -0,0 +1,250 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use crate::payload::discord::{DiscordWebhookPayload, EmbedField};
+use crate::payload::event::NormalizedEvent;
+use crate::render::color::{BLUE, GREEN, GREY, RED};
+use crate::render::summary::{change, field, Actor, Service, Summary};
+
+const TRELLO: Service = Service {
+    name: "Trello",
+    avatar_url: "https://trello.com/favicon.ico",
+};
+
+/// for more information, see https://developer.atlassian.com/cloud/trello/guides/rest-api/webhooks/
+#[derive(Deserialize, Clone)]
+pub(crate) struct TrelloWebhook {
+    pub(crate) action: TrelloAction,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TrelloAction {
+    #[serde(rename = "type")]
+    pub(crate) kind: ActionType,
+    #[serde(default)]
+    pub(crate) member_creator: Option<TrelloMember>,
+    pub(crate) data: ActionData,
+}
+
+/// for all types, see https://developer.atlassian.com/cloud/trello/guides/rest-api/action-types/
+#[derive(Deserialize, Clone, Eq, PartialEq)]
+#[serde(from = "String")]
+pub(crate) enum ActionType {
+    CreateCard,
+    UpdateCard,
+    DeleteCard,
+    CommentCard,
+    AddMemberToCard,
+    RemoveMemberFromCard,
+    AddLabelToCard,
+    RemoveLabelFromCard,
+    AddAttachmentToCard,
+    UpdateCheckItemStateOnCard,
+    CreateList,
+    UpdateList,
+    Other(String),
+}
+
+impl From<String> for ActionType {
+    fn from(raw: String) -> Self {
+        match raw.as_str() {
+            "createCard" => Self::CreateCard,
+            "updateCard" => Self::UpdateCard,
+            "deleteCard" => Self::DeleteCard,
+            "commentCard" => Self::CommentCard,
+            "addMemberToCard" => Self::AddMemberToCard,
+            "removeMemberFromCard" => Self::RemoveMemberFromCard,
+            "addLabelToCard" => Self::AddLabelToCard,
+            "removeLabelFromCard" => Self::RemoveLabelFromCard,
+            "addAttachmentToCard" => Self::AddAttachmentToCard,
+            "updateCheckItemStateOnCard" => Self::UpdateCheckItemStateOnCard,
+            "createList" => Self::CreateList,
+            "updateList" => Self::UpdateList,
+            _ => Self::Other(raw),
+        }
+    }
+}
+
+impl ActionType {
+    /// `type` as sent by Trello, e.g. `updateCard`
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            Self::CreateCard => "createCard",
+            Self::UpdateCard => "updateCard",
+            Self::DeleteCard => "deleteCard",
+            Self::CommentCard => "commentCard",
+            Self::AddMemberToCard => "addMemberToCard",
+            Self::RemoveMemberFromCard => "removeMemberFromCard",
+            Self::AddLabelToCard => "addLabelToCard",
+            Self::RemoveLabelFromCard => "removeLabelFromCard",
+            Self::AddAttachmentToCard => "addAttachmentToCard",
+            Self::UpdateCheckItemStateOnCard => "updateCheckItemStateOnCard",
+            Self::CreateList => "createList",
+            Self::UpdateList => "updateList",
+            Self::Other(raw) => raw,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TrelloMember {
+    pub(crate) full_name: String,
+    #[serde(default)]
+    pub(crate) avatar_url: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Named {
+    #[serde(default)]
+    pub(crate) name: String,
+}
+
+/// the fields included differ by the kind
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ActionData {
+    #[serde(default)]
+    pub(crate) card: Option<TrelloCard>,
+    #[serde(default)]
+    pub(crate) board: Option<TrelloBoard>,
+    #[serde(default)]
+    pub(crate) list: Option<Named>,
+    #[serde(default)]
+    pub(crate) list_before: Option<Named>,
+    #[serde(default)]
+    pub(crate) list_after: Option<Named>,
+    /// previous values of the updated fields of the card or list
+    #[serde(default)]
+    pub(crate) old: Map<String, Value>,
+    /// body of a comment
+    #[serde(default)]
+    pub(crate) text: Option<String>,
+    #[serde(default)]
+    pub(crate) member: Option<Named>,
+    #[serde(default)]
+    pub(crate) label: Option<Named>,
+    #[serde(default)]
+    pub(crate) attachment: Option<Named>,
+    #[serde(default)]
+    pub(crate) check_item: Option<CheckItem>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TrelloCard {
+    /// absent on `deleteCard`
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    #[serde(default)]
+    pub(crate) short_link: Option<String>,
+    /// new values of the updated fields, e.g. `desc`, `due`, `closed`
+    #[serde(flatten)]
+    pub(crate) rest: Map<String, Value>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TrelloBoard {
+    pub(crate) name: String,
+    pub(crate) short_link: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct CheckItem {
+    pub(crate) name: String,
+    /// `complete` or `incomplete`
+    pub(crate) state: String,
+}
+
+/// shows `null` as `none`, strings as they are, and anything else as JSON
+fn display(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => "none".to_string(),
+        Some(Value::String(text)) if text.is_empty() => "none".to_string(),
+        Some(Value::String(text)) => text.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+impl TrelloWebhook {
+    fn changes(&self, card: &TrelloCard) -> Vec<EmbedField> {
+        let data = &self.action.data;
+        data.old.iter().map(|(key, old)| match key.as_str() {
+            "idList" => change(
+                "list",
+                data.list_before.as_ref().map(|list| list.name.as_str()),
+                data.list_after.as_ref().map_or("none", |list| &list.name),
+            ),
+            // long text is just noted as changed
+            "desc" => change("description", None, "(edited)"),
+            "name" => change("name", Some(&display(Some(old))), card.name.as_deref().unwrap_or("none")),
+            key => change(key, Some(&display(Some(old))), &display(card.rest.get(key))),
+        }).collect()
+    }
+
+    fn summary(&self) -> Summary {
+        let action = &self.action;
+        let data = &action.data;
+        let board = data.board.as_ref().map_or("Trello", |board| &board.name);
+        let card = data.card.as_ref();
+        let card_name = card.and_then(|card| card.name.as_deref()).unwrap_or("card");
+        let url = card.and_then(|card| card.short_link.as_ref()).map(|short_link| format!("https://trello.com/c/{short_link}"))
+            .or_else(|| data.board.as_ref().map(|board| format!("https://trello.com/b/{}", board.short_link)))
+            .unwrap_or_default();
+        let detail = |name: &str, value: Option<&Named>| value.map(|value| field(name, value.name.clone())).into_iter().collect::<Vec<_>>();
+        let (title, description, color, fields) = match &action.kind {
+            ActionType::CreateCard => (format!("Card created: {card_name}"), None, GREEN, detail("List", data.list.as_ref())),
+            ActionType::UpdateCard if data.old.contains_key("idList") => (format!("Card moved: {card_name}"), None, BLUE, card.map(|card| self.changes(card)).unwrap_or_default()),
+            ActionType::UpdateCard if data.old.contains_key("closed") => (format!("Card archived: {card_name}"), None, GREY, vec![]),
+            ActionType::UpdateCard => (format!("Card updated: {card_name}"), None, BLUE, card.map(|card| self.changes(card)).unwrap_or_default()),
+            ActionType::DeleteCard => ("Card deleted".to_string(), None, RED, detail("List", data.list.as_ref())),
+            ActionType::CommentCard => (format!("Comment on {card_name}"), data.text.clone(), BLUE, vec![]),
+            ActionType::AddMemberToCard => (format!("Member added: {card_name}"), None, BLUE, detail("Member", data.member.as_ref())),
+            ActionType::RemoveMemberFromCard => (format!("Member removed: {card_name}"), None, BLUE, detail("Member", data.member.as_ref())),
+            ActionType::AddLabelToCard => (format!("Label added: {card_name}"), None, BLUE, detail("Label", data.label.as_ref())),
+            ActionType::RemoveLabelFromCard => (format!("Label removed: {card_name}"), None, BLUE, detail("Label", data.label.as_ref())),
+            ActionType::AddAttachmentToCard => (format!("Attachment added: {card_name}"), None, BLUE, detail("Attachment", data.attachment.as_ref())),
+            ActionType::UpdateCheckItemStateOnCard => {
+                let fields = data.check_item.iter().map(|item| field(&item.name, item.state.clone())).collect();
+                (format!("Checklist updated: {card_name}"), None, BLUE, fields)
+            }
+            ActionType::CreateList => (format!("List created: {}", data.list.as_ref().map_or("", |list| &list.name)), None, GREEN, vec![]),
+            ActionType::UpdateList => (format!("List updated: {}", data.list.as_ref().map_or("", |list| &list.name)), None, BLUE, vec![]),
+            ActionType::Other(kind) => (kind.clone(), None, GREY, vec![]),
+        };
+        Summary {
+            title: format!("[{board}] {title}"),
+            url,
+            description,
+            color,
+            fields,
+        }
+    }
+
+    pub(crate) fn to_webhook(&self) -> DiscordWebhookPayload {
+        let actor = self.action.member_creator.as_ref().map(|member| Actor {
+            name: member.full_name.clone(),
+            url: None,
+            // the URL needs a size, e.g. `<avatarUrl>/50.png`
+            avatar_url: member.avatar_url.as_ref().map(|avatar_url| format!("{avatar_url}/50.png")),
+        });
+        self.summary().into_webhook(&TRELLO, actor, self.action.kind.name().to_string())
+    }
+
+    pub(crate) fn to_event(&self) -> NormalizedEvent {
+        let summary = self.summary();
+        NormalizedEvent {
+            source: "trello",
+            event: self.action.kind.name().to_string(),
+            title: summary.title,
+            body: summary.description,
+            url: Some(summary.url).filter(|url| !url.is_empty()),
+            project: self.action.data.board.as_ref().map(|board| board.name.clone()),
+            priority: None,
+            labels: self.action.data.label.iter().map(|label| label.name.clone()).collect(),
+            initiator: self.action.member_creator.as_ref().map(|member| member.full_name.clone()),
+            received_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
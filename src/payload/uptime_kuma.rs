@@ -0,0 +1,287 @@
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::event::NormalizedEvent;
+use crate::render::color::{BLUE, GREEN, GREY, RED, YELLOW};
+use crate::render::summary::{field, Service, Summary};
+
+const UPTIME_KUMA: Service = Service {
+    name: "Uptime Kuma",
+    avatar_url: "https://uptime.kuma.pet/img/icon.svg",
+};
+
+/// notification of a status change of a monitor. `heartbeat` and `monitor` are `null` for a test notification.
+/// for more information, see https://github.com/louislam/uptime-kuma/blob/master/server/notification-providers/webhook.js
+#[derive(Deserialize, Clone)]
+pub(crate) struct UptimeKumaPayload {
+    #[serde(default)]
+    pub(crate) heartbeat: Option<Heartbeat>,
+    #[serde(default)]
+    pub(crate) monitor: Option<Monitor>,
+    /// e.g. `[example] [🔴 Down] timeout of 48000ms exceeded`
+    pub(crate) msg: String,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Heartbeat {
+    pub(crate) status: MonitorStatus,
+    #[serde(default)]
+    pub(crate) msg: String,
+    /// milliseconds
+    #[serde(default)]
+    pub(crate) ping: Option<f64>,
+    /// e.g. `2026-10-19 15:00:00`
+    #[serde(default)]
+    pub(crate) local_date_time: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(try_from = "u8")]
+pub(crate) enum MonitorStatus {
+    // raw: 0
+    Down,
+    // raw: 1
+    Up,
+    // raw: 2
+    Pending,
+    // raw: 3
+    Maintenance,
+}
+
+impl TryFrom<u8> for MonitorStatus {
+    type Error = String;
+
+    fn try_from(raw: u8) -> Result<Self, Self::Error> {
+        match raw {
+            0 => Ok(Self::Down),
+            1 => Ok(Self::Up),
+            2 => Ok(Self::Pending),
+            3 => Ok(Self::Maintenance),
+            _ => Err(format!("status must be in 0..=3, but got {raw}"))
+        }
+    }
+}
+
+impl MonitorStatus {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Down => "down",
+            Self::Up => "up",
+            Self::Pending => "pending",
+            Self::Maintenance => "maintenance",
+        }
+    }
+
+    const fn color(self) -> u32 {
+        match self {
+            Self::Down => RED,
+            Self::Up => GREEN,
+            Self::Pending => YELLOW,
+            Self::Maintenance => BLUE,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct Monitor {
+    pub(crate) id: u64,
+    pub(crate) name: String,
+    /// e.g. `http`, `ping`, `dns`
+    #[serde(rename = "type", default)]
+    pub(crate) kind: Option<String>,
+    /// HTTP monitors only
+    #[serde(default)]
+    pub(crate) url: Option<String>,
+    #[serde(default)]
+    pub(crate) hostname: Option<String>,
+    #[serde(default)]
+    pub(crate) port: Option<u16>,
+}
+
+impl Monitor {
+    /// the default `https://` may remain in `url` even for monitors other than HTTP
+    fn http_url(&self) -> Option<&String> {
+        self.url.as_ref().filter(|url| !url.is_empty() && *url != "https://")
+    }
+
+    /// the monitored target
+    fn target(&self) -> Option<String> {
+        match (self.http_url(), &self.hostname, self.port) {
+            (Some(url), _, _) => Some(url.clone()),
+            (None, Some(hostname), Some(port)) => Some(format!("{hostname}:{port}")),
+            (None, Some(hostname), None) => Some(hostname.clone()),
+            (None, None, _) => None,
+        }
+    }
+}
+
+impl UptimeKumaPayload {
+    fn status(&self) -> Option<MonitorStatus> {
+        self.heartbeat.as_ref().map(|heartbeat| heartbeat.status)
+    }
+
+    /// `base_url` is where Uptime Kuma is served. links to the monitored target itself when omitted.
+    /// `url` is empty when there is nothing to link to
+    fn summary(&self, base_url: Option<&str>) -> Summary {
+        let Some(monitor) = &self.monitor else {
+            return Summary {
+                title: "Uptime Kuma".to_string(),
+                url: base_url.unwrap_or_default().to_string(),
+                description: Some(self.msg.clone()),
+                color: GREY,
+                fields: vec![],
+            }
+        };
+        let status = self.status();
+        let dashboard = base_url.map(|base_url| format!("{}/dashboard/{}", base_url.trim_end_matches('/'), monitor.id));
+        let mut fields = vec![];
+        if let Some(target) = monitor.target() {
+            fields.push(field("Target", target));
+        }
+        if let Some(ping) = self.heartbeat.as_ref().and_then(|heartbeat| heartbeat.ping) {
+            fields.push(field("Ping", format!("{ping} ms")));
+        }
+        if let Some(time) = self.heartbeat.as_ref().and_then(|heartbeat| heartbeat.local_date_time.clone()) {
+            fields.push(field("Time", time));
+        }
+        Summary {
+            title: format!("[{}] {}", monitor.name, status.map_or("unknown", MonitorStatus::as_str).to_uppercase()),
+            url: dashboard.or_else(|| monitor.http_url().cloned()).unwrap_or_default(),
+            description: self.heartbeat.as_ref().map(|heartbeat| heartbeat.msg.clone()),
+            color: status.map_or(GREY, MonitorStatus::color),
+            fields,
+        }
+    }
+
+    fn event_name(&self) -> String {
+        self.status().map_or("test", MonitorStatus::as_str).to_string()
+    }
+
+    pub(crate) fn to_webhook(&self, base_url: Option<&str>) -> DiscordWebhookPayload {
+        self.summary(base_url).into_webhook(&UPTIME_KUMA, None, self.event_name())
+    }
+
+    pub(crate) fn to_event(&self, base_url: Option<&str>) -> NormalizedEvent {
+        let summary = self.summary(base_url);
+        NormalizedEvent {
+            source: "uptime_kuma",
+            event: self.event_name(),
+            title: summary.title,
+            body: summary.description,
+            url: Some(summary.url).filter(|url| !url.is_empty()),
+            project: self.monitor.as_ref().map(|monitor| monitor.name.clone()),
+            priority: None,
+            labels: self.monitor.as_ref().and_then(|monitor| monitor.kind.clone()).into_iter().collect(),
+            initiator: None,
+            received_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use super::*;
+
+    /// sent by the webhook notification of Uptime Kuma 1.23, with fields not used here left out of `monitor`
+    fn down() -> Value {
+        json!({
+            "heartbeat": {
+                "monitorID": 1,
+                "status": 0,
+                "time": "2026-10-19 06:00:00.123",
+                "msg": "timeout of 48000ms exceeded",
+                "important": true,
+                "duration": 60,
+                "timezone": "Asia/Tokyo",
+                "timezoneOffset": "+09:00",
+                "localDateTime": "2026-10-19 15:00:00",
+                "ping": null,
+                "retries": 0,
+            },
+            "monitor": {
+                "id": 1,
+                "name": "example",
+                "description": null,
+                "pathName": "example",
+                "url": "https://example.com",
+                "method": "GET",
+                "hostname": null,
+                "port": null,
+                "maxretries": 0,
+                "type": "http",
+                "interval": 60,
+                "accepted_statuscodes": ["200-299"],
+                "notificationIDList": {"1": true},
+                "tags": [],
+            },
+            "msg": "[example] [🔴 Down] timeout of 48000ms exceeded",
+        })
+    }
+
+    fn parse(raw: Value) -> UptimeKumaPayload {
+        UptimeKumaPayload::deserialize(raw).unwrap()
+    }
+
+    #[test]
+    fn down_links_to_the_dashboard() {
+        let summary = parse(down()).summary(Some("https://status.example.com/"));
+        assert_eq!(summary.title, "[example] DOWN");
+        assert_eq!(summary.url, "https://status.example.com/dashboard/1");
+        assert_eq!(summary.description.as_deref(), Some("timeout of 48000ms exceeded"));
+        assert_eq!(summary.color, RED);
+        let fields = summary.fields.iter().map(|field| (field.name.as_str(), field.value.as_str())).collect::<Vec<_>>();
+        // no ping, as the monitor timed out
+        assert_eq!(fields, [("Target", "https://example.com"), ("Time", "2026-10-19 15:00:00")]);
+    }
+
+    #[test]
+    fn up_links_to_the_target_without_base_url() {
+        let mut raw = down();
+        raw["heartbeat"]["status"] = 1.into();
+        raw["heartbeat"]["msg"] = "200 - OK".into();
+        raw["heartbeat"]["ping"] = 123.into();
+        let payload = parse(raw);
+        let summary = payload.summary(None);
+        assert_eq!(summary.title, "[example] UP");
+        assert_eq!(summary.url, "https://example.com");
+        assert_eq!(summary.color, GREEN);
+        assert!(summary.fields.iter().any(|field| field.name == "Ping" && field.value == "123 ms"));
+
+        let event = payload.to_event(None);
+        assert_eq!((event.source, event.event.as_str()), ("uptime_kuma", "up"));
+        assert_eq!(event.project.as_deref(), Some("example"));
+        assert_eq!(event.labels, ["http"]);
+    }
+
+    #[test]
+    fn host_monitor_shows_the_host() {
+        let mut raw = down();
+        raw["monitor"]["type"] = "port".into();
+        // left as the default of the form
+        raw["monitor"]["url"] = "https://".into();
+        raw["monitor"]["hostname"] = "db.example.com".into();
+        raw["monitor"]["port"] = 5432.into();
+        let summary = parse(raw).summary(None);
+        assert_eq!(summary.url, "");
+        assert_eq!(summary.fields[0].value, "db.example.com:5432");
+    }
+
+    #[test]
+    fn test_notification_has_no_monitor() {
+        let payload = parse(json!({ "heartbeat": null, "monitor": null, "msg": "Uptime Kuma Webhook Test" }));
+        let event = payload.to_event(None);
+        assert_eq!(event.event, "test");
+        assert_eq!(event.title, "Uptime Kuma");
+        assert_eq!(event.body.as_deref(), Some("Uptime Kuma Webhook Test"));
+        assert_eq!(event.url, None);
+        assert_eq!(payload.to_webhook(None).username.as_deref(), Some("Uptime Kuma"));
+    }
+
+    #[test]
+    fn unknown_status_is_rejected() {
+        let mut raw = down();
+        raw["heartbeat"]["status"] = 4.into();
+        assert!(UptimeKumaPayload::deserialize(raw).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use std::path::PathBuf;
+use anyhow::{anyhow, bail, Context};
+use serde::{Deserialize, Serialize};
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimitsBuilder};
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::event::NormalizedEvent;
+use crate::plugin::wasi::State;
+
+mod wasi;
+
+/// a WebAssembly module transforming the received data of a route.
+///
+/// the module must export the following. For imports, only a part of WASI (`wasi_snapshot_preview1`) is provided.
+/// stdout and stderr are logged, and the clock and random numbers are available, but the file system and network are not.
+///
+/// * `memory`
+/// * `alloc(len: i32) -> i32`: allocates the area the input is written to
+/// * `transform(ptr: i32, len: i32) -> i64`: the input is `{"payload": <received JSON>, "event": <normalized event>}`.
+///   The output is JSON in the shape of a Discord webhook, whose location is returned as `(ptr << 32) | len`. Returning `0` drops the event.
+///
+/// a new instance is made for every call, so no state is kept across calls.
+#[derive(Deserialize)]
+#[serde(try_from = "PluginConfig")]
+pub(crate) struct Plugin {
+    /// path of the module, to prefix the output
+    name: String,
+    engine: Engine,
+    linker: Linker<State>,
+    module: Module,
+    fuel: u64,
+    max_memory_bytes: usize,
+}
+
+#[derive(Deserialize)]
+struct PluginConfig {
+    path: PathBuf,
+    /// roughly the number of executed instructions, to bound CPU time
+    #[serde(default = "default_fuel")]
+    fuel: u64,
+    #[serde(default = "default_max_memory_bytes")]
+    max_memory_bytes: usize,
+}
+
+const fn default_fuel() -> u64 {
+    10_000_000
+}
+
+const fn default_max_memory_bytes() -> usize {
+    16 * 1024 * 1024
+}
+
+#[derive(Serialize)]
+struct PluginInput<'a> {
+    payload: &'a serde_json::Value,
+    event: &'a NormalizedEvent,
+}
+
+impl TryFrom<PluginConfig> for Plugin {
+    type Error = anyhow::Error;
+
+    /// invalid modules are detected when the config is loaded
+    fn try_from(config: PluginConfig) -> Result<Self, Self::Error> {
+        let wasm = std::fs::read(&config.path)
+            .with_context(|| format!("failed to read plugin {}", config.path.display()))?;
+        Self::new(config.path.display().to_string(), &wasm, config.fuel, config.max_memory_bytes)
+    }
+}
+
+impl Plugin {
+    fn new(name: String, wasm: &[u8], fuel: u64, max_memory_bytes: usize) -> anyhow::Result<Self> {
+        let mut engine_config = Config::default();
+        engine_config.consume_fuel(true);
+        let engine = Engine::new(&engine_config);
+        let module = Module::new(&engine, wasm).map_err(|e| anyhow!("invalid plugin {name}: {e}"))?;
+
+        if let Some(import) = module.imports().find(|import| import.module() != wasi::MODULE || !wasi::FUNCTIONS.contains(&import.name())) {
+            bail!("plugin {name} imports {}::{}, which is not provided", import.module(), import.name());
+        }
+        for required in ["memory", "alloc", "transform"] {
+            if !module.exports().any(|export| export.name() == required) {
+                bail!("plugin {name} must export `{required}`");
+            }
+        }
+
+        let mut linker = Linker::new(&engine);
+        wasi::link(&mut linker)?;
+
+        Ok(Self { name, engine, linker, module, fuel, max_memory_bytes })
+    }
+
+    /// `None` drops the event
+    pub(crate) fn run(&self, payload: &serde_json::Value, event: &NormalizedEvent) -> anyhow::Result<Option<DiscordWebhookPayload>> {
+        let limits = StoreLimitsBuilder::new().memory_size(self.max_memory_bytes).build();
+        let mut store = Store::new(&self.engine, State::new(limits));
+        store.limiter(|state: &mut State| &mut state.limits);
+        store.set_fuel(self.fuel).map_err(|e| anyhow!("{e}"))?;
+
+        let result = self.transform(&mut store, payload, event);
+        store.data().log(&self.name);
+        result
+    }
+
+    fn transform(&self, store: &mut Store<State>, payload: &serde_json::Value, event: &NormalizedEvent) -> anyhow::Result<Option<DiscordWebhookPayload>> {
+        let input = serde_json::to_vec(&PluginInput { payload, event })?;
+
+        let instance = self.linker
+            .instantiate(&mut *store, &self.module)
+            .and_then(|instance| instance.start(&mut *store))
+            .map_err(|e| anyhow!("failed to instantiate plugin: {e}"))?;
+        let memory = instance.get_memory(&*store, "memory").context("plugin does not export `memory`")?;
+        let alloc = instance.get_typed_func::<i32, i32>(&*store, "alloc")
+            .map_err(|e| anyhow!("invalid `alloc`: {e}"))?;
+        let transform = instance.get_typed_func::<(i32, i32), i64>(&*store, "transform")
+            .map_err(|e| anyhow!("invalid `transform`: {e}"))?;
+
+        let length = i32::try_from(input.len()).context("input is too large")?;
+        let pointer = alloc.call(&mut *store, length).map_err(|e| anyhow!("plugin failed in `alloc`: {e}"))?;
+        #[allow(clippy::cast_sign_loss)]
+        memory.write(&mut *store, pointer as u32 as usize, &input).map_err(|e| anyhow!("`alloc` returned invalid region: {e}"))?;
+
+        let result = transform.call(&mut *store, (pointer, length)).map_err(|e| anyhow!("plugin failed in `transform`: {e}"))?;
+        if result == 0 {
+            return Ok(None);
+        }
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let (pointer, length) = ((result as u64 >> 32) as usize, (result as u64 & 0xFFFF_FFFF) as usize);
+        let output = memory.data(&*store)
+            .get(pointer..pointer.saturating_add(length))
+            .context("`transform` returned invalid region")?;
+        let message = serde_json::from_slice(output).context("plugin returned invalid message")?;
+        Ok(Some(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(super) fn plugin(wat: &str) -> anyhow::Result<Plugin> {
+        Plugin::new("test.wasm".to_string(), &wat::parse_str(wat)?, default_fuel(), default_max_memory_bytes())
+    }
+
+    fn run(plugin: &Plugin) -> anyhow::Result<Option<DiscordWebhookPayload>> {
+        plugin.run(&serde_json::json!({}), &NormalizedEvent::example())
+    }
+
+    #[test]
+    fn message_is_returned() {
+        let plugin = plugin(r#"(module
+            (memory (export "memory") 1)
+            (data (i32.const 0) "{\"content\":\"from wasm\"}")
+            (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "transform") (param i32 i32) (result i64) (i64.const 23)))"#).unwrap();
+        assert_eq!(run(&plugin).unwrap().unwrap().content, "from wasm");
+    }
+
+    #[test]
+    fn zero_discards_event() {
+        let plugin = plugin(r#"(module
+            (memory (export "memory") 1)
+            (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "transform") (param i32 i32) (result i64) (i64.const 0)))"#).unwrap();
+        assert!(run(&plugin).unwrap().is_none());
+    }
+
+    #[test]
+    fn filesystem_is_not_provided() {
+        let error = plugin(r#"(module
+            (import "wasi_snapshot_preview1" "path_open" (func (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "transform") (param i32 i32) (result i64) (i64.const 0)))"#).err().unwrap();
+        assert_eq!(error.to_string(), "plugin test.wasm imports wasi_snapshot_preview1::path_open, which is not provided");
+    }
+
+    #[test]
+    fn other_imports_are_rejected() {
+        let error = plugin(r#"(module
+            (import "env" "log" (func (param i32)))
+            (memory (export "memory") 1)
+            (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "transform") (param i32 i32) (result i64) (i64.const 0)))"#).err().unwrap();
+        assert_eq!(error.to_string(), "plugin test.wasm imports env::log, which is not provided");
+    }
+
+    #[test]
+    fn proc_exit_fails_transform() {
+        let plugin = plugin(r#"(module
+            (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+            (memory (export "memory") 1)
+            (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "transform") (param i32 i32) (result i64) (call $proc_exit (i32.const 3)) (i64.const 0)))"#).unwrap();
+        assert!(run(&plugin).is_err());
+    }
+
+    #[test]
+    fn fuel_bounds_cpu_time() {
+        let plugin = plugin(r#"(module
+            (memory (export "memory") 1)
+            (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "transform") (param i32 i32) (result i64) (loop (br 0)) (i64.const 0)))"#).unwrap();
+        assert!(run(&plugin).is_err());
+    }
+}
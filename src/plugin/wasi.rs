@@ -0,0 +1,281 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use log::{info, warn};
+use rand::RngCore;
+use wasmi::errors::LinkerError;
+use wasmi::{Caller, Extern, Linker, Memory, StoreLimits};
+
+/// for more information, see https://github.com/WebAssembly/WASI/blob/main/legacy/preview1/docs.md
+pub(crate) const MODULE: &str = "wasi_snapshot_preview1";
+
+/// the provided functions. No file system or network is provided, so the only file descriptors are stdin, stdout and stderr.
+pub(crate) const FUNCTIONS: [&str; 16] = [
+    "args_get",
+    "args_sizes_get",
+    "environ_get",
+    "environ_sizes_get",
+    "clock_res_get",
+    "clock_time_get",
+    "fd_close",
+    "fd_fdstat_get",
+    "fd_prestat_get",
+    "fd_prestat_dir_name",
+    "fd_seek",
+    "fd_write",
+    "proc_exit",
+    "random_get",
+    "sched_yield",
+    "poll_oneoff",
+];
+
+/// limit of the length logged for each of stdout and stderr
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// upper bound of `iovs_len` of a single `fd_write`
+const MAX_IOVS: usize = 64;
+
+// errno
+const SUCCESS: i32 = 0;
+const BADF: i32 = 8;
+const FAULT: i32 = 21;
+const INVAL: i32 = 28;
+const NOSYS: i32 = 52;
+const SPIPE: i32 = 70;
+
+/// state of a call
+pub(crate) struct State {
+    pub(crate) limits: StoreLimits,
+    started: Instant,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+impl State {
+    pub(crate) fn new(limits: StoreLimits) -> Self {
+        Self { limits, started: Instant::now(), stdout: vec![], stderr: vec![] }
+    }
+
+    /// logs stdout and stderr
+    pub(crate) fn log(&self, name: &str) {
+        for line in String::from_utf8_lossy(&self.stdout).lines() {
+            info!("[{name}] {line}");
+        }
+        for line in String::from_utf8_lossy(&self.stderr).lines() {
+            warn!("[{name}] {line}");
+        }
+    }
+}
+
+pub(crate) fn link(linker: &mut Linker<State>) -> Result<(), LinkerError> {
+    linker
+        .func_wrap(MODULE, "args_get", |_: Caller<'_, State>, _: i32, _: i32| SUCCESS)?
+        .func_wrap(MODULE, "args_sizes_get", empty_sizes)?
+        .func_wrap(MODULE, "environ_get", |_: Caller<'_, State>, _: i32, _: i32| SUCCESS)?
+        .func_wrap(MODULE, "environ_sizes_get", empty_sizes)?
+        .func_wrap(MODULE, "clock_res_get", clock_res_get)?
+        .func_wrap(MODULE, "clock_time_get", clock_time_get)?
+        .func_wrap(MODULE, "fd_close", |_: Caller<'_, State>, _: i32| BADF)?
+        .func_wrap(MODULE, "fd_fdstat_get", fd_fdstat_get)?
+        .func_wrap(MODULE, "fd_prestat_get", |_: Caller<'_, State>, _: i32, _: i32| BADF)?
+        .func_wrap(MODULE, "fd_prestat_dir_name", |_: Caller<'_, State>, _: i32, _: i32, _: i32| BADF)?
+        .func_wrap(MODULE, "fd_seek", |_: Caller<'_, State>, fd: i32, _: i64, _: i32, _: i32| if is_stdio(fd) { SPIPE } else { BADF })?
+        .func_wrap(MODULE, "fd_write", fd_write)?
+        .func_wrap(MODULE, "proc_exit", |_: Caller<'_, State>, status: i32| -> Result<(), wasmi::Error> { Err(wasmi::Error::i32_exit(status)) })?
+        .func_wrap(MODULE, "random_get", random_get)?
+        .func_wrap(MODULE, "sched_yield", |_: Caller<'_, State>| SUCCESS)?
+        // sleeping would only block the worker
+        .func_wrap(MODULE, "poll_oneoff", |_: Caller<'_, State>, _: i32, _: i32, _: i32, _: i32| NOSYS)?;
+    Ok(())
+}
+
+const fn is_stdio(fd: i32) -> bool {
+    matches!(fd, 0..=2)
+}
+
+#[allow(clippy::cast_sign_loss)]
+const fn address(pointer: i32) -> usize {
+    pointer as u32 as usize
+}
+
+fn memory(caller: &Caller<'_, State>) -> Option<Memory> {
+    caller.get_export("memory").and_then(Extern::into_memory)
+}
+
+fn write(caller: &mut Caller<'_, State>, pointer: i32, bytes: &[u8]) -> i32 {
+    match memory(caller).map(|memory| memory.write(caller, address(pointer), bytes)) {
+        Some(Ok(())) => SUCCESS,
+        _ => FAULT,
+    }
+}
+
+/// no arguments or environment variables are passed
+fn empty_sizes(mut caller: Caller<'_, State>, count: i32, size: i32) -> i32 {
+    match write(&mut caller, count, &0u32.to_le_bytes()) {
+        SUCCESS => write(&mut caller, size, &0u32.to_le_bytes()),
+        errno => errno,
+    }
+}
+
+/// every clock except `realtime` is the time elapsed since the call started
+fn clock(caller: &Caller<'_, State>, id: i32) -> Option<Duration> {
+    match id {
+        0 => Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()),
+        1..=3 => Some(caller.data().started.elapsed()),
+        _ => None,
+    }
+}
+
+fn clock_res_get(mut caller: Caller<'_, State>, id: i32, resolution: i32) -> i32 {
+    if clock(&caller, id).is_none() {
+        return INVAL
+    }
+    write(&mut caller, resolution, &1u64.to_le_bytes())
+}
+
+fn clock_time_get(mut caller: Caller<'_, State>, id: i32, _precision: i64, time: i32) -> i32 {
+    let Some(elapsed) = clock(&caller, id) else {
+        return INVAL
+    };
+    let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+    write(&mut caller, time, &nanos.to_le_bytes())
+}
+
+/// stdout and stderr are shown as character devices
+fn fd_fdstat_get(mut caller: Caller<'_, State>, fd: i32, stat: i32) -> i32 {
+    if !is_stdio(fd) {
+        return BADF
+    }
+    // filetype, flags, rights_base and rights_inheriting
+    let mut fdstat = [0u8; 24];
+    // character_device
+    fdstat[0] = 2;
+    // fd_read or fd_write
+    let rights: u64 = if fd == 0 { 1 << 1 } else { 1 << 6 };
+    fdstat[8..16].copy_from_slice(&rights.to_le_bytes());
+    write(&mut caller, stat, &fdstat)
+}
+
+fn fd_write(mut caller: Caller<'_, State>, fd: i32, iovs: i32, iovs_len: i32, written: i32) -> i32 {
+    if !matches!(fd, 1 | 2) {
+        return BADF
+    }
+    if address(iovs_len) > MAX_IOVS {
+        return INVAL
+    }
+    let Some(memory) = memory(&caller) else {
+        return FAULT
+    };
+    let (data, state) = memory.data_and_store_mut(&mut caller);
+    let buffer = if fd == 1 { &mut state.stdout } else { &mut state.stderr };
+    let mut length = 0u32;
+    for index in 0..address(iovs_len) {
+        // pairs of pointer and length
+        let iovec = address(iovs).checked_add(index * 8).and_then(|start| data.get(start..start + 8));
+        let Some(iovec) = iovec else {
+            return FAULT
+        };
+        let pointer = u32::from_le_bytes([iovec[0], iovec[1], iovec[2], iovec[3]]);
+        let size = u32::from_le_bytes([iovec[4], iovec[5], iovec[6], iovec[7]]);
+        let Some(bytes) = (pointer as usize).checked_add(size as usize).and_then(|end| data.get(pointer as usize..end)) else {
+            return FAULT
+        };
+        let Some(total) = length.checked_add(size) else {
+            return INVAL
+        };
+        length = total;
+        // the rest is reported as written, but not kept
+        let room = MAX_OUTPUT_BYTES.saturating_sub(buffer.len());
+        buffer.extend_from_slice(&bytes[..bytes.len().min(room)]);
+    }
+    write(&mut caller, written, &length.to_le_bytes())
+}
+
+fn random_get(mut caller: Caller<'_, State>, buffer: i32, length: i32) -> i32 {
+    let Some(memory) = memory(&caller) else {
+        return FAULT
+    };
+    let start = address(buffer);
+    let Some(bytes) = start.checked_add(address(length)).and_then(|end| memory.data_mut(&mut caller).get_mut(start..end)) else {
+        return FAULT
+    };
+    rand::thread_rng().fill_bytes(bytes);
+    SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use wasmi::{Store, StoreLimitsBuilder};
+    use crate::payload::event::NormalizedEvent;
+    use crate::plugin::Plugin;
+    use crate::plugin::tests::plugin;
+    use super::*;
+
+    fn store(plugin: &Plugin) -> Store<State> {
+        let mut store = Store::new(&plugin.engine, State::new(StoreLimitsBuilder::new().build()));
+        store.set_fuel(plugin.fuel).unwrap();
+        store
+    }
+
+    #[test]
+    fn stdio_clock_and_random_are_provided() {
+        let plugin = plugin(r#"(module
+            (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+            (import "wasi_snapshot_preview1" "clock_time_get" (func $clock_time_get (param i32 i64 i32) (result i32)))
+            (import "wasi_snapshot_preview1" "random_get" (func $random_get (param i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            ;; iovec pointing 6 bytes at 200
+            (data (i32.const 100) "\c8\00\00\00\06\00\00\00")
+            (data (i32.const 200) "hello\n")
+            (data (i32.const 300) "{\"content\":\"from wasi\"}")
+            (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "transform") (param i32 i32) (result i64)
+                (if (call $fd_write (i32.const 1) (i32.const 100) (i32.const 1) (i32.const 400)) (then unreachable))
+                (if (call $fd_write (i32.const 2) (i32.const 100) (i32.const 1) (i32.const 400)) (then unreachable))
+                (if (call $clock_time_get (i32.const 0) (i64.const 1) (i32.const 408)) (then unreachable))
+                ;; realtime is after 2020-01-01
+                (if (i64.lt_u (i64.load (i32.const 408)) (i64.const 1577836800000000000)) (then unreachable))
+                (if (call $random_get (i32.const 416) (i32.const 16)) (then unreachable))
+                ;; (300 << 32) | 23
+                (i64.const 0x12C00000017)))"#).unwrap();
+        let mut store = store(&plugin);
+        let message = plugin.transform(&mut store, &serde_json::json!({}), &NormalizedEvent::example()).unwrap().unwrap();
+        assert_eq!(message.content, "from wasi");
+        assert_eq!(store.data().stdout, b"hello\n");
+        assert_eq!(store.data().stderr, b"hello\n");
+    }
+
+    #[test]
+    fn only_stdout_and_stderr_are_writable() {
+        let plugin = plugin(r#"(module
+            (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "transform") (param i32 i32) (result i64)
+                ;; EBADF
+                (if (i32.ne (call $fd_write (i32.const 3) (i32.const 100) (i32.const 1) (i32.const 400)) (i32.const 8)) (then unreachable))
+                (i64.const 0)))"#).unwrap();
+        let mut store = store(&plugin);
+        assert!(plugin.transform(&mut store, &serde_json::json!({}), &NormalizedEvent::example()).unwrap().is_none());
+    }
+
+    #[test]
+    fn output_is_capped() {
+        let plugin = plugin(r#"(module
+            (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+            (memory (export "memory") 2)
+            ;; iovec pointing at the whole memory
+            (data (i32.const 0) "\00\00\00\00\00\00\02\00")
+            (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "transform") (param i32 i32) (result i64)
+                (if (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 16)) (then unreachable))
+                ;; reported as written, though not kept
+                (if (i32.ne (i32.load (i32.const 16)) (i32.const 131072)) (then unreachable))
+                (if (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 16)) (then unreachable))
+                ;; EINVAL for too many iovecs
+                (if (i32.ne (call $fd_write (i32.const 1) (i32.const 0) (i32.const 65) (i32.const 16)) (i32.const 28)) (then unreachable))
+                (i64.const 0)))"#).unwrap();
+        let mut store = store(&plugin);
+        assert!(plugin.transform(&mut store, &serde_json::json!({}), &NormalizedEvent::example()).unwrap().is_none());
+        assert_eq!(store.data().stdout.len(), MAX_OUTPUT_BYTES);
+        assert!(store.data().stderr.is_empty());
+    }
+}
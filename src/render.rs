@@ -0,0 +1,7 @@
+pub(crate) mod html;
+pub(crate) mod plain;
+pub(crate) mod color;
+pub(crate) mod template;
+pub(crate) mod message;
+pub(crate) mod git;
+pub(crate) mod summary;
@@ -0,0 +1,17 @@
+/// formats `0xRRGGBB` as `#RRGGBB`. Higher bits such as alpha are ignored.
+pub(crate) fn hex(rgb: u32) -> String {
+    format!("#{:06X}", rgb & 0x00FF_FFFF)
+}
+
+/// success, creation, etc.
+pub(crate) const GREEN: u32 = 0x2D_A4_4E;
+/// failure, deletion, etc.
+pub(crate) const RED: u32 = 0xCF_22_2E;
+/// merge
+pub(crate) const PURPLE: u32 = 0x82_50_DF;
+/// update, etc.
+pub(crate) const BLUE: u32 = 0x09_69_DA;
+/// warning
+pub(crate) const YELLOW: u32 = 0xBF_87_00;
+/// things without a state
+pub(crate) const GREY: u32 = 0x6E_77_81;
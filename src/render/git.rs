@@ -0,0 +1,34 @@
+use crate::render::plain;
+
+/// maximum number of commits in the list
+const MAX_COMMITS: usize = 10;
+
+/// a line of the commit list
+pub(crate) struct CommitLine<'a> {
+    pub(crate) id: &'a str,
+    pub(crate) message: &'a str,
+    pub(crate) url: &'a str,
+    pub(crate) author: &'a str,
+}
+
+pub(crate) fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+/// `refs/heads/main` -> `main`
+pub(crate) fn branch_name(git_ref: &str) -> &str {
+    git_ref.strip_prefix("refs/heads/").or_else(|| git_ref.strip_prefix("refs/tags/")).unwrap_or(git_ref)
+}
+
+/// commit list in Markdown. If `total` exceeds the commits in the payload, the number of the rest is added.
+pub(crate) fn commit_list(commits: &[CommitLine], total: usize) -> String {
+    let mut lines = commits.iter().take(MAX_COMMITS).map(|commit| {
+        let summary = commit.message.lines().next().unwrap_or_default();
+        format!("[`{}`]({}) {} - {}", short_sha(commit.id), commit.url, plain::truncate(summary, 50), commit.author)
+    }).collect::<Vec<_>>();
+    let shown = commits.len().min(MAX_COMMITS);
+    if total > shown {
+        lines.push(format!("... and {} more", total - shown));
+    }
+    lines.join("\n")
+}
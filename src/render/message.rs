@@ -0,0 +1,294 @@
+use std::collections::BTreeMap;
+use anyhow::Context;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use crate::payload::discord::{Components, DiscordWebhookPayload, Embed, EmbedCollection, EmbedField, EmbedFields};
+use crate::payload::event::NormalizedEvent;
+use crate::render::template::Template;
+
+const TODOIST_USERNAME: &str = "Todoist";
+const TODOIST_AVATAR_URL: &str = "https://upload.wikimedia.org/wikipedia/commons/thumb/e/e1/Cib-todoist_%28CoreUI_Icons_v1.0.0%29.svg/240px-Cib-todoist_%28CoreUI_Icons_v1.0.0%29.svg.png";
+
+/// built-in templates for each Todoist event, compiled once
+static TODOIST_DEFAULTS: Lazy<BTreeMap<&'static str, MessageTemplate>> = Lazy::new(|| {
+    use MessageTemplate as T;
+    BTreeMap::from([
+        ("item:added", T::item("Task added")),
+        ("item:removed", T::item("Task removed")),
+        ("item:deleted", T::item("Task deleted")),
+        ("item:completed", T::item("Task completed")),
+        ("item:uncompleted", T::item("Task uncompleted")),
+        ("note:added", T::note("Comment added")),
+        ("note:updated", T::note("Comment updated")),
+        ("note:deleted", T::note("Comment deleted")),
+        ("project:added", T::other("Project added")),
+        ("project:updated", T::other("Project updated")),
+        ("project:deleted", T::other("Project deleted")),
+        ("project:archived", T::other("Project archived")),
+        ("project:unarchived", T::other("Project unarchived")),
+        ("section:added", T::other("Section added")),
+        ("section:updated", T::other("Section updated")),
+        ("section:deleted", T::other("Section deleted")),
+        ("section:archived", T::other("Section archived")),
+        ("section:unarchived", T::other("Section unarchived")),
+        ("label:added", T::other("Label added")),
+        ("label:deleted", T::other("Label deleted")),
+        ("label:updated", T::other("Label updated")),
+        ("filter:added", T::other("Filter added")),
+        ("filter:deleted", T::other("Filter deleted")),
+        ("filter:updated", T::other("Filter updated")),
+        ("reminder:fired", T { color: Some(Template::built_in("#D1453B")), ..T::other("Reminder") }),
+    ])
+});
+/// for Todoist events without their own template
+static TODOIST_FALLBACK: Lazy<MessageTemplate> = Lazy::new(|| MessageTemplate::other("{{ event }}"));
+static GENERIC: Lazy<MessageTemplate> = Lazy::new(MessageTemplate::generic);
+
+/// parts of a message written as Jinja2 templates, rendered with the normalized event as variables.
+/// parts which render to an empty string are left out.
+#[derive(Deserialize, Clone, Default)]
+pub(crate) struct MessageTemplate {
+    #[serde(default)]
+    content: Option<Template>,
+    #[serde(default)]
+    username: Option<Template>,
+    #[serde(default)]
+    avatar_url: Option<Template>,
+    #[serde(default)]
+    title: Option<Template>,
+    #[serde(default)]
+    description: Option<Template>,
+    #[serde(default)]
+    url: Option<Template>,
+    /// `#RRGGBB`, `0xRRGGBB` or decimal
+    #[serde(default)]
+    color: Option<Template>,
+    #[serde(default)]
+    fields: Vec<FieldTemplate>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct FieldTemplate {
+    name: Template,
+    value: Template,
+}
+
+impl FieldTemplate {
+    fn new(name: &str, value: &str) -> Self {
+        Self { name: Template::built_in(name), value: Template::built_in(value) }
+    }
+}
+
+impl MessageTemplate {
+    /// built-in templates for each Todoist event
+    pub(crate) fn todoist_default(event_name: &str) -> &'static Self {
+        TODOIST_DEFAULTS.get(event_name).unwrap_or(&TODOIST_FALLBACK)
+    }
+
+    fn item(label: &str) -> Self {
+        Self {
+            title: Some(Template::built_in(&format!("{label}: {{{{ title }}}}"))),
+            description: Some(Template::built_in("{{ body }}")),
+            url: Some(Template::built_in("{{ url }}")),
+            color: Some(Template::built_in("{{ priority_color }}")),
+            fields: vec![
+                FieldTemplate::new("Project", "{{ project }}"),
+                FieldTemplate::new("Priority", "{{ priority }}"),
+                FieldTemplate::new("Labels", "{{ labels | join(', ') }}"),
+            ],
+            ..Self::todoist()
+        }
+    }
+
+    fn note(label: &str) -> Self {
+        Self {
+            title: Some(Template::built_in(label)),
+            description: Some(Template::built_in("{{ body }}")),
+            url: Some(Template::built_in("{{ url }}")),
+            color: Some(Template::built_in("#34EB5E")),
+            fields: vec![FieldTemplate::new("Project", "{{ project }}")],
+            ..Self::todoist()
+        }
+    }
+
+    fn other(label: &str) -> Self {
+        Self {
+            title: Some(Template::built_in(label)),
+            description: Some(Template::built_in("{{ body }}")),
+            url: Some(Template::built_in("{{ url }}")),
+            color: Some(Template::built_in("#808080")),
+            ..Self::todoist()
+        }
+    }
+
+    fn todoist() -> Self {
+        Self {
+            username: Some(Template::built_in(TODOIST_USERNAME)),
+            avatar_url: Some(Template::built_in(TODOIST_AVATAR_URL)),
+            ..Self::default()
+        }
+    }
+
+    /// built-in templates for events of sources without dedicated templates
+    fn generic() -> Self {
+        Self {
+            title: Some(Template::built_in("{{ title }}")),
+            description: Some(Template::built_in("{{ body }}")),
+            url: Some(Template::built_in("{{ url }}")),
+            color: Some(Template::built_in("{{ priority_color }}")),
+            fields: vec![
+                FieldTemplate::new("Project", "{{ project }}"),
+                FieldTemplate::new("Labels", "{{ labels | join(', ') }}"),
+                FieldTemplate::new("By", "{{ initiator }}"),
+            ],
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn built_in(event: &NormalizedEvent) -> &'static Self {
+        match event.source {
+            "todoist" => Self::todoist_default(&event.event),
+            _ => &GENERIC,
+        }
+    }
+
+    pub(crate) fn render(&self, event: &NormalizedEvent) -> anyhow::Result<DiscordWebhookPayload> {
+        let context = event.template_context();
+        let render = |part: &Option<Template>| -> anyhow::Result<Option<String>> {
+            part.as_ref()
+                .map(|part| part.render(&context))
+                .transpose()
+                .map(|rendered| rendered.filter(|rendered| !rendered.trim().is_empty()))
+        };
+
+        let fields = self.fields.iter()
+            .map(|field| Ok((field.name.render(&context)?, field.value.render(&context)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .into_iter()
+            .filter(|(_, value)| !value.trim().is_empty())
+            .map(|(name, value)| EmbedField { name, value })
+            .collect();
+        let color = render(&self.color)?
+            .map(|color| parse_color(&color).with_context(|| format!("invalid color: {color}")))
+            .transpose()?;
+
+        Ok(DiscordWebhookPayload {
+            content: render(&self.content)?.unwrap_or_default(),
+            username: render(&self.username)?,
+            avatar_url: render(&self.avatar_url)?,
+            tts: false,
+            embeds: EmbedCollection(vec![
+                Embed {
+                    title: render(&self.title)?,
+                    description: render(&self.description)?,
+                    url: render(&self.url)?,
+                    color,
+                    fields: EmbedFields(fields),
+                    ..Embed::default()
+                }
+            ]),
+            components: Components::default(),
+        })
+    }
+}
+
+fn parse_color(color: &str) -> Option<u32> {
+    let color = color.trim();
+    color.strip_prefix('#')
+        .or_else(|| color.strip_prefix("0x"))
+        .map_or_else(|| color.parse().ok(), |hex| u32::from_str_radix(hex, 16).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embed(payload: &DiscordWebhookPayload) -> &Embed {
+        &payload.embeds.0[0]
+    }
+
+    fn fields(embed: &Embed) -> Vec<(&str, &str)> {
+        embed.fields.0.iter().map(|field| (field.name.as_str(), field.value.as_str())).collect()
+    }
+
+    fn todoist(name: &str) -> NormalizedEvent {
+        NormalizedEvent { event: name.to_string(), ..NormalizedEvent::example() }
+    }
+
+    #[test]
+    fn item_events_show_the_task() {
+        let payload = MessageTemplate::built_in(&todoist("item:completed")).render(&todoist("item:completed")).unwrap();
+        assert_eq!(payload.username.as_deref(), Some("Todoist"));
+        assert_eq!(payload.avatar_url.as_deref(), Some(TODOIST_AVATAR_URL));
+        let embed = embed(&payload);
+        assert_eq!(embed.title.as_deref(), Some("Task completed: Buy milk"));
+        assert_eq!(embed.url.as_deref(), Some("https://todoist.com/showTask?id=1"));
+        assert_eq!(embed.color, Some(0x00D1_453B));
+        // empty body is left out
+        assert_eq!(embed.description, None);
+        assert_eq!(fields(embed), [("Project", "Inbox"), ("Priority", "P1"), ("Labels", "errand")]);
+    }
+
+    #[test]
+    fn note_events_show_the_comment() {
+        let event = NormalizedEvent { body: Some("Whole milk".to_string()), priority: None, ..todoist("note:added") };
+        let payload = MessageTemplate::built_in(&event).render(&event).unwrap();
+        let embed = embed(&payload);
+        assert_eq!(embed.title.as_deref(), Some("Comment added"));
+        assert_eq!(embed.description.as_deref(), Some("Whole milk"));
+        assert_eq!(embed.color, Some(0x0034_EB5E));
+        assert_eq!(fields(embed), [("Project", "Inbox")]);
+    }
+
+    #[test]
+    fn every_todoist_event_has_a_template() {
+        for (name, template) in TODOIST_DEFAULTS.iter() {
+            let payload = template.render(&todoist(name)).unwrap();
+            assert_eq!(payload.username.as_deref(), Some("Todoist"), "{name}");
+            assert!(embed(&payload).title.is_some(), "{name}");
+        }
+        let reminder = MessageTemplate::todoist_default("reminder:fired").render(&todoist("reminder:fired")).unwrap();
+        assert_eq!(embed(&reminder).title.as_deref(), Some("Reminder"));
+        assert_eq!(embed(&reminder).color, Some(0x00D1_453B));
+        let project = MessageTemplate::todoist_default("project:archived").render(&todoist("project:archived")).unwrap();
+        assert_eq!(embed(&project).color, Some(0x0080_8080));
+    }
+
+    #[test]
+    fn unknown_todoist_event_is_titled_by_its_name() {
+        let payload = MessageTemplate::built_in(&todoist("item:moved")).render(&todoist("item:moved")).unwrap();
+        assert_eq!(embed(&payload).title.as_deref(), Some("item:moved"));
+    }
+
+    #[test]
+    fn other_sources_use_the_generic_template() {
+        let event = NormalizedEvent { source: "github", priority: None, ..NormalizedEvent::example() };
+        let payload = MessageTemplate::built_in(&event).render(&event).unwrap();
+        assert_eq!(payload.username, None);
+        let embed = embed(&payload);
+        assert_eq!(embed.title.as_deref(), Some("Buy milk"));
+        assert_eq!(embed.color, None);
+        assert_eq!(fields(embed), [("Project", "Inbox"), ("Labels", "errand"), ("By", "Alice")]);
+    }
+
+    #[test]
+    fn configured_template_is_compiled_on_load() {
+        let template: MessageTemplate = serde_json::from_value(serde_json::json!({
+            "content": "{{ initiator }} did {{ event }}",
+            "title": "{{ title | upper }}",
+            "color": "0x{{ 'ff' }}0000",
+        })).unwrap();
+        let payload = template.render(&NormalizedEvent::example()).unwrap();
+        assert_eq!(payload.content, "Alice did item:completed");
+        assert_eq!(embed(&payload).title.as_deref(), Some("BUY MILK"));
+        assert_eq!(embed(&payload).color, Some(0x00FF_0000));
+        let invalid = serde_json::from_value::<MessageTemplate>(serde_json::json!({ "title": "{% if title %}" }));
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn invalid_color_is_an_error() {
+        let template: MessageTemplate = serde_json::from_value(serde_json::json!({ "color": "red" })).unwrap();
+        assert!(template.render(&NormalizedEvent::example()).is_err());
+    }
+}
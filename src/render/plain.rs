@@ -0,0 +1,80 @@
+use crate::payload::discord::{DiscordWebhookPayload, Embed};
+
+/// turns the content and embeds into line-separated text for clients which do not interpret markup
+pub(crate) fn render(payload: &DiscordWebhookPayload) -> String {
+    let mut blocks = vec![];
+    if !payload.content.is_empty() {
+        blocks.push(payload.content.clone());
+    }
+    blocks.extend(payload.embeds.0.iter().map(embed));
+    blocks.join("\n\n")
+}
+
+pub(crate) fn embed(embed: &Embed) -> String {
+    let mut lines = vec![];
+    if let Some(author) = &embed.author {
+        lines.push(author.name.clone());
+    }
+    match (&embed.title, &embed.url) {
+        (Some(title), Some(url)) => lines.push(format!("{title} ({url})")),
+        (Some(title), None) => lines.push(title.clone()),
+        (None, Some(url)) => lines.push(url.clone()),
+        (None, None) => {}
+    }
+    if let Some(description) = &embed.description {
+        lines.push(description.clone());
+    }
+    for field in &embed.fields.0 {
+        lines.push(format!("{}: {}", field.name, field.value));
+    }
+    if let Some(footer) = &embed.footer {
+        lines.push(footer.text.clone());
+    }
+    lines.join("\n")
+}
+
+/// cuts the text at the character limit, replacing the end with `…`
+pub(crate) fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        text.chars().take(max_chars.saturating_sub(1)).chain(std::iter::once('…')).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn embeds_are_rendered_line_by_line() {
+        let payload: DiscordWebhookPayload = serde_json::from_value(json!({
+            "content": "Heads up",
+            "embeds": [{
+                "author": {"name": "Alice"},
+                "title": "Buy milk",
+                "url": "https://todoist.com/showTask?id=1",
+                "description": "2 bottles",
+                "fields": [{"name": "Priority", "value": "P1"}],
+                "footer": {"text": "item:completed"},
+            }, {
+                "url": "https://example.com/",
+            }],
+        })).unwrap();
+        assert_eq!(render(&payload), "Heads up\n\nAlice\nBuy milk (https://todoist.com/showTask?id=1)\n2 bottles\nPriority: P1\nitem:completed\n\nhttps://example.com/");
+    }
+
+    #[test]
+    fn empty_content_is_skipped() {
+        let payload: DiscordWebhookPayload = serde_json::from_value(json!({"content": "", "embeds": [{"title": "Buy milk"}]})).unwrap();
+        assert_eq!(render(&payload), "Buy milk");
+    }
+
+    #[test]
+    fn truncate_counts_characters() {
+        assert_eq!(truncate("牛乳を買う", 5), "牛乳を買う");
+        assert_eq!(truncate("牛乳を買う", 4), "牛乳を…");
+        assert_eq!(truncate("牛乳を買う", 0), "…");
+    }
+}
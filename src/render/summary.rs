@@ -0,0 +1,117 @@
+use crate::payload::discord::{Components, DiscordWebhookPayload, Embed, EmbedAuthor, EmbedCollection, EmbedField, EmbedFields, EmbedFooter};
+use crate::render::plain;
+
+/// limit of the description of a Discord embed
+const MAX_DESCRIPTION: usize = 4096;
+/// limit of the number of fields in a Discord embed
+const MAX_FIELDS: usize = 25;
+const MAX_FIELD_NAME: usize = 256;
+const MAX_FIELD_VALUE: usize = 1024;
+/// limit of the total characters of the title, description, fields, footer, etc.
+const MAX_EMBED: usize = 6000;
+
+/// an event of a source summarized into a single embed. The conversions of each source make this, and building the message is shared.
+pub(crate) struct Summary {
+    pub(crate) title: String,
+    pub(crate) url: String,
+    pub(crate) description: Option<String>,
+    pub(crate) color: u32,
+    pub(crate) fields: Vec<EmbedField>,
+}
+
+/// the person who caused the event
+pub(crate) struct Actor {
+    pub(crate) name: String,
+    pub(crate) url: Option<String>,
+    pub(crate) avatar_url: Option<String>,
+}
+
+/// name and icon shown as the source service
+pub(crate) struct Service {
+    pub(crate) name: &'static str,
+    pub(crate) avatar_url: &'static str,
+}
+
+pub(crate) fn field(name: &str, value: impl Into<String>) -> EmbedField {
+    EmbedField { name: name.to_string(), value: value.into() }
+}
+
+/// a field showing a change as `old → new`, or `→ new` when the old value is unknown
+pub(crate) fn change(name: &str, old: Option<&str>, new: &str) -> EmbedField {
+    field(name, old.map_or_else(|| format!("→ {new}"), |old| format!("{old} → {new}")))
+}
+
+/// characters reserved for the field telling how many fields were left out
+const MAX_OMISSION_NOTE: usize = 32;
+
+fn field_length(field: &EmbedField) -> usize {
+    field.name.chars().count() + field.value.chars().count()
+}
+
+/// fits the fields into the limits. Trailing fields over the count or total characters (`budget`) are left out, and the last field tells how many.
+fn fit_fields(fields: Vec<EmbedField>, budget: usize) -> Vec<EmbedField> {
+    let total = fields.len();
+    let mut fitted = vec![];
+    let mut used = 0;
+    for field in fields {
+        let field = EmbedField {
+            name: plain::truncate(&field.name, MAX_FIELD_NAME),
+            value: plain::truncate(&field.value, MAX_FIELD_VALUE),
+        };
+        let length = field_length(&field);
+        if fitted.len() == MAX_FIELDS || used + length > budget {
+            break
+        }
+        used += length;
+        fitted.push(field);
+    }
+    if fitted.len() < total {
+        while fitted.len() >= MAX_FIELDS || used + MAX_OMISSION_NOTE > budget {
+            let Some(last) = fitted.pop() else { break };
+            used -= field_length(&last);
+        }
+        if used + MAX_OMISSION_NOTE <= budget {
+            fitted.push(self::field("...", format!("and {} more", total - fitted.len())));
+        }
+    }
+    fitted
+}
+
+impl Summary {
+    /// `footer` is usually the event name
+    pub(crate) fn into_webhook(self, service: &Service, actor: Option<Actor>, footer: String) -> DiscordWebhookPayload {
+        let title = plain::truncate(&self.title, 256);
+        let description = self.description.filter(|description| !description.is_empty()).map(|description| plain::truncate(&description, MAX_DESCRIPTION));
+        let author_length = actor.as_ref().map_or(0, |actor| actor.name.chars().count());
+        let used = title.chars().count() + description.as_ref().map_or(0, |description| description.chars().count()) + author_length + footer.chars().count();
+        let fields = fit_fields(self.fields, MAX_EMBED.saturating_sub(used));
+        DiscordWebhookPayload {
+            content: String::new(),
+            username: Some(service.name.to_string()),
+            avatar_url: Some(service.avatar_url.to_string()),
+            tts: false,
+            embeds: EmbedCollection(vec![
+                Embed {
+                    title: Some(title),
+                    description,
+                    url: Some(self.url).filter(|url| !url.is_empty()),
+                    color: Some(self.color),
+                    author: actor.map(|actor| EmbedAuthor {
+                        name: actor.name,
+                        url: actor.url,
+                        icon_url: actor.avatar_url,
+                        proxy_icon_url: None,
+                    }),
+                    footer: Some(EmbedFooter {
+                        text: footer,
+                        icon_url: None,
+                        proxy_icon_url: None,
+                    }),
+                    fields: EmbedFields(fields),
+                    ..Embed::default()
+                }
+            ]),
+            components: Components::default(),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+use minijinja::Environment;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::Error;
+
+/// The environment templates are compiled with. Compiled templates keep referring to it, so it is never dropped.
+/// Output is not escaped, as the format of the output is up to each template. Use the `tojson` filter for values embedded in JSON.
+/// Variables without a value (`none`) are rendered as empty strings.
+static ENVIRONMENT: Lazy<Environment<'static>> = Lazy::new(|| {
+    let mut environment = Environment::new();
+    environment.set_auto_escape_callback(|_| minijinja::AutoEscape::None);
+    environment.set_formatter(|out, state, value| {
+        if value.is_none() {
+            Ok(())
+        } else {
+            minijinja::escape_formatter(out, state, value)
+        }
+    });
+    environment
+});
+
+/// A Jinja2 compatible template, compiled once when the config is loaded.
+#[derive(Clone)]
+pub(crate) struct Template(Arc<minijinja::Template<'static, 'static>>);
+
+impl Template {
+    /// The source is kept as long as the process, like the config which holds the template.
+    pub(crate) fn new(source: impl Into<String>) -> anyhow::Result<Self> {
+        let source: &'static str = Box::leak(source.into().into_boxed_str());
+        Ok(Self(Arc::new(ENVIRONMENT.template_from_str(source)?)))
+    }
+
+    /// for templates written in the code
+    pub(crate) fn built_in(source: &str) -> Self {
+        Self::new(source).expect("built-in templates must be valid")
+    }
+
+    pub(crate) fn render<C: Serialize>(&self, context: C) -> anyhow::Result<String> {
+        Ok(self.0.render(context)?)
+    }
+}
+
+impl<'de> Deserialize<'de> for Template {
+    /// syntax errors are reported when the config is loaded
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Self::new(source.clone()).map_err(|e| D::Error::custom(format!("invalid template `{source}`: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn none_is_rendered_as_empty() {
+        let template = Template::built_in("{{ title }}|{{ body }}|{{ missing }}");
+        let rendered = template.render(minijinja::context! { title => "Buy milk", body => None::<String> }).unwrap();
+        assert_eq!(rendered, "Buy milk||");
+    }
+
+    #[test]
+    fn output_is_not_escaped() {
+        let template = Template::built_in("{{ title }} {{ title | tojson }}");
+        assert_eq!(template.render(minijinja::context! { title => "\"milk\"" }).unwrap(), r#""milk" "\"milk\"""#);
+    }
+
+    #[test]
+    fn syntax_error_is_rejected_on_load() {
+        let error = serde_json::from_value::<Template>(serde_json::json!("{{ title")).err().unwrap();
+        assert!(error.to_string().contains("invalid template `{{ title`"), "{error}");
+    }
+}
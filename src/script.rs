@@ -0,0 +1,170 @@
+use std::path::PathBuf;
+use anyhow::{anyhow, Context};
+use log::{debug, info};
+use rhai::{Dynamic, Engine, Scope, AST};
+use rhai::module_resolvers::DummyModuleResolver;
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::event::NormalizedEvent;
+
+/// a [Rhai](https://rhai.rs) script transforming the received data of a route.
+///
+/// the script is given the received JSON as `payload` and the normalized event as `event`.
+/// returning a map in the shape of a Discord webhook sends it to the chat sinks, and returning `()` drops the event.
+///
+/// files and the network are out of reach, and `import` and `eval` are disabled.
+#[derive(Deserialize)]
+#[serde(try_from = "ScriptConfig")]
+pub(crate) struct Script {
+    engine: Engine,
+    ast: AST,
+}
+
+#[derive(Deserialize)]
+struct ScriptConfig {
+    /// either `path` or `source` is required
+    #[serde(default)]
+    path: Option<PathBuf>,
+    #[serde(default)]
+    source: Option<String>,
+    /// aborts the script after this many operations, to bound CPU time
+    #[serde(default = "default_max_operations")]
+    max_operations: u64,
+    /// bounds memory, in characters
+    #[serde(default = "default_max_string_size")]
+    max_string_size: usize,
+    /// bounds memory, in elements
+    #[serde(default = "default_max_collection_size")]
+    max_collection_size: usize,
+}
+
+const fn default_max_operations() -> u64 {
+    100_000
+}
+
+const fn default_max_string_size() -> usize {
+    64 * 1024
+}
+
+const fn default_max_collection_size() -> usize {
+    1024
+}
+
+impl TryFrom<ScriptConfig> for Script {
+    type Error = anyhow::Error;
+
+    /// syntax errors are detected when the config is loaded
+    fn try_from(config: ScriptConfig) -> Result<Self, Self::Error> {
+        let source = match (config.path, config.source) {
+            (Some(path), None) => std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read script {}", path.display()))?,
+            (None, Some(source)) => source,
+            _ => return Err(anyhow!("script requires exactly one of `path` or `source`")),
+        };
+
+        let mut engine = Engine::new();
+        engine
+            .set_module_resolver(DummyModuleResolver::new())
+            .set_max_operations(config.max_operations)
+            .set_max_string_size(config.max_string_size)
+            .set_max_array_size(config.max_collection_size)
+            .set_max_map_size(config.max_collection_size)
+            .set_max_call_levels(32)
+            .set_max_expr_depths(64, 32)
+            .on_print(|text| info!("script: {text}"))
+            .on_debug(|text, _, position| debug!("script ({position}): {text}"));
+        engine.disable_symbol("eval");
+
+        let ast = engine.compile(source).map_err(|e| anyhow!("invalid script: {e}"))?;
+        Ok(Self { engine, ast })
+    }
+}
+
+impl Script {
+    /// `None` drops the event
+    pub(crate) fn run(&self, payload: &serde_json::Value, event: &NormalizedEvent) -> anyhow::Result<Option<DiscordWebhookPayload>> {
+        let mut scope = Scope::new();
+        scope.push_constant("payload", rhai::serde::to_dynamic(payload)?);
+        scope.push_constant("event", rhai::serde::to_dynamic(event)?);
+        let result: Dynamic = self.engine.eval_ast_with_scope(&mut scope, &self.ast)
+            .map_err(|e| anyhow!("script failed: {e}"))?;
+        if result.is_unit() {
+            return Ok(None);
+        }
+        let message = rhai::serde::from_dynamic(&result)
+            .map_err(|e| anyhow!("script returned invalid message: {e}"))?;
+        Ok(Some(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    fn load(source: &str) -> Result<Script, serde_json::Error> {
+        serde_json::from_value(json!({ "source": source }))
+    }
+
+    fn run(source: &str) -> anyhow::Result<Option<DiscordWebhookPayload>> {
+        load(source).unwrap().run(&json!({ "action": "opened" }), &NormalizedEvent::example())
+    }
+
+    fn failure(source: &str) -> String {
+        run(source).err().expect("the script fails").to_string()
+    }
+
+    #[test]
+    fn message_is_built_from_payload_and_event() {
+        let message = run(r#"#{ content: event.title + " " + payload.action }"#).unwrap().unwrap();
+        assert_eq!(message.content, "Buy milk opened");
+    }
+
+    #[test]
+    fn unit_drops_the_event() {
+        assert!(run(r#"if event.priority == "P1" { return; } #{ content: "" }"#).unwrap().is_none());
+    }
+
+    #[test]
+    fn runaway_loop_is_aborted() {
+        assert!(failure("loop {}").contains("Too many operations"));
+    }
+
+    #[test]
+    fn huge_string_is_rejected() {
+        assert!(failure(r#"let text = "x"; loop { text += text; }"#).contains("Length of string too large"));
+    }
+
+    #[test]
+    fn huge_array_is_rejected() {
+        assert!(failure("let values = []; loop { values.push(1); }").contains("Size of array/BLOB too large"));
+    }
+
+    #[test]
+    fn deep_recursion_is_rejected() {
+        assert!(failure("fn deeper(n) { deeper(n + 1) } deeper(0)").contains("Stack overflow"));
+    }
+
+    #[test]
+    fn deep_expression_is_rejected_on_load() {
+        let error = load(&format!("{}1{}", "(".repeat(100), ")".repeat(100))).err().unwrap();
+        assert!(error.to_string().contains("Expression exceeds maximum complexity"), "{error}");
+    }
+
+    #[test]
+    fn eval_is_rejected_on_load() {
+        let error = load(r#"eval("40 + 2")"#).err().unwrap();
+        assert!(error.to_string().contains("'eval' is disabled"), "{error}");
+    }
+
+    #[test]
+    fn import_is_not_resolved() {
+        assert!(failure(r#"import "os" as os; os::exit()"#).contains("Module not found: os"));
+    }
+
+    #[test]
+    fn source_or_path_is_required() {
+        assert!(serde_json::from_value::<Script>(json!({})).is_err());
+        assert!(serde_json::from_value::<Script>(json!({ "source": "()", "path": "script.rhai" })).is_err());
+    }
+}
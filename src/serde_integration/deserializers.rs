@@ -30,4 +30,21 @@ pub fn deserialize_iso8601<'de, D: Deserializer<'de>>(deserializer: D) -> Result
             Err(D::Error::custom(b))
         }
     }
-}
\ No newline at end of file
+}
+
+/// Todoist Sync API v8 sends label IDs, while v9 sends label names. Both are kept as string.
+pub fn deserialize_labels<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Label {
+        Name(String),
+        ID(i64),
+    }
+
+    Vec::<Label>::deserialize(deserializer).map(|labels| {
+        labels.into_iter().map(|label| match label {
+            Label::Name(name) => name,
+            Label::ID(id) => id.to_string(),
+        }).collect()
+    })
+}
@@ -0,0 +1,30 @@
+use serde::Serialize;
+
+pub(crate) mod discord;
+pub(crate) mod matrix;
+pub(crate) mod telegram;
+pub(crate) mod line;
+pub(crate) mod misskey;
+pub(crate) mod mastodon;
+pub(crate) mod mattermost;
+pub(crate) mod rocketchat;
+pub(crate) mod email;
+pub(crate) mod ntfy;
+pub(crate) mod gotify;
+pub(crate) mod pushover;
+pub(crate) mod http;
+pub(crate) mod mqtt;
+pub(crate) mod amqp;
+pub(crate) mod redis_stream;
+pub(crate) mod jsonl;
+pub(crate) mod exec;
+pub(crate) mod any;
+pub(crate) mod connection;
+pub(crate) mod retry;
+#[cfg(test)]
+pub(crate) mod mock;
+
+/// a sink delivering converted payloads to an external service
+pub(crate) trait Sink<S: Serialize> {
+    async fn deliver(&self, client: &reqwest::Client, payload: &S) -> anyhow::Result<()>;
+}
@@ -0,0 +1,125 @@
+use std::borrow::Cow;
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::matrix::MatrixRoomMessage;
+use crate::sink::Sink;
+use crate::sink::amqp::AmqpSink;
+use crate::sink::discord::DiscordWebhook;
+use crate::sink::email::EmailSink;
+use crate::sink::exec::ExecSink;
+use crate::sink::gotify::GotifySink;
+use crate::sink::http::HttpSink;
+use crate::sink::jsonl::{JsonLinesSink, Record, RecordedPayload};
+use crate::sink::line::LineSink;
+use crate::sink::mastodon::MastodonSink;
+use crate::sink::matrix::MatrixSink;
+use crate::sink::mattermost::MattermostSink;
+use crate::sink::misskey::MisskeySink;
+use crate::sink::mqtt::MqttSink;
+use crate::sink::ntfy::NtfySink;
+use crate::sink::pushover::PushoverSink;
+use crate::sink::redis_stream::RedisStreamSink;
+use crate::sink::rocketchat::RocketChatSink;
+use crate::sink::telegram::TelegramSink;
+use crate::payload::event::NormalizedEvent;
+use crate::render::message::MessageTemplate;
+use crate::source::Incoming;
+
+/// a sink chosen by `kind` in the config file. Payloads go through the matching conversion before delivery.
+#[derive(Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum AnySink {
+    Discord { url: String },
+    Matrix(MatrixSink),
+    Telegram(TelegramSink),
+    Line(LineSink),
+    Misskey(MisskeySink),
+    Mastodon(MastodonSink),
+    Mattermost(MattermostSink),
+    #[serde(rename = "rocketchat")]
+    RocketChat(RocketChatSink),
+    Email(EmailSink),
+    Ntfy(NtfySink),
+    Gotify(GotifySink),
+    Pushover(PushoverSink),
+    Http(HttpSink),
+    Mqtt(MqttSink),
+    Amqp(AmqpSink),
+    RedisStream(RedisStreamSink),
+    Jsonl(JsonLinesSink),
+    Exec(ExecSink),
+}
+
+/// how messages to chat sinks (Discord, Matrix, Telegram, email, etc.) are made
+#[derive(Copy, Clone)]
+pub(crate) enum MessageSource<'a> {
+    /// built-in template for each Todoist event
+    BuiltIn,
+    Template(&'a MessageTemplate),
+    /// returned from the script or the plugin of the route
+    Transformed(&'a DiscordWebhookPayload),
+}
+
+impl AnySink {
+    pub(crate) const fn kind(&self) -> &'static str {
+        match self {
+            Self::Discord { .. } => "discord",
+            Self::Matrix(_) => "matrix",
+            Self::Telegram(_) => "telegram",
+            Self::Line(_) => "line",
+            Self::Misskey(_) => "misskey",
+            Self::Mastodon(_) => "mastodon",
+            Self::Mattermost(_) => "mattermost",
+            Self::RocketChat(_) => "rocketchat",
+            Self::Email(_) => "email",
+            Self::Ntfy(_) => "ntfy",
+            Self::Gotify(_) => "gotify",
+            Self::Pushover(_) => "pushover",
+            Self::Http(_) => "http",
+            Self::Mqtt(_) => "mqtt",
+            Self::Amqp(_) => "amqp",
+            Self::RedisStream(_) => "redis_stream",
+            Self::Jsonl(_) => "jsonl",
+            Self::Exec(_) => "exec",
+        }
+    }
+
+    /// `transaction_id` identifies this delivery of the event, and is kept on retries
+    /// so that sinks with idempotent APIs do not post twice
+    /// `event` is built once from `incoming` by the caller, and shared between sinks.
+    pub(crate) async fn deliver(&self, client: &reqwest::Client, incoming: &Incoming, event: &NormalizedEvent, message: MessageSource<'_>, transaction_id: &str) -> anyhow::Result<()> {
+        let webhook = || -> anyhow::Result<Cow<'_, DiscordWebhookPayload>> {
+            match message {
+                MessageSource::BuiltIn => incoming.message(event).map(Cow::Owned),
+                MessageSource::Template(template) => template.render(event).map(Cow::Owned),
+                MessageSource::Transformed(message) => Ok(Cow::Borrowed(message)),
+            }
+        };
+        match self {
+            Self::Discord { url } => DiscordWebhook(url.clone()).deliver(client, webhook()?.as_ref()).await,
+            Self::Matrix(sink) => sink.send(client, &MatrixRoomMessage::from(webhook()?.as_ref()), transaction_id).await,
+            Self::Telegram(sink) => sink.deliver(client, webhook()?.as_ref()).await,
+            Self::Line(sink) => sink.deliver(client, &incoming.line(event)).await,
+            Self::Misskey(sink) => sink.deliver(client, webhook()?.as_ref()).await,
+            Self::Mastodon(sink) => sink.deliver(client, webhook()?.as_ref()).await,
+            Self::Mattermost(sink) => sink.deliver(client, webhook()?.as_ref()).await,
+            Self::RocketChat(sink) => sink.deliver(client, webhook()?.as_ref()).await,
+            Self::Email(sink) => sink.send(webhook()?.as_ref(), event.source).await,
+            Self::Ntfy(sink) => sink.deliver(client, &incoming.push(event)).await,
+            Self::Gotify(sink) => sink.deliver(client, &incoming.push(event)).await,
+            Self::Pushover(sink) => sink.deliver(client, &incoming.push(event)).await,
+            Self::Http(sink) => sink.deliver(client, event).await,
+            Self::Mqtt(sink) => sink.deliver(client, event).await,
+            Self::Amqp(sink) => sink.deliver(client, event).await,
+            Self::RedisStream(sink) => sink.deliver(client, event).await,
+            Self::Jsonl(sink) => {
+                let recorded = match sink.record {
+                    Record::Event => RecordedPayload::Event(Cow::Borrowed(event)),
+                    Record::Discord => RecordedPayload::Discord(webhook()?),
+                };
+                sink.deliver(client, &recorded).await
+            }
+            Self::Exec(sink) => sink.deliver(client, event).await,
+        }
+    }
+}
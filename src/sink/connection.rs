@@ -0,0 +1,113 @@
+use std::sync::Arc;
+use log::warn;
+use tokio::sync::Mutex;
+
+/// keeps the connection to the broker so that it is not reopened for every delivery.
+/// sinks cloned from the config share the same connection, and deliveries are made one at a time.
+pub(crate) struct Cached<T>(Arc<Mutex<Option<T>>>);
+
+impl<T> Default for Cached<T> {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(None)))
+    }
+}
+
+impl<T> Clone for Cached<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Cached<T> {
+    /// runs `deliver` on the kept connection, opening one with `connect` if there is none.
+    /// a failed connection is dropped. If a kept connection fails, it may have been closed by the broker, so it is reopened once.
+    pub(crate) async fn with<R>(
+        &self,
+        connect: impl AsyncFn() -> anyhow::Result<T>,
+        deliver: impl AsyncFn(&mut T) -> anyhow::Result<R>,
+    ) -> anyhow::Result<R> {
+        let mut slot = self.0.lock().await;
+        if let Some(connection) = slot.as_mut() {
+            match deliver(connection).await {
+                Ok(result) => return Ok(result),
+                Err(e) => {
+                    warn!("reconnecting, the cached connection failed: {e:?}");
+                    *slot = None;
+                }
+            }
+        }
+        let connection = slot.insert(connect().await?);
+        let result = deliver(connection).await;
+        if result.is_err() {
+            *slot = None;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[actix_web::test]
+    async fn connection_is_reused() {
+        let connected = AtomicUsize::new(0);
+        let cached = Cached::<usize>::default();
+        let connect = async || Ok(connected.fetch_add(1, Ordering::SeqCst));
+        for _ in 0..3 {
+            cached.clone().with(connect, async |connection| Ok(*connection)).await.unwrap();
+        }
+        assert_eq!(connected.load(Ordering::SeqCst), 1);
+    }
+
+    #[actix_web::test]
+    async fn failed_connection_is_replaced_once() {
+        let connected = AtomicUsize::new(0);
+        let cached = Cached::<usize>::default();
+        let connect = async || Ok(connected.fetch_add(1, Ordering::SeqCst));
+        cached.with(connect, async |_| Ok(())).await.unwrap();
+        // the first connection went stale
+        let used = cached.with(connect, async |connection| {
+            anyhow::ensure!(*connection != 0, "closed by the broker");
+            Ok(*connection)
+        }).await.unwrap();
+        assert_eq!(used, 1);
+        assert_eq!(connected.load(Ordering::SeqCst), 2);
+    }
+
+    #[actix_web::test]
+    async fn fresh_connection_is_not_retried() {
+        let connected = AtomicUsize::new(0);
+        let cached = Cached::<usize>::default();
+        let connect = async || Ok(connected.fetch_add(1, Ordering::SeqCst));
+        let result: anyhow::Result<()> = cached.with(connect, async |_| anyhow::bail!("rejected")).await;
+        assert!(result.is_err());
+        assert_eq!(connected.load(Ordering::SeqCst), 1);
+        // discarded, so the next delivery connects again
+        cached.with(connect, async |_| Ok(())).await.unwrap();
+        assert_eq!(connected.load(Ordering::SeqCst), 2);
+    }
+
+    #[actix_web::test]
+    async fn failed_reconnection_is_discarded() {
+        let connected = AtomicUsize::new(0);
+        let cached = Cached::<usize>::default();
+        let connect = async || Ok(connected.fetch_add(1, Ordering::SeqCst));
+        cached.with(connect, async |_| Ok(())).await.unwrap();
+        // both the cached connection and the new one fail, e.g. the broker is down
+        let result: anyhow::Result<()> = cached.with(connect, async |_| anyhow::bail!("unreachable")).await;
+        assert!(result.is_err());
+        assert_eq!(connected.load(Ordering::SeqCst), 2);
+        let used = cached.with(connect, async |connection| Ok(*connection)).await.unwrap();
+        assert_eq!(used, 2);
+    }
+
+    #[actix_web::test]
+    async fn failed_connect_is_reported() {
+        let cached = Cached::<usize>::default();
+        let result = cached.with(async || anyhow::bail!("refused"), async |_| Ok(())).await;
+        assert_eq!(result.unwrap_err().to_string(), "refused");
+        cached.with(async || Ok(0), async |_| Ok(())).await.unwrap();
+    }
+}
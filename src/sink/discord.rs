@@ -0,0 +1,20 @@
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::sink::Sink;
+
+/// for more information, see https://discord.com/developers/docs/resources/webhook#execute-webhook
+#[derive(Deserialize, Clone)]
+#[serde(transparent)]
+pub(crate) struct DiscordWebhook(pub(crate) String);
+
+impl Sink<DiscordWebhookPayload> for DiscordWebhook {
+    async fn deliver(&self, client: &reqwest::Client, payload: &DiscordWebhookPayload) -> anyhow::Result<()> {
+        client
+            .post(&self.0)
+            .json(payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
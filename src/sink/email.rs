@@ -0,0 +1,206 @@
+use anyhow::Context;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::email::{render_html, render_subject};
+use crate::render::plain;
+use crate::render::template::Template;
+use crate::sink::Sink;
+
+/// for more information, see https://www.rfc-editor.org/rfc/rfc5321
+#[derive(Deserialize, Clone)]
+#[serde(try_from = "EmailConfig")]
+pub(crate) struct EmailSink {
+    config: EmailConfig,
+    /// built once, so that SMTP connections are pooled across deliveries
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+#[derive(Deserialize, Clone)]
+struct EmailConfig {
+    host: String,
+    /// defaults to 587 for `starttls`, 465 for `implicit` and 25 for `none`
+    #[serde(default)]
+    port: Option<u16>,
+    #[serde(default)]
+    tls: SmtpTls,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+    /// e.g. `Webhook Handler <noreply@example.com>`
+    from: String,
+    to: Vec<String>,
+    #[serde(default)]
+    cc: Vec<String>,
+    #[serde(default)]
+    bcc: Vec<String>,
+    /// Jinja2 template with `title`, `url`, `content`, `username` and `source`
+    #[serde(default = "default_subject")]
+    subject: Template,
+}
+
+#[derive(Deserialize, Copy, Clone, Eq, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SmtpTls {
+    /// upgrade plain connection by `STARTTLS` command
+    #[default]
+    StartTls,
+    /// also known as SMTPS
+    Implicit,
+    /// only for local SMTP stand-in, such as `MailHog`
+    None,
+}
+
+/// e.g. `[GitHub] Fix typo`, or `[alertmanager] ...` for sources without a name of the service
+fn default_subject() -> Template {
+    Template::built_in("[{{ username or source }}] {{ title or content }}")
+}
+
+impl TryFrom<EmailConfig> for EmailSink {
+    type Error = anyhow::Error;
+
+    /// The pool of the transport runs on the runtime, so the config must be loaded inside it.
+    fn try_from(config: EmailConfig) -> Result<Self, Self::Error> {
+        let mut builder = match config.tls {
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?,
+            SmtpTls::Implicit => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+        };
+        if let Some(port) = config.port {
+            builder = builder.port(port);
+        }
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        Ok(Self { transport: builder.build(), config })
+    }
+}
+
+impl EmailSink {
+    /// `source` of the event, for the subject
+    pub(crate) async fn send(&self, payload: &DiscordWebhookPayload, source: &str) -> anyhow::Result<()> {
+        self.transport.send(self.message(payload, source)?).await?;
+        Ok(())
+    }
+
+    fn message(&self, payload: &DiscordWebhookPayload, source: &str) -> anyhow::Result<Message> {
+        let config = &self.config;
+        let parse = |address: &String| address.parse::<Mailbox>().with_context(|| format!("invalid mailbox: {address}"));
+        let mut builder = Message::builder()
+            .from(parse(&config.from)?)
+            .subject(render_subject(&config.subject, payload, source)?);
+        for to in &config.to {
+            builder = builder.to(parse(to)?);
+        }
+        for cc in &config.cc {
+            builder = builder.cc(parse(cc)?);
+        }
+        for bcc in &config.bcc {
+            builder = builder.bcc(parse(bcc)?);
+        }
+        Ok(builder.multipart(MultiPart::alternative_plain_html(plain::render(payload), render_html(payload)))?)
+    }
+}
+
+/// for the Todoist-only route
+impl Sink<DiscordWebhookPayload> for EmailSink {
+    async fn deliver(&self, _client: &reqwest::Client, payload: &DiscordWebhookPayload) -> anyhow::Result<()> {
+        self.send(payload, "todoist").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+    use super::*;
+
+    /// SMTP server on a local port which accepts every message, and sends the data of each
+    fn smtp_stand_in() -> (u16, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (sender, received) = mpsc::channel();
+        thread::spawn(move || {
+            let Ok((stream, _)) = listener.accept() else { return };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            let _ = writer.write_all(b"220 localhost ESMTP\r\n");
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|read| read > 0) {
+                let command = line.trim_end().to_ascii_uppercase();
+                let reply: &[u8] = if command.starts_with("EHLO") {
+                    b"250 localhost\r\n"
+                } else if command == "DATA" {
+                    let _ = writer.write_all(b"354 go ahead\r\n");
+                    let mut data = String::new();
+                    while reader.read_line(&mut data).is_ok_and(|read| read > 0) && !data.ends_with("\r\n.\r\n") {}
+                    let _ = sender.send(data);
+                    b"250 queued\r\n"
+                } else if command == "QUIT" {
+                    b"221 bye\r\n"
+                } else {
+                    b"250 ok\r\n"
+                };
+                let _ = writer.write_all(reply);
+                line.clear();
+            }
+        });
+        (port, received)
+    }
+
+    fn sink(port: u16) -> EmailSink {
+        serde_json::from_value(serde_json::json!({
+            "host": "127.0.0.1",
+            "port": port,
+            "tls": "none",
+            "from": "Webhook Handler <noreply@example.com>",
+            "to": ["alice@example.com"],
+        })).unwrap()
+    }
+
+    fn payload(username: Option<&str>) -> DiscordWebhookPayload {
+        serde_json::from_value(serde_json::json!({
+            "content": "2 tasks",
+            "username": username,
+            "embeds": [{ "title": "Buy milk", "url": "https://todoist.com/showTask?id=1" }],
+        })).unwrap()
+    }
+
+    fn formatted(sink: &EmailSink, payload: &DiscordWebhookPayload, source: &str) -> String {
+        String::from_utf8(sink.message(payload, source).unwrap().formatted()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn message_is_sent_over_smtp() {
+        let (port, received) = smtp_stand_in();
+        sink(port).send(&payload(Some("Todoist")), "todoist").await.unwrap();
+        let data = received.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(data.contains("Subject: [Todoist] Buy milk\r\n"), "{data}");
+        assert!(data.contains("To: alice@example.com\r\n"), "{data}");
+    }
+
+    #[actix_web::test]
+    async fn body_is_multipart_alternative() {
+        let message = formatted(&sink(25), &payload(Some("Todoist")), "todoist");
+        assert!(message.contains("Content-Type: multipart/alternative;"), "{message}");
+        let plain = message.find("Content-Type: text/plain").expect("a text/plain part");
+        let html = message.find("Content-Type: text/html").expect("a text/html part");
+        // clients show the last part they support, so the richer one comes last
+        assert!(plain < html);
+        assert!(message[plain..html].contains("Buy milk (https://todoist.com/showTask?id=1)"), "{message}");
+    }
+
+    #[actix_web::test]
+    async fn default_subject_names_the_source() {
+        let message = formatted(&sink(25), &payload(None), "alertmanager");
+        assert!(message.contains("Subject: [alertmanager] Buy milk\r\n"), "{message}");
+        let message = formatted(&sink(25), &payload(Some("GitHub")), "github");
+        assert!(message.contains("Subject: [GitHub] Buy milk\r\n"), "{message}");
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{bail, Context};
+use log::{info, warn};
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use crate::payload::event::NormalizedEvent;
+use crate::sink::Sink;
+
+/// runs a local command with the normalized event as JSON on its stdin.
+/// stdout and stderr are logged, and a non-zero exit code fails the delivery.
+#[derive(Deserialize, Clone)]
+pub(crate) struct ExecSink {
+    /// program and its arguments. This is not interpreted by shell.
+    command: Vec<String>,
+    #[serde(default)]
+    working_directory: Option<PathBuf>,
+    /// additional environment variables
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    /// the process is killed after this duration
+    #[serde(default = "default_timeout_seconds")]
+    timeout_seconds: u64,
+    /// limit of the processes running at once (`max_concurrency`), shared among cloned sinks
+    #[serde(rename = "max_concurrency", default = "default_running", deserialize_with = "deserialize_running")]
+    running: Arc<Semaphore>,
+}
+
+const fn default_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_running() -> Arc<Semaphore> {
+    Arc::new(Semaphore::new(1))
+}
+
+fn deserialize_running<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Arc<Semaphore>, D::Error> {
+    usize::deserialize(deserializer).map(|max_concurrency| Arc::new(Semaphore::new(max_concurrency.max(1))))
+}
+
+impl ExecSink {
+    fn metadata(event: &NormalizedEvent) -> Vec<(&'static str, String)> {
+        let mut metadata = vec![
+            ("WEBHOOK_HANDLER_SOURCE", event.source.to_string()),
+            ("WEBHOOK_HANDLER_EVENT", event.event.clone()),
+            ("WEBHOOK_HANDLER_TITLE", event.title.clone()),
+            ("WEBHOOK_HANDLER_LABELS", event.labels.join(",")),
+            ("WEBHOOK_HANDLER_RECEIVED_AT", event.received_at.clone()),
+        ];
+        if let Some(url) = &event.url {
+            metadata.push(("WEBHOOK_HANDLER_URL", url.clone()));
+        }
+        if let Some(project) = &event.project {
+            metadata.push(("WEBHOOK_HANDLER_PROJECT", project.clone()));
+        }
+        if let Some(priority) = event.priority {
+            metadata.push(("WEBHOOK_HANDLER_PRIORITY", priority.to_string()));
+        }
+        metadata
+    }
+}
+
+impl Sink<NormalizedEvent> for ExecSink {
+    async fn deliver(&self, _client: &reqwest::Client, payload: &NormalizedEvent) -> anyhow::Result<()> {
+        let Some((program, args)) = self.command.split_first() else {
+            bail!("command must not be empty");
+        };
+        let _permit = self.running.acquire().await?;
+
+        let mut command = Command::new(program);
+        command
+            .args(args)
+            .envs(Self::metadata(payload))
+            .envs(&self.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // the process is killed when the timeout drops the future
+            .kill_on_drop(true);
+        if let Some(working_directory) = &self.working_directory {
+            command.current_dir(working_directory);
+        }
+
+        let stdin = serde_json::to_vec(payload)?;
+        let run = async {
+            let mut child = command.spawn().with_context(|| format!("failed to spawn {program}"))?;
+            if let Some(mut child_stdin) = child.stdin.take() {
+                match child_stdin.write_all(&stdin).await {
+                    // the command does not need the event and exited without reading it
+                    Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {}
+                    result => result?,
+                }
+                // closes stdin so that the process can see EOF
+                drop(child_stdin);
+            }
+            anyhow::Ok(child.wait_with_output().await?)
+        };
+        let output = tokio::time::timeout(Duration::from_secs(self.timeout_seconds), run)
+            .await
+            .with_context(|| format!("{program} did not finish within {} seconds", self.timeout_seconds))??;
+
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            info!("[{program}] {line}");
+        }
+        for line in String::from_utf8_lossy(&output.stderr).lines() {
+            warn!("[{program}] {line}");
+        }
+        if !output.status.success() {
+            bail!("{program} exited with {}", output.status);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sink(max_concurrency: usize) -> ExecSink {
+        serde_json::from_value(serde_json::json!({
+            "command": ["true"],
+            "max_concurrency": max_concurrency,
+        })).unwrap()
+    }
+
+    #[test]
+    fn semaphore_is_per_sink() {
+        let one = sink(1);
+        let three = sink(3);
+        assert_eq!(one.running.available_permits(), 1);
+        assert_eq!(three.running.available_permits(), 3);
+        let _permit = one.running.try_acquire().unwrap();
+        assert_eq!(three.running.available_permits(), 3);
+    }
+
+    #[test]
+    fn semaphore_is_shared_between_clones() {
+        let original = sink(2);
+        let cloned = original.clone();
+        let _permit = cloned.running.try_acquire().unwrap();
+        assert_eq!(original.running.available_permits(), 1);
+    }
+
+    #[test]
+    fn max_concurrency_defaults_to_one() {
+        let sink: ExecSink = serde_json::from_value(serde_json::json!({ "command": ["true"] })).unwrap();
+        assert_eq!(sink.running.available_permits(), 1);
+        assert_eq!(self::sink(0).running.available_permits(), 1);
+    }
+
+    #[actix_web::test]
+    async fn exit_status_decides_delivery() {
+        let client = reqwest::Client::new();
+        let event = NormalizedEvent::example();
+        sink(1).deliver(&client, &event).await.unwrap();
+        // reads nothing from stdin
+        let quiet: ExecSink = serde_json::from_value(serde_json::json!({ "command": ["sh", "-c", "exec 0<&-; sleep 0.1"] })).unwrap();
+        quiet.deliver(&client, &event).await.unwrap();
+        let failing: ExecSink = serde_json::from_value(serde_json::json!({ "command": ["false"] })).unwrap();
+        assert!(failing.deliver(&client, &event).await.is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use serde::Deserialize;
+use crate::payload::gotify::{self, GotifyClick, GotifyExtras, GotifyMessage, GotifyNotificationExtras};
+use crate::payload::push::PushNotification;
+use crate::sink::Sink;
+
+/// for more information, see https://gotify.net/docs/pushmsg
+#[derive(Deserialize, Clone)]
+pub(crate) struct GotifySink {
+    /// e.g. `https://gotify.example.com`
+    server: String,
+    /// token of the application, not of the client
+    app_token: String,
+}
+
+impl Sink<PushNotification> for GotifySink {
+    async fn deliver(&self, client: &reqwest::Client, payload: &PushNotification) -> anyhow::Result<()> {
+        client
+            .post(format!("{}/message", self.server.trim_end_matches('/')))
+            .header("X-Gotify-Key", &self.app_token)
+            .json(&GotifyMessage {
+                title: &payload.title,
+                message: &payload.message,
+                priority: payload.priority.map(gotify::priority),
+                extras: payload.click.as_deref().map(|url| GotifyExtras {
+                    notification: GotifyNotificationExtras { click: GotifyClick { url } },
+                }),
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use crate::payload::todoist::TodoistPriority;
+    use crate::sink::mock::{client, MockServer};
+    use super::*;
+
+    #[actix_web::test]
+    async fn message_is_posted_with_the_app_token() {
+        let server = MockServer::start(vec![(200, "{}")]);
+        let sink: GotifySink = serde_json::from_value(json!({"server": format!("{}/", server.url), "app_token": "AbCdEf"})).unwrap();
+        let notification = PushNotification {
+            title: "Buy milk".to_string(),
+            message: "item:completed".to_string(),
+            priority: Some(TodoistPriority::P4),
+            click: None,
+            tags: vec![],
+        };
+        sink.deliver(&client(), &notification).await.unwrap();
+        let request = server.request();
+        assert_eq!(request.path, "/message");
+        assert_eq!(request.header("x-gotify-key"), Some("AbCdEf"));
+        assert_eq!(request.json(), json!({"title": "Buy milk", "message": "item:completed", "priority": 2}));
+    }
+}
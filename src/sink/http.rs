@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use hmac::{Hmac, Mac};
+use reqwest::Method;
+use serde::Deserialize;
+use sha2::Sha256;
+use crate::payload::event::NormalizedEvent;
+use crate::render::template::Template;
+use crate::sink::Sink;
+
+/// sends the normalized event to any URL.
+/// with `secret`, the HMAC-SHA256 of `{timestamp}.{body}` is added as a header so that the receiver can verify the sender.
+#[derive(Deserialize, Clone)]
+pub(crate) struct HttpSink {
+    url: String,
+    #[serde(default = "default_method", with = "method")]
+    method: Method,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    /// Jinja2 template rendered with the variables of the event, e.g. `priority_color`. The event is sent as JSON when omitted.
+    #[serde(default)]
+    body_template: Option<Template>,
+    #[serde(default = "default_content_type")]
+    content_type: String,
+    #[serde(default)]
+    secret: Option<String>,
+    #[serde(default = "default_signature_header")]
+    signature_header: String,
+    #[serde(default = "default_timestamp_header")]
+    timestamp_header: String,
+}
+
+const fn default_method() -> Method {
+    Method::POST
+}
+
+fn default_content_type() -> String {
+    "application/json".to_string()
+}
+
+fn default_signature_header() -> String {
+    "X-Webhook-Signature".to_string()
+}
+
+fn default_timestamp_header() -> String {
+    "X-Webhook-Timestamp".to_string()
+}
+
+mod method {
+    use reqwest::Method;
+    use serde::{Deserialize, Deserializer};
+    use serde::de::Error;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Method, D::Error> {
+        String::deserialize(deserializer)
+            .and_then(|method| method.to_ascii_uppercase().parse().map_err(D::Error::custom))
+    }
+}
+
+/// `sha256=<hex>`, the same format as GitHub's `X-Hub-Signature-256`
+pub(crate) fn sign(secret: &[u8], timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts a key of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+impl Sink<NormalizedEvent> for HttpSink {
+    async fn deliver(&self, client: &reqwest::Client, payload: &NormalizedEvent) -> anyhow::Result<()> {
+        let body = match &self.body_template {
+            Some(body_template) => body_template.render(payload.template_context())?.into_bytes(),
+            None => serde_json::to_vec(payload)?,
+        };
+        let timestamp = chrono::Utc::now().timestamp();
+
+        let mut request = client
+            .request(self.method.clone(), &self.url)
+            .header(reqwest::header::CONTENT_TYPE, &self.content_type)
+            .header(&self.timestamp_header, timestamp);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        if let Some(secret) = &self.secret {
+            request = request.header(&self.signature_header, sign(secret.as_bytes(), timestamp, &body));
+        }
+        request
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sink::mock::{client, MockServer};
+    use super::*;
+
+    const SECRET: &str = "webhook-secret";
+    const TIMESTAMP: i64 = 1_760_832_000;
+    const BODY: &str = r#"{"title":"Buy milk"}"#;
+    /// HMAC-SHA256 of `{TIMESTAMP}.{BODY}` with `SECRET`
+    const SIGNATURE: &str = "sha256=6e47cc45a4e2c7fbafe40318b336a764a43024b9180f72aabb56a0970aef7762";
+
+    fn sink(server: &MockServer, config: serde_json::Value) -> HttpSink {
+        let mut config = config;
+        config["url"] = format!("{}/hook", server.url).into();
+        serde_json::from_value(config).unwrap()
+    }
+
+    #[test]
+    fn signature_matches_known_vector() {
+        assert_eq!(sign(SECRET.as_bytes(), TIMESTAMP, BODY.as_bytes()), SIGNATURE);
+        assert_ne!(sign(SECRET.as_bytes(), TIMESTAMP + 1, BODY.as_bytes()), SIGNATURE);
+    }
+
+    #[actix_web::test]
+    async fn event_is_sent_as_signed_json() {
+        let server = MockServer::start(vec![(200, "")]);
+        let sink = sink(&server, serde_json::json!({ "secret": SECRET, "headers": { "X-Env": "test" } }));
+        sink.deliver(&client(), &NormalizedEvent::example()).await.unwrap();
+
+        let request = server.request();
+        assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/hook"));
+        assert_eq!(request.json()["title"], "Buy milk");
+        assert_eq!(request.header("x-env"), Some("test"));
+        let timestamp = request.header("x-webhook-timestamp").unwrap().parse().unwrap();
+        assert_eq!(request.header("x-webhook-signature"), Some(sign(SECRET.as_bytes(), timestamp, &request.body).as_str()));
+    }
+
+    #[actix_web::test]
+    async fn body_template_is_rendered_with_the_template_context() {
+        let server = MockServer::start(vec![(200, "")]);
+        let sink = sink(&server, serde_json::json!({
+            "method": "put",
+            "content_type": "text/plain",
+            "body_template": "{{ title }} ({{ priority }}, {{ priority_color }}){{ body }}",
+        }));
+        sink.deliver(&client(), &NormalizedEvent::example()).await.unwrap();
+
+        let request = server.request();
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.header("content-type"), Some("text/plain"));
+        assert_eq!(request.header("x-webhook-signature"), None);
+        assert_eq!(String::from_utf8(request.body).unwrap(), "Buy milk (P1, #D1453B)");
+    }
+
+    #[actix_web::test]
+    async fn error_status_is_a_failure() {
+        let server = MockServer::start(vec![(500, "")]);
+        assert!(sink(&server, serde_json::json!({})).deliver(&client(), &NormalizedEvent::example()).await.is_err());
+    }
+}
@@ -0,0 +1,169 @@
+use std::borrow::Cow;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::event::NormalizedEvent;
+use crate::sink::Sink;
+
+/// lock keeping other requests from writing during rotation
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// appends each event as a line of JSON. Useful as an audit log or for checking conversions without a real sink.
+#[derive(Deserialize, Clone)]
+pub(crate) struct JsonLinesSink {
+    /// writes to stdout when omitted
+    #[serde(default)]
+    path: Option<PathBuf>,
+    /// rotates the file before it grows beyond this size
+    #[serde(default)]
+    max_bytes: Option<u64>,
+    /// number of rotated files to keep, as `<path>.1` (newest) to `<path>.<max_files>` (oldest)
+    #[serde(default = "default_max_files")]
+    max_files: u32,
+    #[serde(default)]
+    pub(crate) record: Record,
+}
+
+const fn default_max_files() -> u32 {
+    5
+}
+
+/// what to record
+#[derive(Deserialize, Copy, Clone, Eq, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Record {
+    /// normalized event
+    #[default]
+    Event,
+    /// the payload which would be sent to Discord
+    Discord,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub(crate) enum RecordedPayload<'a> {
+    Event(Cow<'a, NormalizedEvent>),
+    Discord(Cow<'a, DiscordWebhookPayload>),
+}
+
+impl JsonLinesSink {
+    pub(crate) const fn writes_to_stdout(&self) -> bool {
+        self.path.is_none()
+    }
+
+    fn append(&self, line: &[u8]) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(line)?;
+            return stdout.flush();
+        };
+
+        let _guard = WRITE_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(max_bytes) = self.max_bytes {
+            let current = std::fs::metadata(path).map_or(0, |metadata| metadata.len());
+            if current > 0 && current + line.len() as u64 > max_bytes {
+                rotate(path, self.max_files)?;
+            }
+        }
+        let mut file: File = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(line)
+    }
+}
+
+fn rotated_path(path: &Path, generation: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{generation}"));
+    PathBuf::from(name)
+}
+
+/// shifts `<path>.<n>` to `<path>.<n+1>` and the current file to `<path>.1`. Generations over the limit are removed.
+fn rotate(path: &Path, max_files: u32) -> std::io::Result<()> {
+    if max_files == 0 {
+        return std::fs::remove_file(path);
+    }
+    for generation in (1..max_files).rev() {
+        let from = rotated_path(path, generation);
+        if from.exists() {
+            std::fs::rename(from, rotated_path(path, generation + 1))?;
+        }
+    }
+    std::fs::rename(path, rotated_path(path, 1))
+}
+
+impl<S: Serialize> Sink<S> for JsonLinesSink {
+    async fn deliver(&self, _client: &reqwest::Client, payload: &S) -> anyhow::Result<()> {
+        let mut line = serde_json::to_vec(payload)?;
+        line.push(b'\n');
+        self.append(&line)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// an empty directory of its own for each test
+    fn directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("webhook-handler-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn sink(path: &Path, max_bytes: u64, max_files: u32) -> JsonLinesSink {
+        serde_json::from_value(serde_json::json!({ "path": path, "max_bytes": max_bytes, "max_files": max_files })).unwrap()
+    }
+
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap_or_default()
+    }
+
+    #[actix_web::test]
+    async fn events_are_appended_as_lines() {
+        let path = directory("append").join("events.jsonl");
+        let sink = sink(&path, 1024, 2);
+        let client = reqwest::Client::new();
+        sink.deliver(&client, &NormalizedEvent::example()).await.unwrap();
+        sink.deliver(&client, &NormalizedEvent::example()).await.unwrap();
+
+        let lines = read(&path);
+        assert_eq!(lines.lines().count(), 2);
+        for line in lines.lines() {
+            assert_eq!(serde_json::from_str::<serde_json::Value>(line).unwrap()["title"], "Buy milk");
+        }
+    }
+
+    #[test]
+    fn file_is_rotated_before_exceeding_max_bytes() {
+        let path = directory("rotate").join("events.jsonl");
+        let sink = sink(&path, 8, 2);
+        for line in ["1111\n", "2222\n", "3333\n", "4444\n"] {
+            sink.append(line.as_bytes()).unwrap();
+        }
+        assert_eq!(read(&path), "4444\n");
+        assert_eq!(read(&rotated_path(&path, 1)), "3333\n");
+        assert_eq!(read(&rotated_path(&path, 2)), "2222\n");
+        // older generations beyond `max_files` are removed
+        assert!(!rotated_path(&path, 3).exists());
+    }
+
+    #[test]
+    fn line_larger_than_max_bytes_is_still_written() {
+        let path = directory("large").join("events.jsonl");
+        let sink = sink(&path, 4, 1);
+        sink.append(b"123456\n").unwrap();
+        sink.append(b"abcdef\n").unwrap();
+        assert_eq!(read(&path), "abcdef\n");
+        assert_eq!(read(&rotated_path(&path, 1)), "123456\n");
+    }
+
+    #[test]
+    fn stdout_is_the_default() {
+        let sink: JsonLinesSink = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(sink.writes_to_stdout());
+    }
+}
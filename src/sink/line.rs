@@ -0,0 +1,28 @@
+use serde::Deserialize;
+use crate::payload::line::{FlexMessage, PushMessage};
+use crate::sink::Sink;
+
+/// for more information, see https://developers.line.biz/en/reference/messaging-api/#send-push-message
+#[derive(Deserialize, Clone)]
+pub(crate) struct LineSink {
+    /// long-lived channel access token issued at LINE Developers console
+    channel_access_token: String,
+    /// userId, groupId or roomId
+    to: String,
+}
+
+impl Sink<FlexMessage> for LineSink {
+    async fn deliver(&self, client: &reqwest::Client, payload: &FlexMessage) -> anyhow::Result<()> {
+        client
+            .post("https://api.line.me/v2/bot/message/push")
+            .bearer_auth(&self.channel_access_token)
+            .json(&PushMessage {
+                to: &self.to,
+                messages: std::slice::from_ref(payload),
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
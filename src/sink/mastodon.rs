@@ -0,0 +1,77 @@
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::mastodon::{StatusCreate, StatusVisibility};
+use crate::render::plain;
+use crate::sink::Sink;
+
+/// for more information, see https://docs.joinmastodon.org/methods/statuses/
+#[derive(Deserialize, Clone)]
+pub(crate) struct MastodonSink {
+    /// e.g. `https://mastodon.social`
+    instance: String,
+    /// requires `write:statuses` scope
+    access_token: String,
+    /// defaults to the account setting when omitted
+    #[serde(default)]
+    visibility: Option<StatusVisibility>,
+    #[serde(default)]
+    spoiler_text: Option<String>,
+    #[serde(default = "default_max_characters")]
+    max_characters: usize,
+}
+
+const fn default_max_characters() -> usize {
+    500
+}
+
+impl Sink<DiscordWebhookPayload> for MastodonSink {
+    async fn deliver(&self, client: &reqwest::Client, payload: &DiscordWebhookPayload) -> anyhow::Result<()> {
+        client
+            .post(format!("{}/api/v1/statuses", self.instance.trim_end_matches('/')))
+            .bearer_auth(&self.access_token)
+            .json(&StatusCreate {
+                status: plain::truncate(&plain::render(payload), self.max_characters),
+                visibility: self.visibility,
+                spoiler_text: self.spoiler_text.as_deref(),
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use crate::sink::mock::{client, MockServer};
+    use super::*;
+
+    #[actix_web::test]
+    async fn status_is_posted_with_the_bearer_token() {
+        let server = MockServer::start(vec![(200, r#"{"id":"1"}"#)]);
+        let sink: MastodonSink = serde_json::from_value(json!({
+            "instance": format!("{}/", server.url),
+            "access_token": "token",
+            "visibility": "unlisted",
+            "spoiler_text": "todoist",
+        })).unwrap();
+        let payload: DiscordWebhookPayload = serde_json::from_value(json!({"embeds": [{"title": "Buy milk", "url": "https://todoist.com/showTask?id=1"}]})).unwrap();
+        sink.deliver(&client(), &payload).await.unwrap();
+        let request = server.request();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/api/v1/statuses");
+        assert_eq!(request.header("authorization"), Some("Bearer token"));
+        assert_eq!(request.json(), json!({"status": "Buy milk (https://todoist.com/showTask?id=1)", "visibility": "unlisted", "spoiler_text": "todoist"}));
+    }
+
+    #[actix_web::test]
+    async fn status_is_cut_at_the_default_limit() {
+        let server = MockServer::start(vec![(200, r#"{"id":"1"}"#)]);
+        let sink: MastodonSink = serde_json::from_value(json!({"instance": server.url, "access_token": "token"})).unwrap();
+        let payload: DiscordWebhookPayload = serde_json::from_value(json!({"content": "あ".repeat(600)})).unwrap();
+        sink.deliver(&client(), &payload).await.unwrap();
+        let body = server.request().json();
+        assert_eq!(body, json!({"status": format!("{}…", "あ".repeat(499))}));
+    }
+}
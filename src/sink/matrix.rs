@@ -0,0 +1,113 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use anyhow::anyhow;
+use reqwest::Url;
+use serde::Deserialize;
+use crate::payload::matrix::MatrixRoomMessage;
+use crate::sink::Sink;
+
+static TRANSACTION_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// for more information, see https://spec.matrix.org/v1.8/client-server-api/#put_matrixclientv3roomsroomidsendeventtypetxnid
+#[derive(Deserialize, Clone)]
+pub(crate) struct MatrixSink {
+    /// e.g. `https://matrix.example.org`
+    homeserver: String,
+    access_token: String,
+    /// e.g. `!abcdefg:example.org`
+    room_id: String,
+}
+
+impl MatrixSink {
+    /// Generates a transaction ID unique within the access token.
+    /// The homeserver treats PUTs with the same transaction ID as duplicates, so generate one per event
+    /// and reuse it on retries to avoid posting twice.
+    pub(crate) fn next_transaction_id() -> String {
+        format!(
+            "webhook-handler.{}.{}",
+            chrono::Utc::now().timestamp_millis(),
+            TRANSACTION_COUNTER.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    fn send_endpoint(&self, transaction_id: &str) -> anyhow::Result<Url> {
+        let mut url = Url::parse(&self.homeserver)?;
+        url.path_segments_mut()
+            .map_err(|()| anyhow!("homeserver URL cannot be a base: {}", self.homeserver))?
+            .pop_if_empty()
+            .extend(["_matrix", "client", "v3", "rooms", &self.room_id, "send", "m.room.message", transaction_id]);
+        Ok(url)
+    }
+
+    /// `transaction_id` must be the same on every retry of the same event
+    pub(crate) async fn send(&self, client: &reqwest::Client, payload: &MatrixRoomMessage, transaction_id: &str) -> anyhow::Result<()> {
+        client
+            .put(self.send_endpoint(transaction_id)?)
+            .bearer_auth(&self.access_token)
+            .json(payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+impl Sink<MatrixRoomMessage> for MatrixSink {
+    /// a single attempt. Use [`MatrixSink::send`] when the delivery may be retried.
+    async fn deliver(&self, client: &reqwest::Client, payload: &MatrixRoomMessage) -> anyhow::Result<()> {
+        self.send(client, payload, &Self::next_transaction_id()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::payload::discord::DiscordWebhookPayload;
+    use crate::sink::mock::{client, MockServer};
+    use crate::sink::retry::RetryPolicy;
+    use super::*;
+
+    fn sink(homeserver: &str) -> MatrixSink {
+        serde_json::from_value(serde_json::json!({
+            "homeserver": format!("{homeserver}/"),
+            "access_token": "syt_token",
+            "room_id": "!abcdefg:example.org",
+        })).unwrap()
+    }
+
+    fn message() -> MatrixRoomMessage {
+        let payload: DiscordWebhookPayload = serde_json::from_value(serde_json::json!({"content": "Buy milk"})).unwrap();
+        MatrixRoomMessage::from(&payload)
+    }
+
+    #[actix_web::test]
+    async fn message_is_put_to_the_room() {
+        let server = MockServer::start(vec![(200, r#"{"event_id":"$1"}"#)]);
+        sink(&server.url).send(&client(), &message(), "txn.1").await.unwrap();
+        let request = server.request();
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.path, "/_matrix/client/v3/rooms/!abcdefg:example.org/send/m.room.message/txn.1");
+        assert_eq!(request.header("authorization"), Some("Bearer syt_token"));
+        assert_eq!(request.json(), serde_json::json!({"msgtype": "m.notice", "body": "Buy milk", "format": "org.matrix.custom.html", "formatted_body": "<p>Buy milk</p>"}));
+    }
+
+    #[actix_web::test]
+    async fn retry_reuses_the_transaction_id() {
+        // the first attempt may have been accepted by the homeserver despite the error
+        let server = MockServer::start(vec![(502, "{}"), (200, r#"{"event_id":"$1"}"#)]);
+        let retry: RetryPolicy = serde_json::from_value(serde_json::json!({"max_attempts": 2, "initial_backoff_millis": 1})).unwrap();
+        let sink = sink(&server.url);
+        let client = client();
+        let message = message();
+        let transaction_id = MatrixSink::next_transaction_id();
+        retry.run(|| sink.send(&client, &message, &transaction_id)).await.unwrap();
+        let first = server.request();
+        let second = server.request();
+        assert!(first.path.ends_with(&format!("/send/m.room.message/{transaction_id}")));
+        assert_eq!(first.path, second.path);
+        assert_eq!(first.body, second.body);
+    }
+
+    #[test]
+    fn transaction_ids_are_unique() {
+        assert_ne!(MatrixSink::next_transaction_id(), MatrixSink::next_transaction_id());
+    }
+}
@@ -0,0 +1,34 @@
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::mattermost::{MattermostProps, MattermostWebhookPayload};
+use crate::render::plain;
+use crate::sink::Sink;
+
+/// for more information, see https://developers.mattermost.com/integrate/webhooks/incoming/
+#[derive(Deserialize, Clone)]
+pub(crate) struct MattermostSink {
+    url: String,
+    /// requires "Lock to this channel" to be disabled on the webhook
+    #[serde(default)]
+    channel: Option<String>,
+    /// attach the whole message as `props.card`
+    #[serde(default)]
+    card: bool,
+}
+
+impl Sink<DiscordWebhookPayload> for MattermostSink {
+    async fn deliver(&self, client: &reqwest::Client, payload: &DiscordWebhookPayload) -> anyhow::Result<()> {
+        let mut message = MattermostWebhookPayload::from(payload);
+        message.channel.clone_from(&self.channel);
+        if self.card {
+            message.props = Some(MattermostProps { card: plain::render(payload) });
+        }
+        client
+            .post(&self.url)
+            .json(&message)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::misskey::{NotesCreate, NoteVisibility};
+use crate::render::plain;
+use crate::sink::Sink;
+
+/// for more information, see https://misskey-hub.net/docs/api/
+#[derive(Deserialize, Clone)]
+pub(crate) struct MisskeySink {
+    /// e.g. `https://misskey.io`
+    instance: String,
+    /// requires `write:notes` permission
+    access_token: String,
+    /// defaults to the instance setting when omitted
+    #[serde(default)]
+    visibility: Option<NoteVisibility>,
+    #[serde(default)]
+    cw: Option<String>,
+    #[serde(default)]
+    local_only: bool,
+    #[serde(default = "default_max_characters")]
+    max_characters: usize,
+}
+
+const fn default_max_characters() -> usize {
+    3000
+}
+
+impl Sink<DiscordWebhookPayload> for MisskeySink {
+    async fn deliver(&self, client: &reqwest::Client, payload: &DiscordWebhookPayload) -> anyhow::Result<()> {
+        client
+            .post(format!("{}/api/notes/create", self.instance.trim_end_matches('/')))
+            .json(&NotesCreate {
+                i: &self.access_token,
+                text: plain::truncate(&plain::render(payload), self.max_characters),
+                visibility: self.visibility,
+                cw: self.cw.as_deref(),
+                local_only: self.local_only,
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use crate::sink::mock::{client, MockServer};
+    use super::*;
+
+    fn payload() -> DiscordWebhookPayload {
+        serde_json::from_value(json!({"embeds": [{"title": "Buy milk", "footer": {"text": "item:completed"}}]})).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn note_is_created_with_the_token_in_the_body() {
+        let server = MockServer::start(vec![(200, r#"{"createdNote":{}}"#)]);
+        let sink: MisskeySink = serde_json::from_value(json!({
+            "instance": format!("{}/", server.url),
+            "access_token": "token",
+            "visibility": "home",
+            "cw": "todoist",
+            "local_only": true,
+        })).unwrap();
+        sink.deliver(&client(), &payload()).await.unwrap();
+        let request = server.request();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/api/notes/create");
+        assert_eq!(request.json(), json!({"i": "token", "text": "Buy milk\nitem:completed", "visibility": "home", "cw": "todoist", "localOnly": true}));
+    }
+
+    #[actix_web::test]
+    async fn optional_fields_are_left_out() {
+        let server = MockServer::start(vec![(200, r#"{"createdNote":{}}"#)]);
+        let sink: MisskeySink = serde_json::from_value(json!({"instance": server.url, "access_token": "token", "max_characters": 5})).unwrap();
+        sink.deliver(&client(), &payload()).await.unwrap();
+        assert_eq!(server.request().json(), json!({"i": "token", "text": "Buy …"}));
+    }
+}
@@ -0,0 +1,80 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// A request received by [`MockServer`].
+pub(crate) struct Recorded {
+    pub(crate) method: String,
+    /// path and query, e.g. `/api/notes/create`
+    pub(crate) path: String,
+    /// names are in lower case
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Recorded {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+
+    pub(crate) fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).expect("the body is JSON")
+    }
+}
+
+/// HTTP server on a local port, which answers each request with the next of the given responses.
+pub(crate) struct MockServer {
+    /// e.g. `http://127.0.0.1:12345`, without a trailing slash
+    pub(crate) url: String,
+    requests: Receiver<Recorded>,
+}
+
+impl MockServer {
+    pub(crate) fn start(responses: Vec<(u16, &'static str)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, requests) = mpsc::channel();
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let Ok((stream, _)) = listener.accept() else { return };
+                let mut reader = BufReader::new(stream);
+                let Some(request) = read(&mut reader) else { return };
+                let _ = sender.send(request);
+                let response = format!("HTTP/1.1 {status} Mock\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}", body.len());
+                let _ = reader.get_mut().write_all(response.as_bytes());
+            }
+        });
+        Self { url, requests }
+    }
+
+    /// the next request, in the order of arrival
+    pub(crate) fn request(&self) -> Recorded {
+        self.requests.recv_timeout(Duration::from_secs(5)).expect("a request arrives")
+    }
+}
+
+fn read(reader: &mut impl BufRead) -> Option<Recorded> {
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+    let mut headers = vec![];
+    loop {
+        line.clear();
+        reader.read_line(&mut line).ok()?;
+        let Some((name, value)) = line.trim_end().split_once(':') else { break };
+        headers.push((name.to_ascii_lowercase(), value.trim().to_string()));
+    }
+    let length = headers.iter().find(|(name, _)| name == "content-length").map_or(0, |(_, value)| value.parse().unwrap_or(0));
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    Some(Recorded { method, path, headers, body })
+}
+
+/// Ignores proxies configured in the environment, which would not reach the local port.
+pub(crate) fn client() -> reqwest::Client {
+    reqwest::Client::builder().no_proxy().build().unwrap()
+}
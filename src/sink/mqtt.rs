@@ -0,0 +1,233 @@
+use std::time::Duration;
+use anyhow::Context;
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Outgoing, Packet, PubAck, PubComp, QoS};
+use serde::Deserialize;
+use crate::payload::event::NormalizedEvent;
+use crate::render::template::Template;
+use crate::sink::Sink;
+use crate::sink::connection::Cached;
+
+/// publishes the normalized event as JSON to an MQTT broker.
+/// the connection is kept and reused. The event loop runs only during deliveries, so after a while without deliveries the broker drops the connection, and the next delivery reopens it.
+#[derive(Deserialize, Clone)]
+pub(crate) struct MqttSink {
+    host: String,
+    #[serde(default = "default_port")]
+    port: u16,
+    #[serde(default = "default_client_id")]
+    client_id: String,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+    /// Jinja2 template rendered with the event
+    #[serde(default = "default_topic")]
+    topic: Template,
+    #[serde(default = "default_qos", deserialize_with = "deserialize_qos")]
+    qos: QoS,
+    #[serde(default)]
+    retain: bool,
+    #[serde(skip)]
+    connection: Cached<(AsyncClient, EventLoop)>,
+}
+
+const fn default_port() -> u16 {
+    1883
+}
+
+fn default_client_id() -> String {
+    "webhook-handler".to_string()
+}
+
+fn default_topic() -> Template {
+    Template::built_in("webhook-handler/{{ source }}/{{ event }}")
+}
+
+const fn default_qos() -> QoS {
+    QoS::AtLeastOnce
+}
+
+fn deserialize_qos<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<QoS, D::Error> {
+    use serde::de::Error;
+    u8::deserialize(deserializer).and_then(|qos| rumqttc::qos(qos).map_err(D::Error::custom))
+}
+
+impl Sink<NormalizedEvent> for MqttSink {
+    async fn deliver(&self, _client: &reqwest::Client, payload: &NormalizedEvent) -> anyhow::Result<()> {
+        let topic = self.topic.render(payload)?;
+        let body = serde_json::to_vec(payload)?;
+        self.connection.with(
+            async || {
+                let mut options = MqttOptions::new(&self.client_id, &self.host, self.port);
+                options.set_keep_alive(Duration::from_secs(30));
+                if let (Some(username), Some(password)) = (&self.username, &self.password) {
+                    options.set_credentials(username, password);
+                }
+                Ok(AsyncClient::new(options, 10))
+            },
+            async |(client, event_loop)| {
+                client.publish(&topic, self.qos, self.retain, body.clone()).await?;
+                // the request is only queued until the event loop is polled
+                let wait_for_ack = async {
+                    // Deliveries hold the connection one at a time, so the next publish sent is this one.
+                    // An earlier publish may still be in flight, so the ack is matched by the packet ID.
+                    let mut published = None;
+                    loop {
+                        match (event_loop.poll().await?, published) {
+                            (Event::Outgoing(Outgoing::Publish(_)), None) if self.qos == QoS::AtMostOnce => break,
+                            (Event::Outgoing(Outgoing::Publish(pkid)), None) => published = Some(pkid),
+                            (Event::Incoming(Packet::PubAck(PubAck { pkid }) | Packet::PubComp(PubComp { pkid })), Some(published))
+                                if pkid == published => break,
+                            _ => {}
+                        }
+                    }
+                    anyhow::Ok(())
+                };
+                tokio::time::timeout(Duration::from_secs(10), wait_for_ack)
+                    .await
+                    .context("timed out waiting for the broker to acknowledge")?
+            },
+        ).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Instant;
+    use super::*;
+
+    const DELAY: Duration = Duration::from_millis(300);
+
+    /// MQTT 3.1.1 broker on a local port, which acks each publish.
+    /// The publish with `held` packet ID is acked only when the next one arrives, whose ack is then delayed by `DELAY`.
+    /// Each connection is closed after `publishes_per_connection` publishes.
+    struct FakeBroker {
+        port: u16,
+        connections: Arc<AtomicUsize>,
+    }
+
+    impl FakeBroker {
+        fn start(held: Option<u16>, publishes_per_connection: usize) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let connections = Arc::new(AtomicUsize::new(0));
+            let counter = Arc::clone(&connections);
+            thread::spawn(move || {
+                while let Ok((mut stream, _)) = listener.accept() {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    serve(&mut stream, held, publishes_per_connection);
+                }
+            });
+            Self { port, connections }
+        }
+
+        fn sink(&self) -> MqttSink {
+            serde_json::from_value(serde_json::json!({
+                "host": "127.0.0.1",
+                "port": self.port,
+                "client_id": "webhook-handler-test",
+            })).unwrap()
+        }
+    }
+
+    fn serve(stream: &mut TcpStream, held: Option<u16>, publishes_per_connection: usize) {
+        let puback = |pkid: u16| [[0x40, 0x02], pkid.to_be_bytes()].concat();
+        let mut holding = None;
+        let mut published = 0;
+        while let Some((kind, body)) = read_packet(stream) {
+            let reply: &[u8] = match kind >> 4 {
+                // CONNECT
+                1 => &[0x20, 0x02, 0x00, 0x00],
+                // PUBLISH
+                3 => {
+                    let topic_length = usize::from(u16::from_be_bytes([body[0], body[1]]));
+                    let pkid = u16::from_be_bytes([body[2 + topic_length], body[3 + topic_length]]);
+                    if Some(pkid) == held {
+                        holding = Some(pkid);
+                        continue;
+                    }
+                    if let Some(held) = holding.take() {
+                        let _ = stream.write_all(&puback(held));
+                        thread::sleep(DELAY);
+                    }
+                    let _ = stream.write_all(&puback(pkid));
+                    published += 1;
+                    if published == publishes_per_connection {
+                        return;
+                    }
+                    continue;
+                }
+                // PINGREQ
+                12 => &[0xD0, 0x00],
+                _ => continue,
+            };
+            let _ = stream.write_all(reply);
+        }
+    }
+
+    fn read_packet(stream: &mut TcpStream) -> Option<(u8, Vec<u8>)> {
+        let mut byte = [0; 1];
+        stream.read_exact(&mut byte).ok()?;
+        let kind = byte[0];
+        let mut length = 0;
+        for shift in (0..4).map(|index| index * 7) {
+            stream.read_exact(&mut byte).ok()?;
+            length |= usize::from(byte[0] & 0x7F) << shift;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+        }
+        let mut body = vec![0; length];
+        stream.read_exact(&mut body).ok()?;
+        Some((kind, body))
+    }
+
+    #[actix_web::test]
+    async fn ack_of_another_publish_is_not_taken() {
+        let broker = FakeBroker::start(Some(2), usize::MAX);
+        let sink = broker.sink();
+        let client = reqwest::Client::new();
+        let event = NormalizedEvent::example();
+        sink.deliver(&client, &event).await.unwrap();
+        // leaves packet 2 in flight on the cached connection
+        sink.connection.with(async || anyhow::bail!("reconnected"), async |(client, event_loop)| {
+            client.publish("other", QoS::AtLeastOnce, false, "other").await?;
+            while !matches!(event_loop.poll().await?, Event::Outgoing(Outgoing::Publish(_))) {}
+            Ok(())
+        }).await.unwrap();
+
+        let started = Instant::now();
+        sink.deliver(&client, &event).await.unwrap();
+        // the ack of packet 2 arrives first, and that of packet 3 after `DELAY`
+        assert!(started.elapsed() >= DELAY, "returned on the ack of another publish");
+    }
+
+    #[actix_web::test]
+    async fn closed_connection_is_replaced() {
+        let broker = FakeBroker::start(None, 1);
+        let sink = broker.sink();
+        let client = reqwest::Client::new();
+        let event = NormalizedEvent::example();
+        for _ in 0..2 {
+            sink.deliver(&client, &event).await.unwrap();
+        }
+        assert_eq!(broker.connections.load(Ordering::SeqCst), 2);
+    }
+
+    #[actix_web::test]
+    async fn connection_is_kept_between_deliveries() {
+        let broker = FakeBroker::start(None, usize::MAX);
+        let sink = broker.sink();
+        let client = reqwest::Client::new();
+        let event = NormalizedEvent::example();
+        for _ in 0..3 {
+            sink.deliver(&client, &event).await.unwrap();
+        }
+        assert_eq!(broker.connections.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,81 @@
+use serde::Deserialize;
+use crate::payload::ntfy::{NtfyPriority, NtfyPublish};
+use crate::payload::push::PushNotification;
+use crate::sink::Sink;
+
+/// for more information, see https://docs.ntfy.sh/publish/
+#[derive(Deserialize, Clone)]
+pub(crate) struct NtfySink {
+    #[serde(default = "default_server")]
+    server: String,
+    topic: String,
+    /// for protected topics
+    #[serde(default)]
+    access_token: Option<String>,
+    /// appended to the tags of each notification
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+fn default_server() -> String {
+    "https://ntfy.sh".to_string()
+}
+
+impl Sink<PushNotification> for NtfySink {
+    async fn deliver(&self, client: &reqwest::Client, payload: &PushNotification) -> anyhow::Result<()> {
+        let mut request = client
+            .post(&self.server)
+            .json(&NtfyPublish {
+                topic: &self.topic,
+                title: &payload.title,
+                message: &payload.message,
+                priority: payload.priority.map(NtfyPriority::from),
+                tags: payload.tags.iter().chain(&self.tags).map(String::as_str).collect(),
+                click: payload.click.as_deref(),
+            });
+        if let Some(access_token) = &self.access_token {
+            request = request.bearer_auth(access_token);
+        }
+        request
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use crate::payload::todoist::TodoistPriority;
+    use crate::sink::mock::{client, MockServer};
+    use super::*;
+
+    fn notification() -> PushNotification {
+        PushNotification {
+            title: "Buy milk".to_string(),
+            message: "item:completed".to_string(),
+            priority: Some(TodoistPriority::P2),
+            click: None,
+            tags: vec!["todoist".to_string()],
+        }
+    }
+
+    #[actix_web::test]
+    async fn tags_of_the_sink_are_appended() {
+        let server = MockServer::start(vec![(200, "{}")]);
+        let sink: NtfySink = serde_json::from_value(json!({"server": server.url, "topic": "todo", "tags": ["house"]})).unwrap();
+        sink.deliver(&client(), &notification()).await.unwrap();
+        let request = server.request();
+        assert_eq!(request.header("authorization"), None);
+        assert_eq!(request.json(), json!({"topic": "todo", "title": "Buy milk", "message": "item:completed", "priority": 4, "tags": ["todoist", "house"]}));
+    }
+
+    #[actix_web::test]
+    async fn access_token_is_sent_as_bearer() {
+        let server = MockServer::start(vec![(200, "{}")]);
+        let sink: NtfySink = serde_json::from_value(json!({"server": server.url, "topic": "todo", "access_token": "tk_token"})).unwrap();
+        sink.deliver(&client(), &notification()).await.unwrap();
+        assert_eq!(server.request().header("authorization"), Some("Bearer tk_token"));
+    }
+}
@@ -0,0 +1,34 @@
+use serde::Deserialize;
+use crate::payload::push::PushNotification;
+use crate::payload::pushover::{self, PushoverMessage};
+use crate::sink::Sink;
+
+/// for more information, see https://pushover.net/api
+#[derive(Deserialize, Clone)]
+pub(crate) struct PushoverSink {
+    app_token: String,
+    user_key: String,
+    /// deliver to all devices of the user when omitted
+    #[serde(default)]
+    device: Option<String>,
+}
+
+impl Sink<PushNotification> for PushoverSink {
+    async fn deliver(&self, client: &reqwest::Client, payload: &PushNotification) -> anyhow::Result<()> {
+        client
+            .post("https://api.pushover.net/1/messages.json")
+            .json(&PushoverMessage {
+                token: &self.app_token,
+                user: &self.user_key,
+                title: &payload.title,
+                message: &payload.message,
+                priority: payload.priority.map(pushover::priority),
+                url: payload.click.as_deref(),
+                device: self.device.as_deref(),
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use redis::AsyncCommands;
+use redis::aio::MultiplexedConnection;
+use redis::streams::StreamMaxlen;
+use serde::Deserialize;
+use crate::payload::event::NormalizedEvent;
+use crate::render::template::Template;
+use crate::sink::Sink;
+use crate::sink::connection::Cached;
+
+/// `XADD`s the normalized event to a Redis Stream.
+/// each entry has the event name in the `event` field and the whole JSON in the `payload` field.
+#[derive(Deserialize, Clone)]
+pub(crate) struct RedisStreamSink {
+    /// e.g. `redis://127.0.0.1/`
+    url: String,
+    /// Jinja2 template rendered with the event
+    #[serde(default = "default_key")]
+    key: Template,
+    /// trims the stream approximately (`MAXLEN ~`) when set
+    #[serde(default)]
+    max_len: Option<usize>,
+    #[serde(skip)]
+    connection: Cached<MultiplexedConnection>,
+}
+
+fn default_key() -> Template {
+    Template::built_in("webhook-handler:{{ source }}")
+}
+
+impl Sink<NormalizedEvent> for RedisStreamSink {
+    async fn deliver(&self, _client: &reqwest::Client, payload: &NormalizedEvent) -> anyhow::Result<()> {
+        let key = self.key.render(payload)?;
+        let fields = [("event", payload.event.clone()), ("payload", serde_json::to_string(payload)?)];
+        let _: String = self.connection.with(
+            async || Ok(redis::Client::open(self.url.as_str())?.get_multiplexed_async_connection().await?),
+            async |connection| Ok(match self.max_len {
+                Some(max_len) => connection.xadd_maxlen(&key, StreamMaxlen::Approx(max_len), "*", &fields).await?,
+                None => connection.xadd(&key, "*", &fields).await?,
+            }),
+        ).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    #[ignore = "needs a Redis server on 127.0.0.1:6379"]
+    async fn connection_is_reused() {
+        let sink: RedisStreamSink = serde_json::from_value(serde_json::json!({
+            "url": "redis://127.0.0.1/",
+            "key": "webhook-handler-test",
+        })).unwrap();
+        let client = reqwest::Client::new();
+        let event = NormalizedEvent::example();
+        for _ in 0..2 {
+            sink.deliver(&client, &event).await.unwrap();
+        }
+        let length: usize = sink.connection.with(
+            async || anyhow::bail!("reconnected"),
+            async |connection| Ok(connection.xlen("webhook-handler-test").await?),
+        ).await.unwrap();
+        assert!(length >= 2);
+    }
+}
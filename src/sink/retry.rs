@@ -0,0 +1,103 @@
+use std::future::Future;
+use std::time::Duration;
+use log::warn;
+use serde::Deserialize;
+
+/// how failed deliveries are retried. The wait doubles with each attempt.
+#[derive(Deserialize, Copy, Clone)]
+pub(crate) struct RetryPolicy {
+    /// includes the first attempt, so `1` means no retry
+    #[serde(default = "default_max_attempts")]
+    max_attempts: u32,
+    #[serde(default = "default_initial_backoff_millis")]
+    initial_backoff_millis: u64,
+    #[serde(default = "default_max_backoff_millis")]
+    max_backoff_millis: u64,
+}
+
+const fn default_max_attempts() -> u32 {
+    1
+}
+
+const fn default_initial_backoff_millis() -> u64 {
+    500
+}
+
+const fn default_max_backoff_millis() -> u64 {
+    30_000
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            initial_backoff_millis: default_initial_backoff_millis(),
+            max_backoff_millis: default_max_backoff_millis(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub(crate) async fn run<T, Fut: Future<Output = anyhow::Result<T>>>(&self, mut attempt: impl FnMut() -> Fut) -> anyhow::Result<T> {
+        let mut backoff = self.initial_backoff_millis;
+        let mut tried = 1;
+        loop {
+            match attempt().await {
+                Ok(value) => return Ok(value),
+                Err(e) if tried < self.max_attempts => {
+                    warn!("attempt {tried}/{} failed, retrying in {backoff}ms: {e:?}", self.max_attempts);
+                    tokio::time::sleep(Duration::from_millis(backoff)).await;
+                    backoff = backoff.saturating_mul(2).min(self.max_backoff_millis);
+                    tried += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use super::*;
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy { max_attempts, initial_backoff_millis: 1, max_backoff_millis: 2 }
+    }
+
+    #[actix_web::test]
+    async fn success_is_not_retried() {
+        let attempts = Cell::new(0);
+        let result = policy(3).run(|| async { attempts.set(attempts.get() + 1); Ok(attempts.get()) }).await;
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[actix_web::test]
+    async fn failure_is_retried_until_success() {
+        let attempts = Cell::new(0);
+        let result = policy(3).run(|| async {
+            attempts.set(attempts.get() + 1);
+            anyhow::ensure!(attempts.get() == 3, "attempt {}", attempts.get());
+            Ok(())
+        }).await;
+        assert!(result.is_ok());
+        assert_eq!(attempts.get(), 3);
+    }
+
+    #[actix_web::test]
+    async fn last_error_is_returned_after_max_attempts() {
+        let attempts = Cell::new(0);
+        let result: anyhow::Result<()> = policy(2).run(|| async {
+            attempts.set(attempts.get() + 1);
+            anyhow::bail!("attempt {}", attempts.get())
+        }).await;
+        assert_eq!(result.unwrap_err().to_string(), "attempt 2");
+        assert_eq!(attempts.get(), 2);
+    }
+
+    #[test]
+    fn default_does_not_retry() {
+        assert_eq!(RetryPolicy::default().max_attempts, 1);
+    }
+}
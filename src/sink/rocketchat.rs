@@ -0,0 +1,26 @@
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::rocketchat::RocketChatWebhookPayload;
+use crate::sink::Sink;
+
+/// for more information, see https://docs.rocket.chat/use-rocket.chat/workspace-administration/integrations
+#[derive(Deserialize, Clone)]
+pub(crate) struct RocketChatSink {
+    url: String,
+    #[serde(default)]
+    channel: Option<String>,
+}
+
+impl Sink<DiscordWebhookPayload> for RocketChatSink {
+    async fn deliver(&self, client: &reqwest::Client, payload: &DiscordWebhookPayload) -> anyhow::Result<()> {
+        let mut message = RocketChatWebhookPayload::from(payload);
+        message.channel.clone_from(&self.channel);
+        client
+            .post(&self.url)
+            .json(&message)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
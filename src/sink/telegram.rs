@@ -0,0 +1,29 @@
+use serde::Deserialize;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::telegram::{ChatID, ParseMode, SendMessage};
+use crate::sink::Sink;
+
+/// for more information, see https://core.telegram.org/bots/api#making-requests
+#[derive(Deserialize, Clone)]
+pub(crate) struct TelegramSink {
+    bot_token: String,
+    chat_id: ChatID,
+    /// for forum supergroups
+    #[serde(default)]
+    message_thread_id: Option<i64>,
+    #[serde(default)]
+    parse_mode: ParseMode,
+}
+
+impl Sink<DiscordWebhookPayload> for TelegramSink {
+    async fn deliver(&self, client: &reqwest::Client, payload: &DiscordWebhookPayload) -> anyhow::Result<()> {
+        let message = SendMessage::render(payload, self.chat_id.clone(), self.message_thread_id, self.parse_mode);
+        client
+            .post(format!("https://api.telegram.org/bot{}/sendMessage", self.bot_token))
+            .json(&message)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+use std::fmt::{Display, Formatter};
+use actix_web::{HttpRequest, HttpResponse};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use crate::payload::alertmanager::{AlertmanagerPayload, ALERTMANAGER, GRAFANA};
+use crate::payload::backlog::BacklogPayload;
+use crate::payload::discord::DiscordWebhookPayload;
+use crate::payload::event::NormalizedEvent;
+use crate::payload::github::{GitHubEvent, GITHUB};
+use crate::payload::gitlab::GitLabEvent;
+use crate::payload::jira::JiraWebhook;
+use crate::payload::line::FlexMessage;
+use crate::payload::linear::LinearWebhook;
+use crate::payload::push::PushNotification;
+use crate::payload::sentry::SentryWebhook;
+use crate::payload::todoist::TodoistPayload;
+use crate::payload::trello::TrelloWebhook;
+use crate::payload::uptime_kuma::UptimeKumaPayload;
+use crate::render::message::MessageTemplate;
+use crate::{event_to_line, event_to_push, todoist_to_event, todoist_to_line, todoist_to_push, todoist_to_webhook};
+
+pub(crate) mod json;
+pub(crate) mod github;
+pub(crate) mod gitlab;
+pub(crate) mod gitea;
+pub(crate) mod alertmanager;
+pub(crate) mod uptime_kuma;
+pub(crate) mod sentry;
+pub(crate) mod backlog;
+pub(crate) mod jira;
+pub(crate) mod linear;
+pub(crate) mod trello;
+
+/// format of the data a route receives
+#[derive(Deserialize, Clone, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum Source {
+    #[default]
+    Todoist,
+    Json(Box<json::JsonSource>),
+    #[serde(rename = "github")]
+    GitHub(github::GitHubSource),
+    #[serde(rename = "gitlab")]
+    GitLab(gitlab::GitLabSource),
+    #[serde(alias = "forgejo")]
+    Gitea(gitea::GiteaSource),
+    Alertmanager(alertmanager::AlertmanagerSource),
+    /// unified alerting, which sends the format of Alertmanager with some extra fields
+    Grafana(alertmanager::AlertmanagerSource),
+    UptimeKuma(uptime_kuma::UptimeKumaSource),
+    Sentry(sentry::SentrySource),
+    Backlog(backlog::BacklogSource),
+    Jira(jira::JiraSource),
+    Linear(linear::LinearSource),
+    Trello(trello::TrelloSource),
+}
+
+/// the received data. Sources with a dedicated conversion are kept as they are, and the others are handled as normalized events.
+#[derive(Clone)]
+pub(crate) enum Incoming {
+    Todoist(Box<TodoistPayload>),
+    GitHub(Box<GitHubEvent>),
+    GitLab(Box<GitLabEvent>),
+    Gitea(Box<GitHubEvent>, gitea::Flavor),
+    Alertmanager(Box<AlertmanagerPayload>),
+    Grafana(Box<AlertmanagerPayload>),
+    /// with `base_url` of the source
+    UptimeKuma(Box<UptimeKumaPayload>, Option<&'static str>),
+    Sentry(Box<SentryWebhook>),
+    /// with `space_url` of the source
+    Backlog(Box<BacklogPayload>, Option<&'static str>),
+    Jira(Box<JiraWebhook>),
+    Linear(Box<LinearWebhook>),
+    Trello(Box<TrelloWebhook>),
+    Event(Box<NormalizedEvent>),
+}
+
+impl Source {
+    /// `body` is the request body as is, which some services sign
+    pub(crate) fn parse(&'static self, request: &HttpRequest, body: &[u8], raw: serde_json::Value) -> Result<Incoming, SourceError> {
+        let invalid = |e: &dyn Display| SourceError::Invalid(e.to_string());
+        match self {
+            Self::Todoist => Ok(Incoming::Todoist(Box::new(TodoistPayload::deserialize(raw).map_err(|e| invalid(&e))?))),
+            Self::Json(source) => Ok(Incoming::Event(Box::new(source.extract(&raw).map_err(|e| invalid(&e))?))),
+            Self::GitHub(source) => Ok(Incoming::GitHub(Box::new(source.parse(request, body, raw)?))),
+            Self::GitLab(source) => Ok(Incoming::GitLab(Box::new(source.parse(request, raw)?))),
+            Self::Gitea(source) => {
+                let (event, flavor) = source.parse(request, body, raw)?;
+                Ok(Incoming::Gitea(Box::new(event), flavor))
+            }
+            Self::Alertmanager(source) => Ok(Incoming::Alertmanager(Box::new(source.parse(request, raw)?))),
+            Self::Grafana(source) => Ok(Incoming::Grafana(Box::new(source.parse(request, raw)?))),
+            Self::UptimeKuma(source) => Ok(Incoming::UptimeKuma(Box::new(source.parse(request, raw)?), source.base_url.as_deref())),
+            Self::Sentry(source) => Ok(Incoming::Sentry(Box::new(source.parse(request, body, raw)?))),
+            Self::Backlog(source) => Ok(Incoming::Backlog(Box::new(BacklogPayload::deserialize(raw).map_err(|e| invalid(&e))?), source.space_url.as_deref())),
+            Self::Jira(source) => Ok(Incoming::Jira(Box::new(source.parse(request, body, raw)?))),
+            Self::Linear(source) => Ok(Incoming::Linear(Box::new(source.parse(request, body, raw)?))),
+            Self::Trello(source) => Ok(Incoming::Trello(Box::new(source.parse(request, body, raw)?))),
+        }
+    }
+}
+
+impl Incoming {
+    pub(crate) fn event(&self) -> NormalizedEvent {
+        match self {
+            Self::Todoist(payload) => todoist_to_event(payload.as_ref().clone()),
+            Self::GitHub(event) => event.to_event("github"),
+            Self::GitLab(event) => event.to_event(),
+            Self::Gitea(event, flavor) => event.to_event(flavor.source()),
+            Self::Alertmanager(payload) => payload.to_event("alertmanager"),
+            Self::Grafana(payload) => payload.to_event("grafana"),
+            Self::UptimeKuma(payload, base_url) => payload.to_event(*base_url),
+            Self::Sentry(webhook) => webhook.to_event(),
+            Self::Backlog(payload, space_url) => payload.to_event(*space_url),
+            Self::Jira(webhook) => webhook.to_event(),
+            Self::Linear(webhook) => webhook.to_event(),
+            Self::Trello(webhook) => webhook.to_event(),
+            Self::Event(event) => event.as_ref().clone(),
+        }
+    }
+
+    /// built-in message sent to the chat sinks
+    pub(crate) fn message(&self, event: &NormalizedEvent) -> anyhow::Result<DiscordWebhookPayload> {
+        match self {
+            Self::Todoist(payload) => Ok(todoist_to_webhook(payload.as_ref().clone())),
+            Self::GitHub(github) => Ok(github.to_webhook(&GITHUB)),
+            Self::GitLab(gitlab) => Ok(gitlab.to_webhook()),
+            Self::Gitea(gitea, flavor) => Ok(gitea.to_webhook(flavor.service())),
+            Self::Alertmanager(payload) => Ok(payload.to_webhook(&ALERTMANAGER)),
+            Self::Grafana(payload) => Ok(payload.to_webhook(&GRAFANA)),
+            Self::UptimeKuma(payload, base_url) => Ok(payload.to_webhook(*base_url)),
+            Self::Sentry(webhook) => Ok(webhook.to_webhook()),
+            Self::Backlog(payload, space_url) => Ok(payload.to_webhook(*space_url)),
+            Self::Jira(webhook) => Ok(webhook.to_webhook()),
+            Self::Linear(webhook) => Ok(webhook.to_webhook()),
+            Self::Trello(webhook) => Ok(webhook.to_webhook()),
+            Self::Event(_) => MessageTemplate::built_in(event).render(event),
+        }
+    }
+
+    pub(crate) fn line(&self, event: &NormalizedEvent) -> FlexMessage {
+        match self {
+            Self::Todoist(payload) => todoist_to_line(payload.as_ref().clone()),
+            _ => event_to_line(event),
+        }
+    }
+
+    pub(crate) fn push(&self, event: &NormalizedEvent) -> PushNotification {
+        match self {
+            Self::Todoist(payload) => todoist_to_push(payload.as_ref().clone()),
+            _ => event_to_push(event),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum SourceError {
+    /// the request is not signed by the expected sender
+    Unauthorized(String),
+    Invalid(String),
+}
+
+impl Display for SourceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unauthorized(detail) | Self::Invalid(detail) => f.write_str(detail),
+        }
+    }
+}
+
+impl From<SourceError> for HttpResponse {
+    fn from(error: SourceError) -> Self {
+        let detail = error.to_string();
+        match error {
+            SourceError::Unauthorized(_) => Self::Unauthorized().body(detail),
+            SourceError::Invalid(_) => Self::UnprocessableEntity().body(detail),
+        }
+    }
+}
+
+pub(crate) fn header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    request.headers().get(name).and_then(|value| value.to_str().ok())
+}
+
+/// `signature` is hex-encoded HMAC-SHA256 of `body`. compares in constant time.
+pub(crate) fn verify_hmac_sha256(secret: &str, body: &[u8], signature: &str) -> Result<(), SourceError> {
+    let signature = hex::decode(signature).map_err(|_| SourceError::Unauthorized("malformed signature".to_string()))?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts a key of any length");
+    mac.update(body);
+    mac.verify_slice(&signature).map_err(|_| SourceError::Unauthorized("signature mismatch".to_string()))
+}
+
+/// compares a shared secret sent as is, in constant time
+pub(crate) fn verify_token(expected: &str, received: &str) -> Result<(), SourceError> {
+    let matches = expected.len() == received.len()
+        && expected.bytes().zip(received.bytes()).fold(0, |difference, (a, b)| difference | (a ^ b)) == 0;
+    if matches { Ok(()) } else { Err(SourceError::Unauthorized("token mismatch".to_string())) }
+}
+
+/// `Authorization: Bearer <token>`
+pub(crate) fn verify_bearer(request: &HttpRequest, expected: &str) -> Result<(), SourceError> {
+    let received = header(request, "Authorization")
+        .and_then(|authorization| authorization.strip_prefix("Bearer "))
+        .ok_or_else(|| SourceError::Unauthorized("Authorization header must be included".to_string()))?;
+    verify_token(expected, received)
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use super::*;
+
+    /// for the values, see https://docs.github.com/en/webhooks/using-webhooks/validating-webhook-deliveries#testing-the-webhook-payload-validation
+    const SECRET: &str = "It's a Secret to Everybody";
+    const BODY: &[u8] = b"Hello, World!";
+    const SIGNATURE: &str = "757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+
+    #[test]
+    fn hmac_sha256_is_verified() {
+        assert!(verify_hmac_sha256(SECRET, BODY, SIGNATURE).is_ok());
+        assert!(verify_hmac_sha256(SECRET, &SIGNATURE.to_uppercase().into_bytes(), SIGNATURE).is_err());
+        assert!(verify_hmac_sha256(&SECRET.to_uppercase(), BODY, SIGNATURE).is_err());
+    }
+
+    #[test]
+    fn tampered_body_is_rejected() {
+        let error = verify_hmac_sha256(SECRET, b"Hello, World?", SIGNATURE).unwrap_err();
+        assert!(matches!(&error, SourceError::Unauthorized(detail) if detail == "signature mismatch"));
+    }
+
+    #[test]
+    fn malformed_signature_is_rejected() {
+        for signature in [&format!("sha256={SIGNATURE}"), "not hex", &SIGNATURE[1..], ""] {
+            assert!(verify_hmac_sha256(SECRET, BODY, signature).is_err(), "{signature}");
+        }
+        let error = verify_hmac_sha256(SECRET, BODY, "zz").unwrap_err();
+        assert!(matches!(&error, SourceError::Unauthorized(detail) if detail == "malformed signature"));
+    }
+
+    #[test]
+    fn token_is_compared_as_is() {
+        assert!(verify_token("secret", "secret").is_ok());
+        assert!(verify_token("secret", "Secret").is_err());
+        assert!(verify_token("secret", "secret ").is_err());
+        assert!(verify_token("secret", "").is_err());
+    }
+
+    #[test]
+    fn bearer_token_is_verified() {
+        let request = TestRequest::default().insert_header(("Authorization", "Bearer secret")).to_http_request();
+        assert!(verify_bearer(&request, "secret").is_ok());
+        assert!(verify_bearer(&request, "other").is_err());
+        let request = TestRequest::default().insert_header(("Authorization", "secret")).to_http_request();
+        assert!(verify_bearer(&request, "secret").is_err());
+        assert!(verify_bearer(&TestRequest::default().to_http_request(), "secret").is_err());
+    }
+}
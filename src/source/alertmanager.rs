@@ -0,0 +1,70 @@
+use actix_web::HttpRequest;
+use serde::Deserialize;
+use crate::payload::alertmanager::AlertmanagerPayload;
+use crate::source::{verify_bearer, SourceError};
+
+/// `http_config.authorization` of the receiver sends the token as `Authorization: Bearer <token>`.
+/// for more information, see https://prometheus.io/docs/alerting/latest/configuration/#http_config
+#[derive(Deserialize, Clone)]
+pub(crate) struct AlertmanagerSource {
+    /// requests are not verified when omitted
+    #[serde(default)]
+    token: Option<String>,
+}
+
+impl AlertmanagerSource {
+    pub(crate) fn parse(&self, request: &HttpRequest, raw: serde_json::Value) -> Result<AlertmanagerPayload, SourceError> {
+        if let Some(token) = &self.token {
+            verify_bearer(request, token)?;
+        }
+        AlertmanagerPayload::deserialize(raw).map_err(|e| SourceError::Invalid(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use serde_json::json;
+    use super::*;
+
+    fn raw() -> serde_json::Value {
+        json!({
+            "version": "4",
+            "status": "firing",
+            "receiver": "webhook",
+            "externalURL": "http://alertmanager:9093",
+            "alerts": [{"status": "firing", "labels": {"alertname": "InstanceDown"}, "startsAt": "2026-10-19T00:00:00Z"}],
+        })
+    }
+
+    fn parse(token: Option<&str>, authorization: Option<&str>) -> Result<AlertmanagerPayload, SourceError> {
+        let mut request = TestRequest::default();
+        if let Some(authorization) = authorization {
+            request = request.insert_header(("Authorization", authorization));
+        }
+        AlertmanagerSource { token: token.map(str::to_string) }.parse(&request.to_http_request(), raw())
+    }
+
+    #[test]
+    fn bearer_token_is_accepted() {
+        assert!(parse(Some("s3cret"), Some("Bearer s3cret")).is_ok());
+    }
+
+    #[test]
+    fn wrong_token_is_rejected() {
+        for authorization in ["Bearer other", "Bearer s3cret2", "Bearer ", "Basic czNjcmV0", "s3cret"] {
+            assert!(matches!(parse(Some("s3cret"), Some(authorization)), Err(SourceError::Unauthorized(_))), "{authorization}");
+        }
+    }
+
+    #[test]
+    fn missing_header_is_rejected() {
+        assert!(matches!(parse(Some("s3cret"), None), Err(SourceError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn token_is_optional() {
+        assert!(parse(None, None).is_ok());
+        assert!(parse(None, Some("Bearer anything")).is_ok());
+    }
+}
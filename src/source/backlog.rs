@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+/// Backlog webhooks can be neither signed nor given extra headers, so nothing is verified.
+/// for more information, see https://support-ja.backlog.com/hc/ja/articles/360035645534
+#[derive(Deserialize, Clone)]
+pub(crate) struct BacklogSource {
+    /// e.g. `https://example.backlog.jp`. links to the issues and wiki pages on it when set
+    #[serde(default)]
+    pub(crate) space_url: Option<String>,
+}
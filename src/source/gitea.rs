@@ -0,0 +1,145 @@
+use actix_web::HttpRequest;
+use serde::Deserialize;
+use crate::payload::github::GitHubEvent;
+use crate::render::summary::Service;
+use crate::source::{header, verify_hmac_sha256, SourceError};
+
+const GITEA: Service = Service {
+    name: "Gitea",
+    avatar_url: "https://about.gitea.com/gitea-text.svg",
+};
+
+const FORGEJO: Service = Service {
+    name: "Forgejo",
+    avatar_url: "https://forgejo.org/favicon.png",
+};
+
+/// the payload is almost the same as GitHub's. The signature is sent without the `sha256=` prefix.
+/// for more information, see https://docs.gitea.com/usage/webhooks
+#[derive(Deserialize, Clone)]
+pub(crate) struct GiteaSource {
+    /// secret of the webhook. requests are not verified when omitted
+    #[serde(default)]
+    secret: Option<String>,
+}
+
+/// Forgejo sends both `X-Gitea-*` and `X-Forgejo-*` headers
+#[derive(Clone, Copy)]
+pub(crate) enum Flavor {
+    Gitea,
+    Forgejo,
+}
+
+impl Flavor {
+    pub(crate) const fn source(self) -> &'static str {
+        match self {
+            Self::Gitea => "gitea",
+            Self::Forgejo => "forgejo",
+        }
+    }
+
+    pub(crate) const fn service(self) -> &'static Service {
+        match self {
+            Self::Gitea => &GITEA,
+            Self::Forgejo => &FORGEJO,
+        }
+    }
+}
+
+impl GiteaSource {
+    pub(crate) fn parse(&self, request: &HttpRequest, body: &[u8], raw: serde_json::Value) -> Result<(GitHubEvent, Flavor), SourceError> {
+        let flavor = if header(request, "X-Forgejo-Event").is_some() { Flavor::Forgejo } else { Flavor::Gitea };
+        if let Some(secret) = &self.secret {
+            let signature = header(request, "X-Gitea-Signature")
+                .or_else(|| header(request, "X-Forgejo-Signature"))
+                .ok_or_else(|| SourceError::Unauthorized("X-Gitea-Signature header must be included".to_string()))?;
+            verify_hmac_sha256(secret, body, signature)?;
+        }
+        let name = header(request, "X-Gitea-Event")
+            .or_else(|| header(request, "X-Forgejo-Event"))
+            .ok_or_else(|| SourceError::Invalid("X-Gitea-Event header must be included".to_string()))?;
+        let event = GitHubEvent::parse(name, raw).map_err(|e| SourceError::Invalid(e.to_string()))?;
+        Ok((event, flavor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use hmac::{Hmac, Mac};
+    use serde_json::json;
+    use super::*;
+
+    const SECRET: &str = "gitea-secret";
+
+    fn source() -> GiteaSource {
+        GiteaSource { secret: Some(SECRET.to_string()) }
+    }
+
+    /// captured from Gitea, trimmed
+    fn body() -> Vec<u8> {
+        serde_json::to_vec(&json!({
+            "ref": "refs/heads/main",
+            "before": "28e1879d029cb852e4844d9c718537df08844e03",
+            "after": "bffeb74224043ba2feb48d137756c8a9331c449a",
+            "compare_url": "http://localhost:3000/gitea/webhooks/compare/28e1879d029cb852e4844d9c718537df08844e03...bffeb74224043ba2feb48d137756c8a9331c449a",
+            "commits": [{
+                "id": "bffeb74224043ba2feb48d137756c8a9331c449a",
+                "message": "Webhooks Yay!",
+                "url": "http://localhost:3000/gitea/webhooks/commit/bffeb74224043ba2feb48d137756c8a9331c449a",
+                "author": {"name": "Gitea", "email": "someone@gitea.io", "username": "gitea"},
+            }],
+            "total_commits": 1,
+            "repository": {"id": 140, "full_name": "gitea/webhooks", "html_url": "http://localhost:3000/gitea/webhooks"},
+            "pusher": {"id": 1, "login": "gitea"},
+            "sender": {"id": 1, "login": "gitea", "avatar_url": "https://localhost:3000/avatars/1"},
+        })).unwrap()
+    }
+
+    fn sign(body: &[u8]) -> String {
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn parse(request: TestRequest, body: &[u8]) -> Result<(GitHubEvent, Flavor), SourceError> {
+        source().parse(&request.to_http_request(), body, serde_json::from_slice(body).unwrap())
+    }
+
+    #[test]
+    fn signed_request_is_accepted() {
+        let body = body();
+        let request = TestRequest::default()
+            .insert_header(("X-Gitea-Event", "push"))
+            .insert_header(("X-Gitea-Signature", sign(&body)));
+        let (event, flavor) = parse(request, &body).unwrap();
+        assert_eq!(event.name(), "push");
+        assert_eq!(flavor.source(), "gitea");
+        assert_eq!(event.to_event(flavor.source()).title, "[gitea/webhooks:main] 1 new commit");
+    }
+
+    #[test]
+    fn forgejo_headers_are_accepted() {
+        let body = body();
+        let request = TestRequest::default()
+            .insert_header(("X-Forgejo-Event", "push"))
+            .insert_header(("X-Forgejo-Signature", sign(&body)));
+        let (_, flavor) = parse(request, &body).unwrap();
+        assert_eq!(flavor.source(), "forgejo");
+    }
+
+    #[test]
+    fn bad_signature_is_rejected() {
+        let body = body();
+        let mut tampered = body.clone();
+        tampered.extend_from_slice(b" ");
+        for (signature, body) in [(sign(&body), &tampered), (format!("sha256={}", sign(&body)), &body), (String::new(), &body)] {
+            let request = TestRequest::default()
+                .insert_header(("X-Gitea-Event", "push"))
+                .insert_header(("X-Gitea-Signature", signature.clone()));
+            assert!(matches!(parse(request, body), Err(SourceError::Unauthorized(_))), "{signature}");
+        }
+        let request = TestRequest::default().insert_header(("X-Gitea-Event", "push"));
+        assert!(matches!(parse(request, &body), Err(SourceError::Unauthorized(_))));
+    }
+}
@@ -0,0 +1,108 @@
+use actix_web::HttpRequest;
+use serde::Deserialize;
+use crate::payload::github::GitHubEvent;
+use crate::source::{header, verify_hmac_sha256, SourceError};
+
+/// for more information, see https://docs.github.com/en/webhooks/using-webhooks/validating-webhook-deliveries
+#[derive(Deserialize, Clone)]
+pub(crate) struct GitHubSource {
+    /// secret of the webhook. requests are not verified when omitted
+    #[serde(default)]
+    secret: Option<String>,
+}
+
+impl GitHubSource {
+    pub(crate) fn parse(&self, request: &HttpRequest, body: &[u8], raw: serde_json::Value) -> Result<GitHubEvent, SourceError> {
+        if let Some(secret) = &self.secret {
+            let signature = header(request, "X-Hub-Signature-256")
+                .and_then(|signature| signature.strip_prefix("sha256="))
+                .ok_or_else(|| SourceError::Unauthorized("X-Hub-Signature-256 header must be included".to_string()))?;
+            verify_hmac_sha256(secret, body, signature)?;
+        }
+        let name = header(request, "X-GitHub-Event")
+            .ok_or_else(|| SourceError::Invalid("X-GitHub-Event header must be included".to_string()))?;
+        GitHubEvent::parse(name, raw).map_err(|e| SourceError::Invalid(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use serde_json::json;
+    use super::*;
+
+    const SECRET: &str = "It's a Secret to Everybody";
+
+    fn source() -> GitHubSource {
+        GitHubSource { secret: Some(SECRET.to_string()) }
+    }
+
+    fn body() -> Vec<u8> {
+        serde_json::to_vec(&json!({"zen": "Keep it logically awesome.", "sender": {"login": "octocat"}})).unwrap()
+    }
+
+    fn sign(body: &[u8]) -> String {
+        use hmac::{Hmac, Mac};
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    fn parse(source: &GitHubSource, signature: Option<&str>, body: &[u8]) -> Result<GitHubEvent, SourceError> {
+        let mut request = TestRequest::default().insert_header(("X-GitHub-Event", "ping"));
+        if let Some(signature) = signature {
+            request = request.insert_header(("X-Hub-Signature-256", signature));
+        }
+        source.parse(&request.to_http_request(), body, serde_json::from_slice(body).unwrap())
+    }
+
+    #[test]
+    fn signed_request_is_accepted() {
+        let body = body();
+        let event = parse(&source(), Some(&sign(&body)), &body).unwrap();
+        assert_eq!(event.name(), "ping");
+    }
+
+    #[test]
+    fn tampered_body_is_rejected() {
+        let body = body();
+        let signature = sign(&body);
+        let tampered = serde_json::to_vec(&json!({"zen": "Keep it logically awesome!", "sender": {"login": "octocat"}})).unwrap();
+        assert!(matches!(parse(&source(), Some(&signature), &tampered), Err(SourceError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn signature_without_prefix_is_rejected() {
+        let body = body();
+        let signature = sign(&body);
+        let bare = signature.strip_prefix("sha256=").unwrap();
+        assert!(matches!(parse(&source(), Some(bare), &body), Err(SourceError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn signature_with_bad_hex_is_rejected() {
+        let body = body();
+        let signature = format!("sha256={}", "g".repeat(64));
+        assert!(matches!(parse(&source(), Some(&signature), &body), Err(SourceError::Unauthorized(detail)) if detail == "malformed signature"));
+    }
+
+    #[test]
+    fn missing_signature_is_rejected() {
+        let body = body();
+        assert!(matches!(parse(&source(), None, &body), Err(SourceError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn unsigned_request_is_accepted_without_secret() {
+        let body = body();
+        assert!(parse(&GitHubSource { secret: None }, None, &body).is_ok());
+    }
+
+    #[test]
+    fn event_header_is_required() {
+        let body = body();
+        let request = TestRequest::default().insert_header(("X-Hub-Signature-256", sign(&body))).to_http_request();
+        let result = source().parse(&request, &body, serde_json::from_slice(&body).unwrap());
+        assert!(matches!(result, Err(SourceError::Invalid(_))));
+    }
+}
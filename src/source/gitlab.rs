@@ -0,0 +1,65 @@
+use actix_web::HttpRequest;
+use serde::Deserialize;
+use crate::payload::gitlab::GitLabEvent;
+use crate::source::{header, verify_token, SourceError};
+
+/// for more information, see https://docs.gitlab.com/ee/user/project/integrations/webhooks.html#validate-payloads-by-using-a-secret-token
+#[derive(Deserialize, Clone)]
+pub(crate) struct GitLabSource {
+    /// secret token of the webhook. requests are not verified when omitted
+    #[serde(default)]
+    token: Option<String>,
+}
+
+impl GitLabSource {
+    pub(crate) fn parse(&self, request: &HttpRequest, raw: serde_json::Value) -> Result<GitLabEvent, SourceError> {
+        if let Some(token) = &self.token {
+            let received = header(request, "X-Gitlab-Token")
+                .ok_or_else(|| SourceError::Unauthorized("X-Gitlab-Token header must be included".to_string()))?;
+            verify_token(token, received)?;
+        }
+        GitLabEvent::parse(raw).map_err(|e| SourceError::Invalid(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use serde_json::json;
+    use super::*;
+
+    fn source() -> GitLabSource {
+        GitLabSource { token: Some("secret-token".to_string()) }
+    }
+
+    fn parse(source: &GitLabSource, token: Option<&str>) -> Result<GitLabEvent, SourceError> {
+        let mut request = TestRequest::default().insert_header(("X-Gitlab-Event", "Pipeline Hook"));
+        if let Some(token) = token {
+            request = request.insert_header(("X-Gitlab-Token", token));
+        }
+        let raw = json!({
+            "object_kind": "pipeline",
+            "user": {"username": "root"},
+            "project": {"path_with_namespace": "gitlabhq/gitlab-test", "web_url": "http://example.com/gitlabhq/gitlab-test"},
+            "object_attributes": {"id": 31, "ref": "master", "sha": "bcbb5ec396a2c0f828686f14fac9b80b780504f2", "status": "success"},
+        });
+        source.parse(&request.to_http_request(), raw)
+    }
+
+    #[test]
+    fn valid_token_is_accepted() {
+        assert!(parse(&source(), Some("secret-token")).is_ok());
+    }
+
+    #[test]
+    fn invalid_token_is_rejected() {
+        assert!(matches!(parse(&source(), Some("secret-token2")), Err(SourceError::Unauthorized(_))));
+        assert!(matches!(parse(&source(), Some("")), Err(SourceError::Unauthorized(_))));
+        assert!(matches!(parse(&source(), None), Err(SourceError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn token_is_not_required_without_one() {
+        assert!(parse(&GitLabSource { token: None }, None).is_ok());
+    }
+}
@@ -0,0 +1,66 @@
+use actix_web::HttpRequest;
+use serde::Deserialize;
+use crate::payload::jira::JiraWebhook;
+use crate::source::{header, verify_hmac_sha256, SourceError};
+
+/// for more information, see https://developer.atlassian.com/cloud/jira/platform/webhooks/#secure-admin-webhooks
+#[derive(Deserialize, Clone)]
+pub(crate) struct JiraSource {
+    /// secret of the webhook. requests are not verified when omitted
+    #[serde(default)]
+    secret: Option<String>,
+}
+
+impl JiraSource {
+    pub(crate) fn parse(&self, request: &HttpRequest, body: &[u8], raw: serde_json::Value) -> Result<JiraWebhook, SourceError> {
+        if let Some(secret) = &self.secret {
+            let signature = header(request, "X-Hub-Signature")
+                .and_then(|signature| signature.strip_prefix("sha256="))
+                .ok_or_else(|| SourceError::Unauthorized("X-Hub-Signature header must be included".to_string()))?;
+            verify_hmac_sha256(secret, body, signature)?;
+        }
+        JiraWebhook::deserialize(raw).map_err(|e| SourceError::Invalid(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use super::*;
+
+    const SECRET: &str = "jira-secret";
+    const BODY: &str = r#"{"timestamp":1710232200000,"webhookEvent":"jira:issue_created","issue_event_type_name":"issue_created","user":{"accountId":"5b10ac8d82e05b22cc7d4ef5","displayName":"Alice Example","avatarUrls":{"48x48":"https://avatar-management.example.com/alice/48"}},"issue":{"id":"10001","self":"https://example.atlassian.net/rest/api/2/issue/10001","key":"PROJ-1","fields":{"summary":"Login fails on Safari","issuetype":{"name":"Bug"},"priority":{"name":"High"},"status":{"name":"To Do"},"project":{"key":"PROJ","name":"Project"},"labels":["frontend"]}}}"#;
+    /// HMAC-SHA256 of `BODY` with `SECRET`
+    const SIGNATURE: &str = "sha256=782849c28f266205a58da2e1c14f759ed23debf2003437f6bfcfe9231204ce1a";
+
+    fn parse(signature: Option<&str>, body: &str) -> Result<JiraWebhook, SourceError> {
+        let mut request = TestRequest::default();
+        if let Some(signature) = signature {
+            request = request.insert_header(("X-Hub-Signature", signature));
+        }
+        let source = JiraSource { secret: Some(SECRET.to_string()) };
+        source.parse(&request.to_http_request(), body.as_bytes(), serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn signed_request_is_accepted() {
+        let event = parse(Some(SIGNATURE), BODY).unwrap().to_event();
+        assert_eq!(event.title, "[PROJ-1] Created: Login fails on Safari");
+        assert_eq!(event.url.as_deref(), Some("https://example.atlassian.net/browse/PROJ-1"));
+    }
+
+    #[test]
+    fn tampered_body_is_rejected() {
+        let tampered = BODY.replace("\"High\"", "\"Low\"");
+        assert!(matches!(parse(Some(SIGNATURE), &tampered), Err(SourceError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn bad_signature_is_rejected() {
+        let bare = SIGNATURE.strip_prefix("sha256=").unwrap();
+        for signature in [bare, &format!("sha1={bare}"), &SIGNATURE.replace('8', "x"), "sha256="] {
+            assert!(matches!(parse(Some(signature), BODY), Err(SourceError::Unauthorized(_))), "{signature}");
+        }
+        assert!(matches!(parse(None, BODY), Err(SourceError::Unauthorized(_))));
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::Context;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+use crate::payload::event::NormalizedEvent;
+
+/// JSON from services without a dedicated conversion. Each field is extracted with [JSONPath](https://www.rfc-editor.org/rfc/rfc9535) and normalized.
+///
+/// e.g. `{"kind": "json", "name": "todoist", "event": "$.event_name", "title": "$.event_data.content"}`
+#[derive(Deserialize, Clone)]
+pub(crate) struct JsonSource {
+    /// `source` of the normalized event
+    name: String,
+    event: JsonPath,
+    /// defaults to the event name
+    #[serde(default)]
+    title: Option<JsonPath>,
+    #[serde(default)]
+    body: Option<JsonPath>,
+    #[serde(default)]
+    url: Option<JsonPath>,
+    #[serde(default)]
+    project: Option<JsonPath>,
+    /// every matched value becomes a label
+    #[serde(default)]
+    labels: Option<JsonPath>,
+    #[serde(default)]
+    initiator: Option<JsonPath>,
+}
+
+impl JsonSource {
+    pub(crate) fn extract(&'static self, raw: &Value) -> anyhow::Result<NormalizedEvent> {
+        let first = |path: &Option<JsonPath>| path.as_ref()
+            .and_then(|path| path.query(raw).first().and_then(stringify));
+        let event = stringify(self.event.query(raw).first().context("event name is missing")?)
+            .context("event name is null")?;
+
+        Ok(NormalizedEvent {
+            source: &self.name,
+            title: first(&self.title).unwrap_or_else(|| event.clone()),
+            event,
+            body: first(&self.body),
+            url: first(&self.url),
+            project: first(&self.project),
+            priority: None,
+            labels: self.labels.as_ref()
+                .map(|path| path.query(raw).all().into_iter().filter_map(stringify).collect())
+                .unwrap_or_default(),
+            initiator: first(&self.initiator),
+            received_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+/// strings as they are, arrays and objects as JSON
+fn stringify(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    /// `extract` borrows the name for the lifetime of the config
+    fn source(config: Value) -> &'static JsonSource {
+        Box::leak(Box::new(serde_json::from_value(config).unwrap()))
+    }
+
+    fn raw() -> Value {
+        json!({
+            "action": "deployed",
+            "deployment": {"id": 42, "environment": null, "meta": {"region": "ap-northeast-1"}},
+            "tags": [{"name": "prod"}, {"name": "web"}, {"name": null}, {"name": 3}],
+        })
+    }
+
+    #[test]
+    fn fields_are_extracted() {
+        let source = source(json!({
+            "name": "deployer",
+            "event": "$.action",
+            "title": "$.tags[*].name",
+            "labels": "$.tags[*].name",
+        }));
+        let event = source.extract(&raw()).unwrap();
+        assert_eq!((event.source, event.event.as_str()), ("deployer", "deployed"));
+        // the first of many matches
+        assert_eq!(event.title, "prod");
+        // every match, without null
+        assert_eq!(event.labels, ["prod", "web", "3"]);
+    }
+
+    #[test]
+    fn missing_paths_are_empty() {
+        let source = source(json!({ "name": "deployer", "event": "$.action", "body": "$.missing", "url": "$.deployment.missing.url", "labels": "$.missing[*]" }));
+        let event = source.extract(&raw()).unwrap();
+        // the title falls back to the event name
+        assert_eq!(event.title, "deployed");
+        assert_eq!((event.body, event.url), (None, None));
+        assert!(event.labels.is_empty());
+    }
+
+    #[test]
+    fn values_other_than_strings_are_json() {
+        let source = source(json!({
+            "name": "deployer",
+            "event": "$.deployment.id",
+            "title": "$.deployment.meta",
+            "project": "$.deployment.environment",
+        }));
+        let event = source.extract(&raw()).unwrap();
+        assert_eq!(event.event, "42");
+        assert_eq!(event.title, r#"{"region":"ap-northeast-1"}"#);
+        assert_eq!(event.project, None);
+    }
+
+    #[test]
+    fn event_name_is_required() {
+        let missing = source(json!({ "name": "deployer", "event": "$.missing" }));
+        assert_eq!(missing.extract(&raw()).err().unwrap().to_string(), "event name is missing");
+        let null = source(json!({ "name": "deployer", "event": "$.deployment.environment" }));
+        assert_eq!(null.extract(&raw()).err().unwrap().to_string(), "event name is null");
+    }
+
+    #[test]
+    fn invalid_path_is_rejected_on_load() {
+        assert!(serde_json::from_value::<JsonSource>(json!({ "name": "deployer", "event": "action" })).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use actix_web::HttpRequest;
+use serde::Deserialize;
+use crate::payload::linear::LinearWebhook;
+use crate::source::{header, verify_hmac_sha256, SourceError};
+
+/// even signed notifications are rejected as replays once some time has passed since they were sent
+const MAX_DELAY_MILLIS: i64 = 60_000;
+
+/// for more information, see https://developers.linear.app/docs/graphql/webhooks#securing-webhooks
+#[derive(Deserialize, Clone)]
+pub(crate) struct LinearSource {
+    /// signing secret of the webhook. requests are not verified when omitted
+    #[serde(default)]
+    secret: Option<String>,
+}
+
+impl LinearSource {
+    pub(crate) fn parse(&self, request: &HttpRequest, body: &[u8], raw: serde_json::Value) -> Result<LinearWebhook, SourceError> {
+        if let Some(secret) = &self.secret {
+            let signature = header(request, "Linear-Signature")
+                .ok_or_else(|| SourceError::Unauthorized("Linear-Signature header must be included".to_string()))?;
+            verify_hmac_sha256(secret, body, signature)?;
+            let sent_at = raw.get("webhookTimestamp").and_then(serde_json::Value::as_i64)
+                .ok_or_else(|| SourceError::Unauthorized("webhookTimestamp must be included".to_string()))?;
+            if (chrono::Utc::now().timestamp_millis() - sent_at).abs() > MAX_DELAY_MILLIS {
+                return Err(SourceError::Unauthorized("webhookTimestamp is too far from now".to_string()))
+            }
+        }
+        LinearWebhook::deserialize(raw).map_err(|e| SourceError::Invalid(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use hmac::{Hmac, Mac};
+    use serde_json::Value;
+    use super::*;
+
+    const SECRET: &str = "lin_wh_secret";
+    /// sent at 2024-03-12T08:30:00Z
+    const BODY: &str = r#"{"action":"create","type":"Issue","createdAt":"2024-03-12T08:30:00.000Z","data":{"id":"2174add1-f7c8-44e3-bbf3-2d60b5ea8bc9","identifier":"ENG-123","title":"Crash on startup","team":{"key":"ENG"},"labels":[{"name":"bug"}]},"actor":{"id":"e5a3c1b2","name":"Alice Example"},"url":"https://linear.app/example/issue/ENG-123/crash-on-startup","webhookTimestamp":1710232200000}"#;
+    /// HMAC-SHA256 of `BODY` with `SECRET`
+    const SIGNATURE: &str = "3e521a314e0cde6b440082f75d4661775012b19dbaa1529df46665a2db3ce70a";
+
+    fn parse(signature: Option<&str>, body: &str) -> Result<LinearWebhook, SourceError> {
+        let mut request = TestRequest::default().insert_header(("Linear-Event", "Issue"));
+        if let Some(signature) = signature {
+            request = request.insert_header(("Linear-Signature", signature));
+        }
+        let source = LinearSource { secret: Some(SECRET.to_string()) };
+        source.parse(&request.to_http_request(), body.as_bytes(), serde_json::from_str(body).unwrap())
+    }
+
+    fn sign(body: &str) -> String {
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(body.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    /// `BODY` sent `delay` milliseconds ago, and its signature
+    fn signed(delay: i64) -> (String, String) {
+        let mut raw: Value = serde_json::from_str(BODY).unwrap();
+        raw["webhookTimestamp"] = Value::from(chrono::Utc::now().timestamp_millis() - delay);
+        let body = raw.to_string();
+        let signature = sign(&body);
+        (body, signature)
+    }
+
+    #[test]
+    fn signature_is_verified_before_the_timestamp() {
+        // the vector is valid, but too old by now
+        let result = parse(Some(SIGNATURE), BODY);
+        assert!(matches!(result, Err(SourceError::Unauthorized(detail)) if detail == "webhookTimestamp is too far from now"));
+    }
+
+    #[test]
+    fn fresh_request_is_accepted() {
+        let (body, signature) = signed(1_000);
+        let event = parse(Some(&signature), &body).unwrap().to_event();
+        assert_eq!(event.title, "[ENG-123] Issue created: Crash on startup");
+        assert_eq!(event.project.as_deref(), Some("ENG"));
+    }
+
+    #[test]
+    fn replayed_request_is_rejected() {
+        for delay in [MAX_DELAY_MILLIS + 5_000, -(MAX_DELAY_MILLIS + 5_000)] {
+            let (body, signature) = signed(delay);
+            assert!(matches!(parse(Some(&signature), &body), Err(SourceError::Unauthorized(_))), "{delay}");
+        }
+    }
+
+    #[test]
+    fn timestamp_is_required() {
+        let mut raw: Value = serde_json::from_str(BODY).unwrap();
+        raw.as_object_mut().unwrap().remove("webhookTimestamp");
+        let body = raw.to_string();
+        assert!(matches!(parse(Some(&sign(&body)), &body), Err(SourceError::Unauthorized(detail)) if detail == "webhookTimestamp must be included"));
+    }
+
+    #[test]
+    fn bad_signature_is_rejected() {
+        let (body, signature) = signed(0);
+        let tampered = body.replace("Crash on startup", "Crash on shutdown");
+        assert!(matches!(parse(Some(&signature), &tampered), Err(SourceError::Unauthorized(detail)) if detail == "signature mismatch"));
+        for signature in [&signature[..10], &format!("sha256={signature}"), ""] {
+            assert!(matches!(parse(Some(signature), &body), Err(SourceError::Unauthorized(_))), "{signature}");
+        }
+        assert!(matches!(parse(None, &body), Err(SourceError::Unauthorized(_))));
+    }
+}
@@ -0,0 +1,76 @@
+use actix_web::HttpRequest;
+use serde::Deserialize;
+use crate::payload::sentry::SentryWebhook;
+use crate::source::{header, verify_hmac_sha256, SourceError};
+
+/// webhook of an integration.
+/// for more information, see https://docs.sentry.io/organization/integrations/integration-platform/webhooks/#verifying-the-signature
+#[derive(Deserialize, Clone)]
+pub(crate) struct SentrySource {
+    /// client secret of the integration. requests are not verified when omitted
+    #[serde(default)]
+    secret: Option<String>,
+}
+
+impl SentrySource {
+    pub(crate) fn parse(&self, request: &HttpRequest, body: &[u8], raw: serde_json::Value) -> Result<SentryWebhook, SourceError> {
+        if let Some(secret) = &self.secret {
+            let signature = header(request, "Sentry-Hook-Signature")
+                .ok_or_else(|| SourceError::Unauthorized("Sentry-Hook-Signature header must be included".to_string()))?;
+            verify_hmac_sha256(secret, body, signature)?;
+        }
+        let resource = header(request, "Sentry-Hook-Resource")
+            .ok_or_else(|| SourceError::Invalid("Sentry-Hook-Resource header must be included".to_string()))?;
+        SentryWebhook::parse(resource, raw).map_err(|e| SourceError::Invalid(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use super::*;
+
+    const SECRET: &str = "sentry-client-secret";
+    const BODY: &str = r#"{"action":"created","installation":{"uuid":"a8e5d37a-696c-4c54-adb5-b3f28d64c7de"},"data":{"issue":{"id":"1170820242","shortId":"BACKEND-4","title":"ZeroDivisionError: division by zero","culprit":"billing.invoice in total","level":"error","web_url":"https://sentry.io/organizations/example/issues/1170820242/","project":{"slug":"backend"},"count":"1"}},"actor":{"type":"application","id":"sentry","name":"Sentry"}}"#;
+    /// HMAC-SHA256 of `BODY` with `SECRET`
+    const SIGNATURE: &str = "05cf2b9e9cd07e0408a2e59c409ef7c0fc93c5b1ccb378ec1c5834987a80542c";
+
+    fn source() -> SentrySource {
+        SentrySource { secret: Some(SECRET.to_string()) }
+    }
+
+    fn parse(signature: Option<&str>, body: &str) -> Result<SentryWebhook, SourceError> {
+        let mut request = TestRequest::default().insert_header(("Sentry-Hook-Resource", "issue"));
+        if let Some(signature) = signature {
+            request = request.insert_header(("Sentry-Hook-Signature", signature));
+        }
+        source().parse(&request.to_http_request(), body.as_bytes(), serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn signed_request_is_accepted() {
+        let webhook = parse(Some(SIGNATURE), BODY).unwrap();
+        assert_eq!(webhook.to_event().title, "[BACKEND-4] Issue created: ZeroDivisionError: division by zero");
+    }
+
+    #[test]
+    fn tampered_body_is_rejected() {
+        let tampered = BODY.replace("\"level\":\"error\"", "\"level\":\"info\"");
+        assert!(matches!(parse(Some(SIGNATURE), &tampered), Err(SourceError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn bad_signature_is_rejected() {
+        for signature in [&SIGNATURE[..62], &format!("sha256={SIGNATURE}"), &SIGNATURE.replace('0', "1"), ""] {
+            assert!(matches!(parse(Some(signature), BODY), Err(SourceError::Unauthorized(_))), "{signature}");
+        }
+        assert!(matches!(parse(None, BODY), Err(SourceError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn resource_header_is_required() {
+        let request = TestRequest::default().insert_header(("Sentry-Hook-Signature", SIGNATURE)).to_http_request();
+        let result = source().parse(&request, BODY.as_bytes(), serde_json::from_str(BODY).unwrap());
+        assert!(matches!(result, Err(SourceError::Invalid(_))));
+    }
+}
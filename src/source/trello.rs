@@ -0,0 +1,97 @@
+use actix_web::HttpRequest;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha1::Sha1;
+use crate::payload::trello::TrelloWebhook;
+use crate::source::{header, SourceError};
+
+/// `X-Trello-Webhook` is the Base64 of the HMAC-SHA1 of the body followed by the callback URL.
+/// for more information, see https://developer.atlassian.com/cloud/trello/guides/rest-api/webhooks/#webhook-signatures
+#[derive(Deserialize, Clone)]
+pub(crate) struct TrelloSource {
+    /// secret of the application. requests are not verified when omitted
+    #[serde(default)]
+    secret: Option<String>,
+    /// `callbackURL` given when the webhook was created, including its query string.
+    /// taken from the request when omitted, which is wrong behind a reverse proxy
+    #[serde(default)]
+    callback_url: Option<String>,
+}
+
+impl TrelloSource {
+    pub(crate) fn parse(&self, request: &HttpRequest, body: &[u8], raw: serde_json::Value) -> Result<TrelloWebhook, SourceError> {
+        if let Some(secret) = &self.secret {
+            let signature = header(request, "X-Trello-Webhook")
+                .ok_or_else(|| SourceError::Unauthorized("X-Trello-Webhook header must be included".to_string()))?;
+            let signature = STANDARD.decode(signature).map_err(|_| SourceError::Unauthorized("malformed signature".to_string()))?;
+            let callback_url = self.callback_url.clone().unwrap_or_else(|| {
+                let connection = request.connection_info();
+                let path = request.uri().path_and_query().map_or_else(|| request.path(), |path| path.as_str());
+                format!("{}://{}{path}", connection.scheme(), connection.host())
+            });
+            let mut mac = Hmac::<Sha1>::new_from_slice(secret.as_bytes()).expect("HMAC accepts a key of any length");
+            mac.update(body);
+            mac.update(callback_url.as_bytes());
+            mac.verify_slice(&signature).map_err(|_| SourceError::Unauthorized("signature mismatch".to_string()))?;
+        }
+        TrelloWebhook::deserialize(raw).map_err(|e| SourceError::Invalid(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use super::*;
+
+    const SECRET: &str = "trello-app-secret";
+    const CALLBACK_URL: &str = "https://hooks.example.com/api/from/trello/to/discord";
+    const BODY: &str = r#"{"model":{"id":"5a1b2c3d4e5f6a7b8c9d0e1f","name":"Roadmap"},"action":{"id":"65f0a1b2c3d4e5f6a7b8c9d0","type":"createCard","date":"2024-03-12T08:30:00.000Z","memberCreator":{"id":"5a00000000000000000000aa","fullName":"Alice Example","username":"alice","avatarUrl":"https://trello-members.s3.amazonaws.com/5a00000000000000000000aa/abc123"},"data":{"card":{"id":"65f0a1b2c3d4e5f6a7b8c9d1","name":"Write release notes","idShort":42,"shortLink":"AbCdEfGh"},"list":{"id":"5a1b2c3d4e5f6a7b8c9d0e20","name":"To Do"},"board":{"id":"5a1b2c3d4e5f6a7b8c9d0e1f","name":"Roadmap","shortLink":"XyZ12345"}}}}"#;
+    /// Base64 of HMAC-SHA1 of `BODY` followed by `CALLBACK_URL`
+    const SIGNATURE: &str = "z4iD4qBIyv4J8lqFN61NzjGhb7Y=";
+
+    fn source(callback_url: Option<&str>) -> TrelloSource {
+        TrelloSource { secret: Some(SECRET.to_string()), callback_url: callback_url.map(str::to_string) }
+    }
+
+    fn parse(source: &TrelloSource, request: TestRequest, body: &str) -> Result<TrelloWebhook, SourceError> {
+        source.parse(&request.to_http_request(), body.as_bytes(), serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn signed_request_is_accepted() {
+        let request = TestRequest::default().insert_header(("X-Trello-Webhook", SIGNATURE));
+        let webhook = parse(&source(Some(CALLBACK_URL)), request, BODY).unwrap();
+        assert_eq!(webhook.to_event().title, "[Roadmap] Card created: Write release notes");
+    }
+
+    #[test]
+    fn callback_url_is_part_of_the_signature() {
+        let request = TestRequest::default().insert_header(("X-Trello-Webhook", SIGNATURE));
+        let result = parse(&source(Some(&format!("{CALLBACK_URL}/"))), request, BODY);
+        assert!(matches!(result, Err(SourceError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn bad_signature_is_rejected() {
+        let tampered = BODY.replace("Write release notes", "Write release notes!");
+        let request = TestRequest::default().insert_header(("X-Trello-Webhook", SIGNATURE));
+        assert!(matches!(parse(&source(Some(CALLBACK_URL)), request, &tampered), Err(SourceError::Unauthorized(_))));
+        for signature in ["not base64!", "z4iD4qBIyv4J8lqFN61NzjGhb7Y", ""] {
+            let request = TestRequest::default().insert_header(("X-Trello-Webhook", signature));
+            assert!(matches!(parse(&source(Some(CALLBACK_URL)), request, BODY), Err(SourceError::Unauthorized(_))), "{signature}");
+        }
+        assert!(matches!(parse(&source(Some(CALLBACK_URL)), TestRequest::default(), BODY), Err(SourceError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn callback_url_is_taken_from_the_request_with_its_query() {
+        // signed for `http://localhost:8080/api/from/trello/to/discord?route=roadmap`
+        let request = TestRequest::default()
+            .uri("/api/from/trello/to/discord?route=roadmap")
+            .insert_header(("Host", "localhost:8080"))
+            .insert_header(("X-Trello-Webhook", "ID3J+e9HNjFnufrq6oLImoE81k0="));
+        assert!(parse(&source(None), request, BODY).is_ok());
+    }
+}
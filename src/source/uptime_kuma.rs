@@ -0,0 +1,59 @@
+use actix_web::HttpRequest;
+use serde::Deserialize;
+use crate::payload::uptime_kuma::UptimeKumaPayload;
+use crate::source::{verify_bearer, SourceError};
+
+/// supports the "Webhook" notification sent as "application/json" or "multipart/form-data".
+/// Uptime Kuma itself does not sign, so set `Authorization` in "Additional Headers" to check it.
+#[derive(Deserialize, Clone)]
+pub(crate) struct UptimeKumaSource {
+    /// e.g. `https://status.example.com`. links to the monitors on it when set
+    #[serde(default)]
+    pub(crate) base_url: Option<String>,
+    /// compared with `Authorization: Bearer <token>`. requests are not verified when omitted
+    #[serde(default)]
+    token: Option<String>,
+}
+
+impl UptimeKumaSource {
+    pub(crate) fn parse(&self, request: &HttpRequest, raw: serde_json::Value) -> Result<UptimeKumaPayload, SourceError> {
+        if let Some(token) = &self.token {
+            verify_bearer(request, token)?;
+        }
+        let invalid = |e: serde_json::Error| SourceError::Invalid(e.to_string());
+        // multipart/form-data puts the whole JSON in the `data` part as text
+        if let Some(data) = raw.get("data").and_then(serde_json::Value::as_str) {
+            return serde_json::from_str(data).map_err(invalid)
+        }
+        UptimeKumaPayload::deserialize(raw).map_err(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use serde_json::json;
+    use super::*;
+
+    fn source(token: Option<&str>) -> UptimeKumaSource {
+        UptimeKumaSource { base_url: None, token: token.map(str::to_string) }
+    }
+
+    #[test]
+    fn multipart_data_part_is_read_as_json() {
+        let request = TestRequest::default().to_http_request();
+        let data = json!({ "heartbeat": null, "monitor": null, "msg": "Uptime Kuma Webhook Test" }).to_string();
+        let payload = source(None).parse(&request, json!({ "data": data })).unwrap();
+        assert_eq!(payload.msg, "Uptime Kuma Webhook Test");
+    }
+
+    #[test]
+    fn token_is_verified() {
+        let raw = json!({ "msg": "Uptime Kuma Webhook Test" });
+        let request = TestRequest::default().insert_header(("Authorization", "Bearer s3cret")).to_http_request();
+        assert!(source(Some("s3cret")).parse(&request, raw.clone()).is_ok());
+        assert!(matches!(source(Some("other")).parse(&request, raw.clone()), Err(SourceError::Unauthorized(_))));
+        let request = TestRequest::default().to_http_request();
+        assert!(matches!(source(Some("s3cret")).parse(&request, raw), Err(SourceError::Unauthorized(_))));
+    }
+}